@@ -0,0 +1,132 @@
+import { useEffect, useState } from 'react';
+import { toast } from 'sonner';
+
+import { repo } from '../lib/repo';
+import {
+  type ApiToken,
+  type TokenScope,
+  tokenScopes,
+} from '../model/ApiToken';
+import { DeleteButton } from './DeleteButton';
+import { Spinner } from './Spinner';
+
+export const ApiTokens = () => {
+  const [tokens, setTokens] = useState<ApiToken[] | undefined>(undefined);
+  const [name, setName] = useState('');
+  const [scopes, setScopes] = useState<TokenScope[]>([]);
+  const [created, setCreated] = useState<string | undefined>(undefined);
+
+  useEffect(() => {
+    repo
+      .getApiTokens()
+      .then(setTokens)
+      .catch(() =>
+        toast.error(
+          'An error occurred while fetching your API tokens, please try again later.'
+        )
+      );
+  }, []);
+
+  const toggleScope = (scope: TokenScope) =>
+    setScopes(
+      scopes.includes(scope)
+        ? scopes.filter((s) => s !== scope)
+        : [...scopes, scope]
+    );
+
+  const addToken = async () => {
+    try {
+      const { token, ...apiToken } = await repo.addApiToken(name, scopes);
+      setTokens([...(tokens ?? []), apiToken]);
+      setCreated(token);
+      setName('');
+      setScopes([]);
+    } catch (err) {
+      toast.error(
+        'An error occurred while creating your API token, please try again later.'
+      );
+    }
+  };
+
+  const deleteToken = async (id: string) => {
+    try {
+      await repo.deleteApiToken(id);
+      setTokens(tokens?.filter((token) => token.id !== id));
+      toast.success('API token revoked successfully.');
+    } catch (err) {
+      toast.error(
+        'An error occurred while revoking your API token, please try again later.'
+      );
+    }
+  };
+
+  if (tokens === undefined)
+    return (
+      <div className='mt-2 text-center'>
+        <Spinner />
+      </div>
+    );
+
+  return (
+    <div className='m-4 flex flex-col gap-4'>
+      {created && (
+        <div className='rounded-lg bg-white p-4 text-sm text-gray-700 dark:bg-neutral-800 dark:text-gray-300'>
+          Copy your new token now, it won't be shown again:
+          <code className='mt-2 block break-all'>{created}</code>
+        </div>
+      )}
+      {tokens.map((token) => (
+        <div
+          key={token.id}
+          className='flex items-center rounded-lg bg-white p-4 dark:bg-neutral-800'
+        >
+          <div className='flex flex-col gap-y-1'>
+            <p className='font-semibold text-gray-800 dark:text-gray-200'>
+              {token.name}
+            </p>
+            <p className='text-sm text-gray-600 dark:text-gray-400'>
+              {token.scopes.join(', ')} · {token.rateLimit} requests/minute
+            </p>
+          </div>
+          <DeleteButton
+            title='Revoke Token'
+            className='ml-auto'
+            text={`Are you sure you want to revoke ${token.name}? Anything using it will stop working.`}
+            onConfirm={() => deleteToken(token.id)}
+            size={20}
+          />
+        </div>
+      ))}
+      <div className='flex flex-col gap-3 rounded-lg bg-white p-4 dark:bg-neutral-800'>
+        <input
+          className='rounded-md border p-2 text-sm dark:bg-neutral-700 dark:text-gray-200'
+          placeholder='Token name'
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+        />
+        <div className='flex flex-wrap gap-3'>
+          {tokenScopes.map((scope) => (
+            <label
+              key={scope}
+              className='flex items-center gap-1 text-sm text-gray-700 dark:text-gray-300'
+            >
+              <input
+                type='checkbox'
+                checked={scopes.includes(scope)}
+                onChange={() => toggleScope(scope)}
+              />
+              {scope}
+            </label>
+          ))}
+        </div>
+        <button
+          className='ml-auto rounded-md bg-red-600 px-3 py-1 text-sm text-white disabled:opacity-50'
+          disabled={!name.trim() || !scopes.length}
+          onClick={addToken}
+        >
+          Create token
+        </button>
+      </div>
+    </div>
+  );
+};
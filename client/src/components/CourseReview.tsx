@@ -82,7 +82,7 @@ const ReviewInteractions = ({
 
   const refreshInteractions = async () => {
     try {
-      const payload = await repo.getInteractions(courseId, userId, user?.id);
+      const payload = await repo.getInteractions(courseId, userId);
       setKind(payload.kind);
     } catch (err: any) {
       toast.error(err.toString());
@@ -91,7 +91,7 @@ const ReviewInteractions = ({
 
   const addInteraction = async (interactionKind: InteractionKind) => {
     try {
-      await repo.addInteraction(interactionKind, courseId, userId, user?.id);
+      await repo.addInteraction(interactionKind, courseId, userId);
       const change = getLikeChange(kind, interactionKind);
       updateLikes(review.likes + change);
 
@@ -110,7 +110,7 @@ const ReviewInteractions = ({
 
   const removeInteraction = async () => {
     try {
-      await repo.removeInteraction(courseId, userId, user?.id);
+      await repo.removeInteraction(courseId, userId);
       if (!kind) {
         throw new Error("Can't remove interaction that doesn't exist.");
       }
@@ -134,7 +134,11 @@ const ReviewInteractions = ({
     setTimeout(() => setPromptLogin(false), 3000);
   };
 
+  const isOwnReview = user?.pseudonym === userId;
+
   const handleLike = () => {
+    if (isOwnReview) return toast.error("You can't vote on your own review.");
+
     user
       ? kind === 'like'
         ? removeInteraction()
@@ -143,6 +147,8 @@ const ReviewInteractions = ({
   };
 
   const handleDislike = () => {
+    if (isOwnReview) return toast.error("You can't vote on your own review.");
+
     user
       ? kind === 'dislike'
         ? removeInteraction()
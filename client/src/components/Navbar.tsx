@@ -45,6 +45,19 @@ export const Navbar = () => {
       .getNotifications()
       .then((data) => setNotifications(data))
       .catch(() => toast.error('Failed to get notifications.'));
+
+    const source = repo.streamNotifications();
+
+    source.addEventListener('notification', (event) => {
+      const notification = JSON.parse(event.data) as Notification;
+
+      setNotifications((notifications) => [
+        notification,
+        ...notifications.filter((n) => n._id.$oid !== notification._id.$oid),
+      ]);
+    });
+
+    return () => source.close();
   }, []);
 
   const handleInputChange = (query: string) => {
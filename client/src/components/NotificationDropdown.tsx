@@ -11,6 +11,27 @@ import { courseIdToUrlParam, spliceCourseCode } from '../lib/utils';
 import type { Notification } from '../model/Notification';
 import { CourseReview } from './CourseReview';
 
+const describe = (notification: Notification) => {
+  switch (notification.type) {
+    case 'courseChange':
+      return `The course catalog updated this course's ${notification.payload.changes
+        .map((change) => change.field)
+        .join(', ')}.`;
+    case 'moderation':
+      return notification.payload.outcome === 'approved'
+        ? 'Your review was approved by a moderator.'
+        : `Your review was removed by a moderator${
+            notification.payload.reason
+              ? `: ${notification.payload.reason}`
+              : '.'
+          }`;
+    case 'review':
+      return notification.payload.content;
+    case 'reviewReply':
+      return `Someone replied to your review: ${notification.payload.content}`;
+  }
+};
+
 export const NotificationDropdown = ({
   notifications,
   setNotifications,
@@ -56,7 +77,7 @@ export const NotificationDropdown = ({
 
     setNotifications(
       notifications.map((n) => {
-        return seen.has(n.review.courseId) ? { ...n, seen: true } : n;
+        return seen.has(n._id.$oid) ? { ...n, seen: true } : n;
       })
     );
 
@@ -67,24 +88,27 @@ export const NotificationDropdown = ({
     if (notification.seen) return;
 
     try {
-      await repo.updateNotification(
-        notification.review.courseId,
-        notification.review.userId,
-        true
-      );
-      seen.add(notification.review.courseId);
+      await repo.updateNotification(notification._id.$oid, true);
+      seen.add(notification._id.$oid);
     } catch (err) {
       toast.error('Failed to update notification.');
     }
   };
 
-  const deleteNotification = async (courseId: string) => {
+  const markAllSeen = async () => {
+    try {
+      await repo.markAllNotificationsSeen();
+      setNotifications(notifications.map((n) => ({ ...n, seen: true })));
+    } catch (err) {
+      toast.error('Failed to update notifications.');
+    }
+  };
+
+  const deleteNotification = async (id: string) => {
     try {
-      await repo.deleteNotification(courseId);
+      await repo.deleteNotification(id);
       setNotifications(
-        notifications.filter(
-          (notification) => notification.review.courseId !== courseId
-        )
+        notifications.filter((notification) => notification._id.$oid !== id)
       );
       toast.success('Successfully deleted notification.');
     } catch (err) {
@@ -127,6 +151,14 @@ export const NotificationDropdown = ({
               >
                 <Menu.Items className='autocomplete absolute -right-8 z-30 mt-2 max-h-[800px] max-w-[325px] origin-top-right divide-y divide-gray-100 overflow-auto rounded-md bg-slate-100 shadow-lg dark:bg-neutral-900 md:max-w-[800px]'>
                   <div className='p-2'>
+                    {notifications.some((n) => !n.seen) && (
+                      <button
+                        className='m-2 text-sm font-medium text-gray-600 underline hover:text-gray-900 dark:text-gray-300 dark:hover:text-gray-50'
+                        onClick={markAllSeen}
+                      >
+                        Mark all as read
+                      </button>
+                    )}
                     {notifications.length !== 0 ? (
                       notifications.map((notification, i) => (
                         <Menu.Item key={i}>
@@ -141,11 +173,11 @@ export const NotificationDropdown = ({
                                   <p className='font-semibold text-gray-800 dark:text-gray-200'>
                                     <Link
                                       to={`/course/${courseIdToUrlParam(
-                                        notification.review.courseId
+                                        notification.courseId
                                       )}`}
                                     >
                                       {spliceCourseCode(
-                                        notification.review.courseId,
+                                        notification.courseId,
                                         ' '
                                       )}
                                     </Link>
@@ -157,19 +189,25 @@ export const NotificationDropdown = ({
                                 <FaTrash
                                   onClick={async () =>
                                     await deleteNotification(
-                                      notification.review.courseId
+                                      notification._id.$oid
                                     )
                                   }
                                   className='ml-auto text-right text-gray-700 underline hover:text-gray-900 dark:text-gray-300 dark:hover:text-gray-50'
                                 />
                               </div>
-                              <CourseReview
-                                className='rounded-md'
-                                review={notification.review}
-                                canModify={false}
-                                handleDelete={() => undefined}
-                                openEditReview={() => undefined}
-                              />
+                              {notification.type === 'review' ? (
+                                <CourseReview
+                                  className='rounded-md'
+                                  review={notification.payload}
+                                  canModify={false}
+                                  handleDelete={() => undefined}
+                                  openEditReview={() => undefined}
+                                />
+                              ) : (
+                                <p className='rounded-md bg-slate-50 p-3 text-sm text-gray-700 dark:bg-neutral-800 dark:text-gray-300'>
+                                  {describe(notification)}
+                                </p>
+                              )}
                             </div>
                           )}
                         </Menu.Item>
@@ -1,3 +1,8 @@
+import type {
+  AddApiTokenPayload,
+  ApiToken,
+  TokenScope,
+} from '../model/ApiToken';
 import { GetCourseReviewsInteractionPayload } from '../model/GetCourseReviewsInteractionsPayload';
 import type { GetCourseWithReviewsPayload } from '../model/GetCourseWithReviewsPayload';
 import { GetCoursesPayload } from '../model/GetCoursesPayload';
@@ -5,12 +10,20 @@ import type { GetInstructorPayload } from '../model/GetInstructorPayload';
 import type { GetInteractionsPayload } from '../model/GetInteractionsPayload';
 import { GetReviewsPayload } from '../model/GetReviewsPayload';
 import type { InteractionKind } from '../model/Interaction';
-import type { Notification } from '../model/Notification';
+import type {
+  Notification,
+  NotificationPreferences,
+  NotificationType,
+} from '../model/Notification';
 import type { SearchResults } from '../model/SearchResults';
-import type { Subscription } from '../model/Subscription';
+import type { GetSessionsPayload } from '../model/Session';
+import type {
+  Subscription,
+  SubscriptionTarget,
+} from '../model/Subscription';
 import type { UserResponse } from '../model/User';
 
-const prefix = '/api';
+const prefix = '/api/v1';
 
 type Method = 'GET' | 'POST' | 'PUT' | 'DELETE';
 
@@ -81,6 +94,9 @@ const client = {
   },
 };
 
+const subscriptionTargetBody = (target: SubscriptionTarget) =>
+  'courseId' in target ? { course_id: target.courseId } : target;
+
 export const repo = {
   async getSubscription(courseId: string): Promise<Subscription | null> {
     return client.deserialize<Subscription | null>(
@@ -112,6 +128,20 @@ export const repo = {
     });
   },
 
+  async follow(target: SubscriptionTarget): Promise<Response> {
+    return client.post('/subscriptions', {
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(subscriptionTargetBody(target)),
+    });
+  },
+
+  async unfollow(target: SubscriptionTarget): Promise<Response> {
+    return client.delete('/subscriptions', {
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(subscriptionTargetBody(target)),
+    });
+  },
+
   async getReviews(params?: {
     courseId?: string;
     instructorName?: string;
@@ -164,30 +194,27 @@ export const repo = {
 
   async getInteractions(
     courseId: string,
-    userId: string,
-    referrer: string | undefined
+    userId: string
   ): Promise<GetInteractionsPayload> {
     return client.deserialize<GetInteractionsPayload>(
       'GET',
-      `/interactions?course_id=${courseId}&user_id=${userId}&referrer=${referrer}`
+      `/interactions?course_id=${courseId}&user_id=${userId}`
     );
   },
 
   async getUserInteractionsForCourse(
-    courseId: string,
-    referrer: string
+    courseId: string
   ): Promise<GetCourseReviewsInteractionPayload> {
     return client.deserialize<GetCourseReviewsInteractionPayload>(
       'GET',
-      `/interactions/${courseId}/referrer/${referrer}`
+      `/interactions/${courseId}`
     );
   },
 
   async addInteraction(
     kind: InteractionKind,
     courseId: string,
-    userId: string,
-    referrer: string | undefined
+    userId: string
   ): Promise<Response> {
     return client.post('/interactions', {
       headers: { 'Content-Type': 'application/json' },
@@ -195,49 +222,66 @@ export const repo = {
         kind,
         course_id: courseId,
         user_id: userId,
-        referrer,
       }),
     });
   },
 
   async removeInteraction(
     courseId: string,
-    userId: string,
-    referrer: string | undefined
+    userId: string
   ): Promise<Response> {
     return client.delete('/interactions', {
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({
         course_id: courseId,
         user_id: userId,
-        referrer,
       }),
     });
   },
 
-  async getNotifications(): Promise<Notification[]> {
-    return client.deserialize<Notification[]>('GET', '/notifications');
+  async getNotifications(
+    limit?: number,
+    offset?: number
+  ): Promise<Notification[]> {
+    return client.deserialize<Notification[]>(
+      'GET',
+      client.buildQuery('/notifications', { limit, offset })
+    );
+  },
+
+  streamNotifications(): EventSource {
+    return new EventSource(`${prefix}/notifications/stream`);
   },
 
-  async updateNotification(
-    courseId: string,
-    creatorId: string,
-    seen: boolean
-  ): Promise<Response> {
-    return client.put('/notifications', {
+  async updateNotification(id: string, seen: boolean): Promise<Response> {
+    return client.put(`/notifications/${id}`, {
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({
-        course_id: courseId,
-        creator_id: creatorId,
-        seen: seen,
-      }),
+      body: JSON.stringify({ seen }),
     });
   },
 
-  async deleteNotification(courseId: string): Promise<Response> {
-    return client.delete('/notifications', {
+  async deleteNotification(id: string): Promise<Response> {
+    return client.delete(`/notifications/${id}`);
+  },
+
+  async markAllNotificationsSeen(): Promise<Response> {
+    return client.post('/notifications/seen');
+  },
+
+  async getNotificationPreferences(): Promise<NotificationPreferences> {
+    return client.deserialize<NotificationPreferences>(
+      'GET',
+      '/notifications/preferences'
+    );
+  },
+
+  async updateNotificationPreferences(
+    mutedCourses: string[],
+    mutedTypes: NotificationType[]
+  ): Promise<Response> {
+    return client.put('/notifications/preferences', {
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ course_id: courseId }),
+      body: JSON.stringify({ mutedCourses, mutedTypes }),
     });
   },
 
@@ -264,18 +308,13 @@ export const repo = {
     filters?: any
   ): Promise<GetCoursesPayload> {
     return client.deserialize<GetCoursesPayload>(
-      'POST',
+      'GET',
       client.buildQuery(`/courses`, {
+        filter: filters && encodeURIComponent(JSON.stringify(filters)),
         limit,
         offset,
         with_course_count: withCourseCount,
-      }),
-      {
-        headers: {
-          'Content-Type': 'application/json',
-        },
-        body: JSON.stringify(filters),
-      }
+      })
     );
   },
 
@@ -291,4 +330,38 @@ export const repo = {
       credentials: 'include',
     });
   },
+
+  async deleteUser(): Promise<Response> {
+    return client.delete('/user');
+  },
+
+  async getSessions(): Promise<GetSessionsPayload> {
+    return client.deserialize<GetSessionsPayload>('GET', '/sessions');
+  },
+
+  async revokeSession(id: string): Promise<Response> {
+    return client.delete(`/sessions/${id}`);
+  },
+
+  async revokeOtherSessions(): Promise<Response> {
+    return client.delete('/sessions?others=true');
+  },
+
+  async getApiTokens(): Promise<ApiToken[]> {
+    return client.deserialize<ApiToken[]>('GET', '/tokens');
+  },
+
+  async addApiToken(
+    name: string,
+    scopes: TokenScope[]
+  ): Promise<AddApiTokenPayload> {
+    return client.deserialize<AddApiTokenPayload>('POST', '/tokens', {
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ name, scopes }),
+    });
+  },
+
+  async deleteApiToken(id: string): Promise<Response> {
+    return client.delete(`/tokens/${id}`);
+  },
 };
@@ -0,0 +1,32 @@
+type Timestamp = {
+  $date: {
+    $numberLong: string;
+  };
+};
+
+export const tokenScopes = [
+  'interactions:read',
+  'interactions:write',
+  'notifications:read',
+  'notifications:write',
+  'reviews:read',
+  'reviews:write',
+  'subscriptions:read',
+  'subscriptions:write',
+  'user:read',
+] as const;
+
+export type TokenScope = (typeof tokenScopes)[number];
+
+export type ApiToken = {
+  id: string;
+  createdAt: Timestamp;
+  lastUsedAt?: Timestamp;
+  name: string;
+  rateLimit: number;
+  scopes: TokenScope[];
+};
+
+export type AddApiTokenPayload = ApiToken & {
+  token: string;
+};
@@ -1,6 +1,7 @@
 import type { Instructor } from './Instructor';
 import type { ReqNode } from './Requirements';
 import type { Schedule } from './Schedule';
+import type { TermAverage } from './TermAverage';
 
 export type Course = {
   _id: string;
@@ -25,4 +26,5 @@ export type Course = {
   leadingTo: string[];
   restrictions: string;
   schedule: Schedule[];
+  averages?: TermAverage[];
 };
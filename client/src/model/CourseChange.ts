@@ -0,0 +1,14 @@
+type Timestamp = {
+  $date: {
+    $numberLong: string;
+  };
+};
+
+export type CourseChange = {
+  courseId: string;
+  field: string;
+  oldValue: unknown;
+  newValue: unknown;
+  source: string;
+  timestamp: Timestamp;
+};
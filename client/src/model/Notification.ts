@@ -1,7 +1,41 @@
+import type { CourseChange } from './CourseChange';
 import type { Review } from './Review';
 
-export type Notification = {
-  review: Review;
+type Timestamp = {
+  $date: {
+    $numberLong: string;
+  };
+};
+
+type NotificationPayload =
+  | { type: 'courseChange'; payload: { changes: CourseChange[] } }
+  | {
+      type: 'moderation';
+      payload: {
+        outcome: 'approved' | 'removed';
+        reason?: string;
+        review: Review;
+      };
+    }
+  | { type: 'review'; payload: Review }
+  | {
+      type: 'reviewReply';
+      payload: { content: string; review: Review; userId: string };
+    };
+
+export type NotificationType = NotificationPayload['type'];
+
+export type Notification = NotificationPayload & {
+  _id: { $oid: string };
+  courseId: string;
+  createdAt: Timestamp;
   seen: boolean;
+  updatedAt: Timestamp;
+  userId: string;
+};
+
+export type NotificationPreferences = {
+  mutedCourses: string[];
+  mutedTypes: NotificationType[];
   userId: string;
 };
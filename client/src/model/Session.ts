@@ -0,0 +1,18 @@
+type Timestamp = {
+  $date: {
+    $numberLong: string;
+  };
+};
+
+export type Session = {
+  id: string;
+  createdAt: Timestamp;
+  expiresAt: Timestamp;
+  lastSeenAt?: Timestamp;
+  userAgent?: string;
+  current: boolean;
+};
+
+export type GetSessionsPayload = {
+  sessions: Session[];
+};
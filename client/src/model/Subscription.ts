@@ -1,4 +1,22 @@
-export type Subscription = {
-  courseId: string;
+export type CourseFilter = {
+  levels?: string[];
+  query?: string;
+  subjects?: string[];
+  terms?: string[];
+};
+
+export type SubscriptionTarget =
+  | { courseId: string }
+  | { filter: CourseFilter }
+  | { instructor: string }
+  | { subject: string };
+
+export type Subscription = SubscriptionTarget & {
   userId: string;
 };
+
+export type CourseSubscription = { courseId: string; userId: string };
+
+export const isCourseSubscription = (
+  subscription: Subscription
+): subscription is CourseSubscription => 'courseId' in subscription;
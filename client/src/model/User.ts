@@ -1,5 +1,7 @@
 export interface User {
   id: string;
+  mail: string;
+  pseudonym: string;
 }
 
 export type UserResponse = {
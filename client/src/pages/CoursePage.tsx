@@ -70,7 +70,7 @@ export const CoursePage = () => {
 
         if (user && id) {
           const courseInteractionsPayload =
-            await repo.getUserInteractionsForCourse(id, user.id);
+            await repo.getUserInteractionsForCourse(id);
 
           setUserInteractions(courseInteractionsPayload.interactions);
         }
@@ -115,9 +115,9 @@ export const CoursePage = () => {
     corequisitesText: course.corequisitesText,
   };
 
-  const userReview = showingReviews?.find((r) => r.userId === user?.id);
+  const userReview = showingReviews?.find((r) => r.userId === user?.pseudonym);
   const canReview = Boolean(
-    user && !allReviews?.find((r) => r.userId === user?.id)
+    user && !allReviews?.find((r) => r.userId === user?.pseudonym)
   );
 
   const allCourseAverages: Record<string, TermAverage[]> =
@@ -244,7 +244,7 @@ export const CoursePage = () => {
             <div className='w-full shadow-sm'>
               {userReview && (
                 <CourseReview
-                  canModify={Boolean(user && userReview.userId === user.id)}
+                  canModify={Boolean(user && userReview.userId === user.pseudonym)}
                   handleDelete={() => handleDelete(userReview)}
                   openEditReview={() => setEditReviewOpen(true)}
                   review={userReview}
@@ -255,12 +255,12 @@ export const CoursePage = () => {
               {showingReviews.length > 0
                 ? showingReviews
                     .filter((review) =>
-                      user ? review.userId !== user.id : true
+                      user ? review.userId !== user.pseudonym : true
                     )
                     .slice(0, showAllReviews ? showingReviews.length : 8)
                     .map((review, i) => (
                       <CourseReview
-                        canModify={Boolean(user && review.userId === user.id)}
+                        canModify={Boolean(user && review.userId === user.pseudonym)}
                         interactions={userInteractions}
                         handleDelete={() => handleDelete(review)}
                         key={i}
@@ -335,7 +335,7 @@ export const CoursePage = () => {
               <div className='w-full shadow-sm'>
                 {userReview && (
                   <CourseReview
-                    canModify={Boolean(user && userReview.userId === user.id)}
+                    canModify={Boolean(user && userReview.userId === user.pseudonym)}
                     handleDelete={() => handleDelete(userReview)}
                     openEditReview={() => setEditReviewOpen(true)}
                     review={userReview}
@@ -346,12 +346,12 @@ export const CoursePage = () => {
                 {showingReviews.length > 0
                   ? showingReviews
                       .filter((review) =>
-                        user ? review.userId !== user.id : true
+                        user ? review.userId !== user.pseudonym : true
                       )
                       .slice(0, showAllReviews ? showingReviews.length : 8)
                       .map((review, i) => (
                         <CourseReview
-                          canModify={Boolean(user && review.userId === user.id)}
+                          canModify={Boolean(user && review.userId === user.pseudonym)}
                           handleDelete={() => handleDelete(review)}
                           key={i}
                           openEditReview={() => setEditReviewOpen(true)}
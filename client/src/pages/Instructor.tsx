@@ -46,7 +46,7 @@ export const Instructor = () => {
   if (instructor === undefined) return <Loading />;
   if (instructor === null) return <NotFound />;
 
-  const userReview = reviews.find((r) => r.userId === user?.id),
+  const userReview = reviews.find((r) => r.userId === user?.pseudonym),
     uniqueReviews = _.uniqBy(reviews, (r) => r.courseId);
 
   const updateLikes = (review: Review) => {
@@ -163,7 +163,7 @@ export const Instructor = () => {
           )}
           {reviews &&
             reviews
-              .filter((review) => (user ? review.userId !== user.id : true))
+              .filter((review) => (user ? review.userId !== user.pseudonym : true))
               .slice(0, showAllReviews ? reviews.length : 8)
               .map((review, i) => (
                 <CourseReview
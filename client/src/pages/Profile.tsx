@@ -1,4 +1,5 @@
 import { Tab } from '@headlessui/react';
+import { format } from 'date-fns';
 import { useEffect, useState } from 'react';
 import { User } from 'react-feather';
 import { Helmet } from 'react-helmet-async';
@@ -8,17 +9,23 @@ import { Link } from 'react-router-dom';
 import { toast } from 'sonner';
 import { twMerge } from 'tailwind-merge';
 
+import { ApiTokens } from '../components/ApiTokens';
 import { CourseReview } from '../components/CourseReview';
 import { DeleteButton } from '../components/DeleteButton';
 import { JumpToTopButton } from '../components/JumpToTopButton';
 import { Layout } from '../components/Layout';
 import { Spinner } from '../components/Spinner';
 import { useAuth } from '../hooks/useAuth';
+import { env } from '../lib/constants';
 import { repo } from '../lib/repo';
 import { courseIdToUrlParam } from '../lib/utils';
 import { spliceCourseCode } from '../lib/utils';
 import type { Review } from '../model/Review';
-import type { Subscription } from '../model/Subscription';
+import type { Session } from '../model/Session';
+import {
+  type CourseSubscription,
+  isCourseSubscription,
+} from '../model/Subscription';
 import { Loading } from './Loading';
 
 export const Profile = () => {
@@ -28,8 +35,9 @@ export const Profile = () => {
     undefined
   );
   const [userSubscriptions, setUserSubscriptions] = useState<
-    Subscription[] | undefined
+    CourseSubscription[] | undefined
   >(undefined);
+  const [sessions, setSessions] = useState<Session[] | undefined>(undefined);
   const [selectedTabIndex, setSelectedTabIndex] = useState(0);
 
   useEffect(() => {
@@ -40,7 +48,7 @@ export const Profile = () => {
     if (selectedTabIndex) setSelectedTabIndex(parseInt(selectedTabIndex, 10));
 
     repo
-      .getReviews({ userId: user.id, sorted: true })
+      .getReviews({ userId: user.pseudonym, sorted: true })
       .then((data) => setUserReviews(data.reviews))
       .catch(() =>
         toast.error(
@@ -50,12 +58,21 @@ export const Profile = () => {
 
     repo
       .getSubscriptions()
-      .then((data) => setUserSubscriptions(data))
+      .then((data) => setUserSubscriptions(data.filter(isCourseSubscription)))
       .catch(() =>
         toast.error(
           'An error occurred while fetching your subscriptions, please try again later.'
         )
       );
+
+    repo
+      .getSessions()
+      .then((data) => setSessions(data.sessions))
+      .catch(() =>
+        toast.error(
+          'An error occurred while fetching your sessions, please try again later.'
+        )
+      );
   }, []);
 
   const removeSubscription = async (courseId: string) => {
@@ -79,9 +96,47 @@ export const Profile = () => {
     }
   };
 
+  const revokeSession = async (id: string) => {
+    try {
+      await repo.revokeSession(id);
+      setSessions(sessions?.filter((session) => session.id !== id));
+      toast.success('Session signed out successfully.');
+    } catch (err) {
+      toast.error(
+        'An error occurred while signing out the session, please try again later.'
+      );
+    }
+  };
+
+  const revokeOtherSessions = async () => {
+    try {
+      await repo.revokeOtherSessions();
+      setSessions(sessions?.filter((session) => session.current));
+      toast.success('Signed out of all other sessions.');
+    } catch (err) {
+      toast.error(
+        'An error occurred while signing out your other sessions, please try again later.'
+      );
+    }
+  };
+
+  const formatTimestamp = (timestamp: Session['createdAt']) =>
+    format(new Date(parseInt(timestamp.$date.$numberLong, 10)), 'PPp');
+
+  const deleteAccount = async () => {
+    try {
+      await repo.deleteUser();
+      window.location.href = '/';
+    } catch (err) {
+      toast.error(
+        'An error occurred while deleting your account, please try again later.'
+      );
+    }
+  };
+
   if (!userReviews || !userSubscriptions) return <Loading />;
 
-  const tabs = ['Reviews', 'Subscriptions'];
+  const tabs = ['Reviews', 'Subscriptions', 'Sessions', 'API Tokens'];
 
   return (
     <Layout>
@@ -128,6 +183,20 @@ export const Profile = () => {
                     (userSubscriptions?.length === 1 ? '' : 's')}
                 </p>
               </div>
+              <div className='flex items-center gap-x-3'>
+                <a
+                  href={`${env.VITE_API_URL}/api/v1/user/export`}
+                  className='text-sm text-gray-700 hover:underline dark:text-gray-300'
+                >
+                  Download your data
+                </a>
+                <DeleteButton
+                  title='Delete Account'
+                  text='Are you sure you want to delete your account? Your reviews, votes, subscriptions and notifications will be permanently removed.'
+                  onConfirm={deleteAccount}
+                  size={18}
+                />
+              </div>
             </div>
           </div>
         </div>
@@ -242,6 +311,62 @@ export const Profile = () => {
                 )}
               </div>
             </Tab.Panel>
+            <Tab.Panel>
+              <div className='m-4 flex flex-col gap-4'>
+                {sessions === undefined ? (
+                  <div className='mt-2 text-center'>
+                    <Spinner />
+                  </div>
+                ) : (
+                  <>
+                    {sessions.map((session) => (
+                      <div
+                        key={session.id}
+                        className='flex items-center rounded-lg bg-white p-4 dark:bg-neutral-800'
+                      >
+                        <div className='flex flex-col gap-y-1'>
+                          <p className='font-semibold text-gray-800 dark:text-gray-200'>
+                            {session.userAgent ?? 'Unknown device'}
+                            {session.current && (
+                              <span className='ml-2 text-sm font-normal text-gray-500'>
+                                (this device)
+                              </span>
+                            )}
+                          </p>
+                          <p className='text-sm text-gray-600 dark:text-gray-400'>
+                            Signed in {formatTimestamp(session.createdAt)}
+                            {session.lastSeenAt &&
+                              `, last active ${formatTimestamp(
+                                session.lastSeenAt
+                              )}`}
+                          </p>
+                        </div>
+                        {!session.current && (
+                          <DeleteButton
+                            title='Sign Out Session'
+                            className='ml-auto'
+                            text='Are you sure you want to sign this session out?'
+                            onConfirm={() => revokeSession(session.id)}
+                            size={20}
+                          />
+                        )}
+                      </div>
+                    ))}
+                    {sessions.length > 1 && (
+                      <button
+                        className='ml-auto text-sm text-gray-700 hover:underline dark:text-gray-300'
+                        onClick={revokeOtherSessions}
+                      >
+                        Sign out of all other sessions
+                      </button>
+                    )}
+                  </>
+                )}
+              </div>
+            </Tab.Panel>
+            <Tab.Panel>
+              <ApiTokens />
+            </Tab.Panel>
           </Tab.Panels>
         </Tab.Group>
       </div>
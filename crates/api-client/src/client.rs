@@ -0,0 +1,461 @@
+use super::*;
+
+/// Name of the cookie holding a browser session.
+pub const SESSION_COOKIE: &str = "session";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Credentials {
+  /// The value of a signed in browser's session cookie.
+  Session(String),
+  /// A personal access token, limited to the scopes it was created with.
+  Token(String),
+}
+
+/// Typed wrapper around the `/api/v1` routes. Signing in goes through the
+/// identity provider in a browser, so it isn't covered here; use a session
+/// cookie from the browser or a personal access token instead.
+#[derive(Debug, Clone)]
+pub struct Client {
+  base_url: Url,
+  credentials: Option<Credentials>,
+  http: reqwest::Client,
+}
+
+impl Client {
+  pub fn new(base_url: &str) -> Result<Self> {
+    let base_url = Url::parse(base_url)?;
+
+    if base_url.cannot_be_a_base() {
+      bail!("`{base_url}` can't be used as a base url");
+    }
+
+    Ok(Self {
+      base_url,
+      credentials: None,
+      // Unauthenticated requests redirect to the login page, which is only
+      // useful to a browser
+      http: reqwest::Client::builder()
+        .redirect(redirect::Policy::none())
+        .build()?,
+    })
+  }
+
+  pub fn with_credentials(self, credentials: Credentials) -> Self {
+    Self {
+      credentials: Some(credentials),
+      ..self
+    }
+  }
+
+  pub fn with_session(self, cookie: &str) -> Self {
+    self.with_credentials(Credentials::Session(cookie.into()))
+  }
+
+  pub fn with_token(self, token: &str) -> Self {
+    self.with_credentials(Credentials::Token(token.into()))
+  }
+
+  fn url(&self, segments: &[&str]) -> Url {
+    let mut url = self.base_url.clone();
+
+    url
+      .path_segments_mut()
+      .expect("checked in `new`")
+      .pop_if_empty()
+      .push("api")
+      .push("v1")
+      .extend(segments);
+
+    url
+  }
+
+  fn request(&self, method: Method, segments: &[&str]) -> RequestBuilder {
+    let request = self.http.request(method, self.url(segments));
+
+    match &self.credentials {
+      Some(Credentials::Session(cookie)) => {
+        request.header(header::COOKIE, format!("{SESSION_COOKIE}={cookie}"))
+      }
+      Some(Credentials::Token(token)) => request.bearer_auth(token),
+      None => request,
+    }
+  }
+
+  async fn send(request: RequestBuilder) -> Result<Response> {
+    let response = request.send().await?;
+
+    let status = response.status();
+
+    if status.is_redirection() {
+      bail!("{} requires signing in", response.url().path());
+    }
+
+    if !status.is_success() && status != StatusCode::NOT_FOUND {
+      let path = response.url().path().to_string();
+
+      bail!("{path} failed with {status}: {}", response.text().await?);
+    }
+
+    Ok(response)
+  }
+
+  async fn json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+    Self::json_optional(request)
+      .await?
+      .ok_or_else(|| anyhow!("Not found"))
+  }
+
+  /// Like `json`, but `None` when the server responds with a 404.
+  async fn json_optional<T: DeserializeOwned>(
+    request: RequestBuilder,
+  ) -> Result<Option<T>> {
+    let response = Self::send(request).await?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(None);
+    }
+
+    Ok(Some(response.json().await?))
+  }
+
+  /// Sends a request without a response body, returning `false` when the
+  /// server responds with a 404.
+  async fn empty(request: RequestBuilder) -> Result<bool> {
+    Ok(Self::send(request).await?.status() != StatusCode::NOT_FOUND)
+  }
+
+  async fn expect_found(request: RequestBuilder) -> Result {
+    if !Self::empty(request).await? {
+      bail!("Not found");
+    }
+
+    Ok(())
+  }
+
+  fn get(&self, segments: &[&str]) -> RequestBuilder {
+    self.request(Method::GET, segments)
+  }
+
+  /// Progress of database initialization, for admins only.
+  pub async fn initialization(&self) -> Result<InitializationStatus> {
+    Self::json(self.get(&["admin", "initialization"])).await
+  }
+
+  /// Fetched with a `GET`, so caches in between can revalidate it.
+  pub async fn courses(
+    &self,
+    params: &GetCoursesParams,
+    filter: &CourseFilter,
+  ) -> Result<GetCoursesPayload> {
+    Self::json(self.get(&["courses"]).query(&ListCoursesParams {
+      filter: Some(serde_json::to_string(filter)?),
+      limit: params.limit,
+      offset: params.offset,
+      with_course_count: params.with_course_count,
+    }))
+    .await
+  }
+
+  pub async fn course(&self, id: &str) -> Result<Option<Course>> {
+    Self::json_optional(self.get(&["courses", id])).await
+  }
+
+  pub async fn course_with_reviews(
+    &self,
+    id: &str,
+  ) -> Result<Option<GetCourseWithReviewsPayload>> {
+    Self::json_optional(self.get(&["courses", id]).query(&GetCourseParams {
+      with_reviews: Some(true),
+    }))
+    .await
+  }
+
+  pub async fn course_changes(
+    &self,
+    id: &str,
+  ) -> Result<Option<Vec<CourseChange>>> {
+    Self::json_optional(self.get(&["courses", id, "changes"])).await
+  }
+
+  pub async fn course_stats(
+    &self,
+    id: &str,
+    params: &GetCourseStatsParams,
+  ) -> Result<Option<ReviewStats>> {
+    Self::json_optional(self.get(&["courses", id, "stats"]).query(params)).await
+  }
+
+  pub async fn instructor(&self, name: &str) -> Result<GetInstructorPayload> {
+    Self::json(self.get(&["instructors", name])).await
+  }
+
+  pub async fn interaction_kind(
+    &self,
+    params: &GetInteractionKindParams,
+  ) -> Result<GetInteractionKindPayload> {
+    Self::json(self.get(&["interactions"]).query(params)).await
+  }
+
+  pub async fn course_interactions(
+    &self,
+    course_id: &str,
+  ) -> Result<GetUserInteractionForCoursePayload> {
+    Self::json(self.get(&["interactions", course_id])).await
+  }
+
+  pub async fn add_interaction(&self, body: &AddInteractionBody) -> Result {
+    Self::expect_found(self.request(Method::POST, &["interactions"]).json(body))
+      .await
+  }
+
+  pub async fn delete_interaction(
+    &self,
+    body: &DeleteInteractionBody,
+  ) -> Result {
+    Self::expect_found(
+      self.request(Method::DELETE, &["interactions"]).json(body),
+    )
+    .await
+  }
+
+  pub async fn notifications(
+    &self,
+    params: &GetNotificationsParams,
+  ) -> Result<Vec<Notification>> {
+    Self::json(self.get(&["notifications"]).query(params)).await
+  }
+
+  /// Notifications as they arrive, starting with any missed since
+  /// `last_event_id`.
+  pub async fn stream_notifications(
+    &self,
+    last_event_id: Option<ObjectId>,
+  ) -> Result<impl Stream<Item = Result<Notification>>> {
+    let mut request = self.get(&["notifications", "stream"]);
+
+    if let Some(id) = last_event_id {
+      request = request.header("Last-Event-ID", id.to_hex());
+    }
+
+    let response = Self::send(request).await?;
+
+    Ok(stream::unfold(
+      (response, EventParser::default(), VecDeque::<String>::new()),
+      |(mut response, mut parser, mut pending)| async move {
+        loop {
+          if let Some(data) = pending.pop_front() {
+            return Some((
+              serde_json::from_str::<Notification>(&data).map_err(Into::into),
+              (response, parser, pending),
+            ));
+          }
+
+          match response.chunk().await {
+            Ok(Some(chunk)) => pending.extend(parser.push(&chunk)),
+            Ok(None) => return None,
+            Err(error) => {
+              return Some((Err(error.into()), (response, parser, pending)))
+            }
+          }
+        }
+      },
+    ))
+  }
+
+  /// Returns `false` if there is no such notification.
+  pub async fn update_notification(
+    &self,
+    id: ObjectId,
+    body: &UpdateNotificationBody,
+  ) -> Result<bool> {
+    Self::empty(
+      self
+        .request(Method::PUT, &["notifications", &id.to_hex()])
+        .json(body),
+    )
+    .await
+  }
+
+  /// Returns `false` if there is no such notification.
+  pub async fn delete_notification(&self, id: ObjectId) -> Result<bool> {
+    Self::empty(self.request(Method::DELETE, &["notifications", &id.to_hex()]))
+      .await
+  }
+
+  pub async fn mark_all_notifications_seen(&self) -> Result {
+    Self::expect_found(self.request(Method::POST, &["notifications", "seen"]))
+      .await
+  }
+
+  pub async fn notification_preferences(
+    &self,
+  ) -> Result<NotificationPreferences> {
+    Self::json(self.get(&["notifications", "preferences"])).await
+  }
+
+  pub async fn update_notification_preferences(
+    &self,
+    body: &UpdateNotificationPreferencesBody,
+  ) -> Result<NotificationPreferences> {
+    Self::json(
+      self
+        .request(Method::PUT, &["notifications", "preferences"])
+        .json(body),
+    )
+    .await
+  }
+
+  pub async fn openapi(&self) -> Result<Value> {
+    Self::json(self.get(&["openapi.json"])).await
+  }
+
+  pub async fn reviews(
+    &self,
+    params: &GetReviewsParams,
+  ) -> Result<GetReviewsPayload> {
+    Self::json(self.get(&["reviews"]).query(params)).await
+  }
+
+  /// The signed in user's review of a course, if they wrote one.
+  pub async fn review(&self, course_id: &str) -> Result<Option<Review>> {
+    Self::json(self.get(&["reviews", course_id])).await
+  }
+
+  pub async fn add_review(&self, body: &AddOrUpdateReviewBody) -> Result {
+    Self::expect_found(self.request(Method::POST, &["reviews"]).json(body))
+      .await
+  }
+
+  pub async fn update_review(&self, body: &AddOrUpdateReviewBody) -> Result {
+    Self::expect_found(self.request(Method::PUT, &["reviews"]).json(body)).await
+  }
+
+  pub async fn delete_review(&self, body: &DeleteReviewBody) -> Result {
+    Self::expect_found(self.request(Method::DELETE, &["reviews"]).json(body))
+      .await
+  }
+
+  pub async fn search(&self, query: &str) -> Result<SearchResults> {
+    Self::json(self.get(&["search"]).query(&SearchParams {
+      query: query.into(),
+    }))
+    .await
+  }
+
+  pub async fn sessions(&self) -> Result<GetSessionsPayload> {
+    Self::json(self.get(&["sessions"])).await
+  }
+
+  pub async fn revoke_sessions(&self, params: &DeleteSessionsParams) -> Result {
+    Self::expect_found(
+      self.request(Method::DELETE, &["sessions"]).query(params),
+    )
+    .await
+  }
+
+  /// Returns `false` if there is no such session.
+  pub async fn revoke_session(&self, id: &str) -> Result<bool> {
+    Self::empty(self.request(Method::DELETE, &["sessions", id])).await
+  }
+
+  pub async fn subscriptions(&self) -> Result<Vec<Subscription>> {
+    Self::json(self.get(&["subscriptions"])).await
+  }
+
+  pub async fn course_subscription(
+    &self,
+    course_id: &str,
+  ) -> Result<Option<Subscription>> {
+    Self::json(self.get(&["subscriptions"]).query(&GetSubscriptionParams {
+      course_id: Some(course_id.into()),
+    }))
+    .await
+  }
+
+  pub async fn add_subscription(&self, target: SubscriptionTarget) -> Result {
+    self.update_subscription(Method::POST, target).await
+  }
+
+  pub async fn delete_subscription(
+    &self,
+    target: SubscriptionTarget,
+  ) -> Result {
+    self.update_subscription(Method::DELETE, target).await
+  }
+
+  async fn update_subscription(
+    &self,
+    method: Method,
+    target: SubscriptionTarget,
+  ) -> Result {
+    Self::expect_found(
+      self
+        .request(method, &["subscriptions"])
+        .json(&AddOrDeleteSubscriptionBody::from(target)),
+    )
+    .await
+  }
+
+  pub async fn api_tokens(&self) -> Result<Vec<ApiTokenPayload>> {
+    Self::json(self.get(&["tokens"])).await
+  }
+
+  pub async fn add_api_token(
+    &self,
+    body: &AddApiTokenBody,
+  ) -> Result<AddApiTokenPayload> {
+    Self::json(self.request(Method::POST, &["tokens"]).json(body)).await
+  }
+
+  /// Returns `false` if there is no such token.
+  pub async fn delete_api_token(&self, id: &str) -> Result<bool> {
+    Self::empty(self.request(Method::DELETE, &["tokens", id])).await
+  }
+
+  /// The signed in user, or `None` without valid credentials.
+  pub async fn user(&self) -> Result<Option<CurrentUser>> {
+    Ok(Self::json::<UserResponse>(self.get(&["user"])).await?.user)
+  }
+
+  pub async fn export_user(&self) -> Result<UserExport> {
+    Self::json(self.get(&["user", "export"])).await
+  }
+
+  /// Deletes the account and everything stored about it.
+  pub async fn delete_user(&self) -> Result {
+    Self::expect_found(self.request(Method::DELETE, &["user"])).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn urls_keep_base_path_and_escape_segments() {
+    for (base, segments, expected) in [
+      (
+        "http://localhost:8000",
+        &["courses", "MATH240"][..],
+        "http://localhost:8000/api/v1/courses/MATH240",
+      ),
+      (
+        "https://example.com/prefix/",
+        &["instructors", "Adrian Roshan Vetta"],
+        "https://example.com/prefix/api/v1/instructors/Adrian%20Roshan%20Vetta",
+      ),
+      (
+        "https://example.com/prefix",
+        &["reviews", "a/b"],
+        "https://example.com/prefix/api/v1/reviews/a%2Fb",
+      ),
+    ] {
+      assert_eq!(Client::new(base).unwrap().url(segments).as_str(), expected);
+    }
+  }
+
+  #[test]
+  fn rejects_urls_without_paths() {
+    assert!(Client::new("mailto:someone@example.com").is_err());
+  }
+}
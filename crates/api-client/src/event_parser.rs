@@ -0,0 +1,56 @@
+/// Splits a server-sent event stream, fed in arbitrary chunks, into the data
+/// of each complete event.
+#[derive(Debug, Default)]
+pub(crate) struct EventParser {
+  buffer: Vec<u8>,
+}
+
+impl EventParser {
+  pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+    self
+      .buffer
+      .extend(chunk.iter().filter(|byte| **byte != b'\r'));
+
+    let mut events = Vec::new();
+
+    while let Some(end) =
+      self.buffer.windows(2).position(|window| window == b"\n\n")
+    {
+      let event = self.buffer.drain(..end + 2).collect::<Vec<u8>>();
+
+      let data = String::from_utf8_lossy(&event)
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data).to_string())
+        .collect::<Vec<String>>();
+
+      // Keep-alive comments have no data
+      if !data.is_empty() {
+        events.push(data.join("\n"));
+      }
+    }
+
+    events
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn splits_events_across_chunks() {
+    let mut parser = EventParser::default();
+
+    assert!(parser
+      .push(b":\n\nevent: notification\nid: 1\nda")
+      .is_empty());
+
+    assert_eq!(
+      parser.push(b"ta: {\"a\":1}\n\nevent: notification\r\ndata: 2\r\n\r\n"),
+      vec!["{\"a\":1}", "2"]
+    );
+
+    assert_eq!(parser.push(b"data: a\ndata: b\n\n"), vec!["a\nb"]);
+  }
+}
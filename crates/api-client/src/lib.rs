@@ -0,0 +1,36 @@
+use {
+  anyhow::{anyhow, bail},
+  bson::oid::ObjectId,
+  event_parser::EventParser,
+  futures::{stream, Stream},
+  model::{
+    AddApiTokenBody, AddApiTokenPayload, AddInteractionBody,
+    AddOrDeleteSubscriptionBody, AddOrUpdateReviewBody, ApiTokenPayload,
+    Course, CourseChange, CourseFilter, CurrentUser, DeleteInteractionBody,
+    DeleteReviewBody, DeleteSessionsParams, GetCourseParams,
+    GetCourseStatsParams, GetCourseWithReviewsPayload, GetCoursesParams,
+    GetCoursesPayload, GetInstructorPayload, GetInteractionKindParams,
+    GetInteractionKindPayload, GetNotificationsParams, GetReviewsParams,
+    GetReviewsPayload, GetSessionsPayload, GetSubscriptionParams,
+    GetUserInteractionForCoursePayload, InitializationStatus,
+    ListCoursesParams, Notification, NotificationPreferences, Review,
+    ReviewStats, SearchParams, SearchResults, Subscription, SubscriptionTarget,
+    UpdateNotificationBody, UpdateNotificationPreferencesBody, UserExport,
+    UserResponse,
+  },
+  reqwest::{header, redirect, Method, RequestBuilder, Response, StatusCode},
+  serde::de::DeserializeOwned,
+  serde_json::Value,
+  std::collections::VecDeque,
+  url::Url,
+};
+
+mod client;
+mod event_parser;
+
+pub use {
+  crate::client::{Client, Credentials, SESSION_COOKIE},
+  model,
+};
+
+type Result<T = (), E = anyhow::Error> = std::result::Result<T, E>;
@@ -4,28 +4,43 @@ use super::*;
 pub struct Db {
   client: Client,
   database: Database,
+  initialization: Arc<Mutex<InitializationStatus>>,
 }
 
 impl Db {
+  const API_TOKEN_COLLECTION: &'static str = "apiTokens";
+  const CHANGE_COLLECTION: &'static str = "changes";
   const COURSE_COLLECTION: &'static str = "courses";
   const INSTRUCTOR_COLLECTION: &'static str = "instructors";
   const INTERACTION_COLLECTION: &'static str = "interactions";
   const NOTIFICATION_COLLECTION: &'static str = "notifications";
+  const NOTIFICATION_PREFERENCES_COLLECTION: &'static str =
+    "notificationPreferences";
   const REVIEW_COLLECTION: &'static str = "reviews";
+  pub const SESSION_COLLECTION: &'static str = "store";
+  const SESSION_METADATA_COLLECTION: &'static str = "sessionMetadata";
   const SUBSCRIPTION_COLLECTION: &'static str = "subscriptions";
 
   pub async fn connect(db_name: &str) -> Result<Self> {
-    let mut client_options =
-      ClientOptions::parse(env::var("MONGODB_URL").unwrap_or_else(|_| {
+    Self::connect_to(
+      &env::var("MONGODB_URL").unwrap_or_else(|_| {
         format!(
           "mongodb://localhost:27017/{}?directConnection=true&replicaSet=rs0",
           db_name
         )
-      }))
-      .await?;
+      }),
+      db_name,
+    )
+    .await
+  }
+
+  pub async fn connect_to(url: &str, db_name: &str) -> Result<Self> {
+    let mut client_options = ClientOptions::parse(url).await?;
 
     client_options.app_name = Some(db_name.to_string());
 
+    client_options.command_event_handler = Some(Arc::new(CommandTimer));
+
     let client = Client::with_options(client_options)?;
 
     client
@@ -35,9 +50,21 @@ impl Db {
 
     info!("Connected to MongoDB.");
 
+    let database = client.database(db_name);
+
+    // Sessions are found by user through their metadata
+    database
+      .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+      .create_index(
+        IndexModel::builder().keys(doc! { "userId": 1 }).build(),
+        None,
+      )
+      .await?;
+
     Ok(Self {
-      database: client.database(db_name),
+      database,
       client,
+      initialization: Arc::default(),
     })
   }
 
@@ -45,100 +72,187 @@ impl Db {
     self.database.name().to_string()
   }
 
-  pub async fn initialize(&self, options: InitializeOptions) -> Result {
-    Initializer::new(self.clone(), options).run().await
+  /// Marks initialization as pending straight away, so readiness checks
+  /// don't pass before the returned future is first polled.
+  pub fn initialize(
+    &self,
+    options: InitializeOptions,
+  ) -> impl Future<Output = Result> + Send + 'static {
+    self.initialize_publishing(options, Arc::new(|_| {}))
   }
 
-  pub async fn courses(
+  /// Like `initialize`, handing the notifications seeding creates, about
+  /// course changes, to `publish` as they're stored.
+  pub fn initialize_publishing(
     &self,
-    limit: Option<i64>,
-    offset: Option<u64>,
-    filter: Option<CourseFilter>,
-  ) -> Result<Vec<Course>> {
-    let (mut document, mut sort_document) = (Document::new(), Document::new());
+    options: InitializeOptions,
+    publish: Publish,
+  ) -> impl Future<Output = Result> + Send + 'static {
+    self.update_initialization(|status| {
+      *status = InitializationStatus {
+        state: InitializationState::Pending,
+        ..Default::default()
+      }
+    });
 
-    if let Some(filter) = filter {
-      let CourseFilter {
-        subjects,
-        levels,
-        terms,
-        query,
-        ..
-      } = filter;
+    Initializer::new(self.clone(), options, publish).run()
+  }
 
-      if let Some(subjects) = subjects {
-        document.insert(
-          "subject",
-          doc! { "$regex": format!("^({})", subjects.join("|")), "$options": "i" },
-        );
-      }
+  pub fn initialization(&self) -> InitializationStatus {
+    self.initialization.lock().unwrap().clone()
+  }
 
-      if let Some(levels) = levels {
-        document.insert(
-          "code",
-          doc! { "$regex": format!("^({})", levels.join("|")), "$options": "i" },
-        );
-      }
+  pub(crate) fn update_initialization(
+    &self,
+    update: impl FnOnce(&mut InitializationStatus),
+  ) {
+    update(&mut self.initialization.lock().unwrap());
+  }
 
-      if let Some(terms) = terms {
-        document.insert(
-          "terms",
-          doc! { "$regex": format!("^({})", terms.join("|")), "$options": "i" },
-        );
+  pub async fn ping(&self) -> Result {
+    self.database.run_command(doc! { "ping": 1 }, None).await?;
+    Ok(())
+  }
+
+  /// Sessions in the session store that haven't expired yet.
+  pub async fn session_count(&self) -> Result<u64> {
+    Ok(
+      self
+        .database
+        .collection::<Document>(Self::SESSION_COLLECTION)
+        .count_documents(
+          doc! {
+            "$or": [
+              { "expireAt": { "$gt": DateTime::now() } },
+              { "expireAt": null },
+            ]
+          },
+          None,
+        )
+        .await?,
+    )
+  }
+
+  /// Whether the text indexes that search relies on exist.
+  pub async fn indexed(&self) -> Result<bool> {
+    for collection in [Self::COURSE_COLLECTION, Self::INSTRUCTOR_COLLECTION] {
+      if !self
+        .database
+        .collection::<Document>(collection)
+        .list_index_names()
+        .await?
+        .iter()
+        .any(|name| name.ends_with("_text"))
+      {
+        return Ok(false);
       }
+    }
 
-      if let Some(query) = query.clone() {
-        let current_terms = current_terms();
+    Ok(true)
+  }
 
-        let id = doc! {
-          "_id": doc! {
-            "$regex": format!(".*{}.*", query.replace(' ', "")),
-            "$options": "i"
-          }
-        };
+  /// The query selecting courses that match `filter`, shared by course
+  /// listings and saved filter subscriptions so the two agree.
+  fn course_filter_query(filter: &CourseFilter) -> Document {
+    let mut document = Document::new();
+
+    let CourseFilter {
+      subjects,
+      levels,
+      terms,
+      query,
+      sort_by,
+    } = filter;
+
+    if let Some(subjects) = subjects {
+      document.insert(
+        "subject",
+        doc! { "$regex": format!("^({})", subjects.join("|")), "$options": "i" },
+      );
+    }
 
-        let instructor = doc! {
-          "instructors": doc! {
-            "$elemMatch": doc! {
-              "name": doc! {
-                "$regex": format!(".*{}.*", query),
-                "$options": "i"
-              },
-              "term": doc! {
-                "$regex": format!(".*({}).*", current_terms.join("|")),
-                "$options": "i"
-              }
+    if let Some(levels) = levels {
+      document.insert(
+        "code",
+        doc! { "$regex": format!("^({})", levels.join("|")), "$options": "i" },
+      );
+    }
+
+    if let Some(terms) = terms {
+      document.insert(
+        "terms",
+        doc! { "$regex": format!("^({})", terms.join("|")), "$options": "i" },
+      );
+    }
+
+    if let Some(query) = query {
+      let current_terms = current_terms();
+
+      let id = doc! {
+        "_id": doc! {
+          "$regex": format!(".*{}.*", query.replace(' ', "")),
+          "$options": "i"
+        }
+      };
+
+      let instructor = doc! {
+        "instructors": doc! {
+          "$elemMatch": doc! {
+            "name": doc! {
+              "$regex": format!(".*{}.*", query),
+              "$options": "i"
+            },
+            "term": doc! {
+              "$regex": format!(".*({}).*", current_terms.join("|")),
+              "$options": "i"
             }
           }
-        };
+        }
+      };
 
-        let rest = ["code", "description", "subject", "title"]
-          .into_iter()
-          .map(|field| {
-            doc! { field: doc! {
-              "$regex": format!(".*{}.*", query), "$options": "i" }
-            }
-          })
-          .collect::<Vec<Document>>();
+      let rest = ["code", "description", "subject", "title"]
+        .into_iter()
+        .map(|field| {
+          doc! { field: doc! {
+            "$regex": format!(".*{}.*", query), "$options": "i" }
+          }
+        })
+        .collect::<Vec<Document>>();
 
-        document.insert("$or", [vec![id, instructor], rest].concat());
-      }
+      document.insert("$or", [vec![id, instructor], rest].concat());
+    }
+
+    if let Some(CourseSort {
+      sort_type: CourseSortType::Rating | CourseSortType::Difficulty,
+      ..
+    }) = sort_by
+    {
+      document.insert("reviewCount", doc! { "$gt": 0 });
+    }
+
+    document
+  }
+
+  pub async fn courses(
+    &self,
+    limit: Option<i64>,
+    offset: Option<u64>,
+    filter: Option<CourseFilter>,
+  ) -> Result<Vec<Course>> {
+    let (mut document, mut sort_document) = (Document::new(), Document::new());
+
+    if let Some(filter) = filter {
+      document = Self::course_filter_query(&filter);
 
       if let Some(sort_by) = filter.sort_by {
         let reverse = if sort_by.reverse { -1 } else { 1 };
         let field = match sort_by.sort_type {
-          CourseSortType::Rating => {
-            document.insert("reviewCount", doc! { "$gt": 0 });
-            "avgRating"
-          }
-          CourseSortType::Difficulty => {
-            document.insert("reviewCount", doc! { "$gt": 0 });
-            "avgDifficulty"
-          }
+          CourseSortType::Rating => "avgRating",
+          CourseSortType::Difficulty => "avgDifficulty",
           CourseSortType::ReviewCount => "reviewCount",
         };
         sort_document.insert(field, reverse);
-      } else if query.is_none() {
+      } else if filter.query.is_none() {
         sort_document.insert("_id", 1);
       }
     }
@@ -325,7 +439,7 @@ impl Db {
         .await?
         .ok_or(mongodb::error::Error::custom(anyhow!("Course not found")))?;
 
-      let (avg_rating, avg_difficulty) = if course.review_count == 0 {
+      let (avg_rating, avg_difficulty) = if course.review_count <= 1 {
         (0.0, 0.0)
       } else {
         let count = course.review_count as f32;
@@ -402,6 +516,20 @@ impl Db {
       .await
   }
 
+  pub async fn review_stats(
+    &self,
+    course_id: &str,
+    instructor_name: Option<&str>,
+  ) -> Result<ReviewStats> {
+    let mut query = doc! { "courseId": course_id };
+
+    if let Some(instructor_name) = instructor_name {
+      query.insert("instructors", doc! { "$in": vec![instructor_name] });
+    }
+
+    Ok(self.find_reviews(query).await?.into())
+  }
+
   pub async fn find_review(
     &self,
     course_id: &str,
@@ -691,12 +819,18 @@ impl Db {
   pub async fn add_subscription(
     &self,
     subscription: Subscription,
-  ) -> Result<InsertOneResult> {
+  ) -> Result<UpdateResult> {
+    let document = bson::to_document(&subscription)?;
+
     Ok(
       self
         .database
         .collection::<Subscription>(Self::SUBSCRIPTION_COLLECTION)
-        .insert_one(subscription, None)
+        .update_one(
+          document.clone(),
+          UpdateModifications::Document(doc! { "$set": document }),
+          UpdateOptions::builder().upsert(true).build(),
+        )
         .await?,
     )
   }
@@ -709,87 +843,273 @@ impl Db {
       self
         .database
         .collection::<Subscription>(Self::SUBSCRIPTION_COLLECTION)
-        .delete_one(
+        .delete_one(bson::to_document(&subscription)?, None)
+        .await?,
+    )
+  }
+
+  async fn subscribers(
+    &self,
+    course_id: &str,
+    instructors: &[String],
+  ) -> Result<Vec<String>> {
+    let course = self.find_course_by_id(course_id).await?;
+
+    let (subject, code) = match &course {
+      Some(course) => (course.subject.clone(), course.code.clone()),
+      None => (
+        course_id.chars().take(4).collect(),
+        course_id.chars().skip(4).collect(),
+      ),
+    };
+
+    // Saved filters list subject and level prefixes, so only those with a
+    // prefix of this course's subject and code, or none at all, can match
+    let prefixes = |value: &str| {
+      let mut prefixes = vec![Bson::Null, Bson::Array(Vec::new())];
+
+      prefixes.extend((1..=value.len()).filter_map(|end| {
+        Some(Bson::RegularExpression(bson::Regex {
+          pattern: format!("^{}$", regex::escape(value.get(..end)?)),
+          options: "i".into(),
+        }))
+      }));
+
+      prefixes
+    };
+
+    let subscriptions = self
+      .database
+      .collection::<Subscription>(Self::SUBSCRIPTION_COLLECTION)
+      .find(
+        doc! {
+          "$or": [
+            { "courseId": course_id },
+            { "subject": &subject },
+            { "instructor": { "$in": instructors } },
+            {
+              "filter": { "$exists": true },
+              "filter.subjects": { "$in": prefixes(&subject) },
+              "filter.levels": { "$in": prefixes(&code) },
+            },
+          ]
+        },
+        None,
+      )
+      .await?
+      .try_collect::<Vec<Subscription>>()
+      .await?;
+
+    let mut subscribers = Vec::new();
+
+    for subscription in subscriptions {
+      if let SubscriptionTarget::Filter(filter) = &subscription.target {
+        if !self.course_matches(course_id, filter).await? {
+          continue;
+        }
+      }
+
+      subscribers.push(subscription.user_id);
+    }
+
+    Ok(subscribers.into_iter().unique().collect())
+  }
+
+  /// Whether the course with `course_id` is listed by `filter`.
+  async fn course_matches(
+    &self,
+    course_id: &str,
+    filter: &CourseFilter,
+  ) -> Result<bool> {
+    Ok(
+      self
+        .database
+        .collection::<Course>(Self::COURSE_COLLECTION)
+        .count_documents(
           doc! {
-            "courseId": subscription.course_id,
-            "userId": subscription.user_id,
+            "$and": [
+              { "_id": course_id },
+              Self::course_filter_query(filter),
+            ]
           },
           None,
         )
-        .await?,
+        .await?
+        > 0,
     )
   }
 
   pub async fn get_notifications(
     &self,
     user_id: &str,
+    limit: Option<i64>,
+    offset: Option<u64>,
   ) -> Result<Vec<Notification>> {
     Ok(
       self
         .database
         .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
-        .find(doc! { "userId": user_id }, None)
+        .find(
+          doc! { "userId": user_id },
+          FindOptions::builder()
+            .sort(doc! { "createdAt": -1, "_id": -1 })
+            .skip(offset)
+            .limit(limit)
+            .build(),
+        )
+        .await?
+        .try_collect::<Vec<Notification>>()
+        .await?,
+    )
+  }
+
+  pub async fn notifications_since(
+    &self,
+    user_id: &str,
+    id: ObjectId,
+  ) -> Result<Vec<Notification>> {
+    Ok(
+      self
+        .database
+        .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
+        .find(
+          doc! { "_id": { "$gt": id }, "userId": user_id },
+          FindOptions::builder().sort(doc! { "_id": 1 }).build(),
+        )
         .await?
         .try_collect::<Vec<Notification>>()
         .await?,
     )
   }
 
-  pub async fn add_notifications(&self, review: Review) -> Result {
-    let course_id = review.course_id.clone();
+  pub async fn add_notifications(
+    &self,
+    review: Review,
+  ) -> Result<Vec<Notification>> {
+    self
+      .notify_subscribers(
+        &review.course_id.clone(),
+        &review.instructors.clone(),
+        Some(&review.user_id.clone()),
+        NotificationPayload::Review(review),
+      )
+      .await
+  }
+
+  pub(crate) async fn add_course_change_notifications(
+    &self,
+    changes: Vec<CourseChange>,
+  ) -> Result<Vec<Notification>> {
+    let current_terms = current_terms();
+
+    let changes = changes
+      .into_iter()
+      .filter(|change| change.is_notable(&current_terms))
+      .collect::<Vec<CourseChange>>();
 
-    let subscriptions = self
+    let Some(course_id) =
+      changes.first().map(|change| change.course_id.clone())
+    else {
+      return Ok(Vec::new());
+    };
+
+    self
+      .notify_subscribers(
+        &course_id,
+        &[],
+        None,
+        NotificationPayload::CourseChange { changes },
+      )
+      .await
+  }
+
+  pub async fn notify_user(
+    &self,
+    user_id: &str,
+    course_id: &str,
+    payload: NotificationPayload,
+  ) -> Result<Vec<Notification>> {
+    self
+      .insert_notifications(vec![Notification::new(
+        user_id, course_id, payload,
+      )])
+      .await
+  }
+
+  async fn notify_subscribers(
+    &self,
+    course_id: &str,
+    instructors: &[String],
+    creator_id: Option<&str>,
+    payload: NotificationPayload,
+  ) -> Result<Vec<Notification>> {
+    self
+      .insert_notifications(
+        self
+          .subscribers(course_id, instructors)
+          .await?
+          .into_iter()
+          .filter(|user_id| Some(user_id.as_str()) != creator_id)
+          .map(|user_id| {
+            Notification::new(&user_id, course_id, payload.clone())
+          })
+          .collect(),
+      )
+      .await
+  }
+
+  async fn insert_notifications(
+    &self,
+    notifications: Vec<Notification>,
+  ) -> Result<Vec<Notification>> {
+    let user_ids = notifications
+      .iter()
+      .map(|notification| notification.user_id.clone())
+      .collect::<Vec<String>>();
+
+    let preferences = self
       .database
-      .collection::<Subscription>(Self::SUBSCRIPTION_COLLECTION)
-      .find(doc! { "courseId": course_id }, None)
+      .collection::<NotificationPreferences>(
+        Self::NOTIFICATION_PREFERENCES_COLLECTION,
+      )
+      .find(doc! { "userId": { "$in": user_ids } }, None)
       .await?
-      .try_collect::<Vec<Subscription>>()
+      .try_collect::<Vec<NotificationPreferences>>()
       .await?;
 
-    let subscriptions = subscriptions
+    let notifications = notifications
       .into_iter()
-      .filter(|subscription| subscription.user_id != review.user_id)
-      .collect::<Vec<Subscription>>();
+      .filter(|notification| {
+        preferences
+          .iter()
+          .filter(|preferences| preferences.user_id == notification.user_id)
+          .all(|preferences| preferences.allows(notification))
+      })
+      .collect::<Vec<Notification>>();
 
-    if subscriptions.is_empty() {
-      return Ok(());
+    if notifications.is_empty() {
+      return Ok(notifications);
     }
 
     self
       .database
       .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
-      .insert_many(
-        subscriptions
-          .into_iter()
-          .map(|subscription| Notification {
-            review: review.clone(),
-            seen: false,
-            user_id: subscription.user_id,
-          })
-          .collect::<Vec<Notification>>(),
-        None,
-      )
+      .insert_many(&notifications, None)
       .await?;
 
-    Ok(())
+    Ok(notifications)
   }
 
   pub async fn delete_notification(
     &self,
     user_id: &str,
-    course_id: &str,
+    id: ObjectId,
   ) -> Result<DeleteResult> {
     Ok(
       self
         .database
         .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
-        .delete_one(
-          doc! {
-            "userId": user_id,
-            "review.courseId": course_id,
-          },
-          None,
-        )
+        .delete_one(doc! { "_id": id, "userId": user_id }, None)
         .await?,
     )
   }
@@ -805,8 +1125,9 @@ impl Db {
         .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
         .delete_many(
           doc! {
-            "review.userId": creator_id,
-            "review.courseId": course_id
+            "courseId": course_id,
+            "type": "review",
+            "payload.userId": creator_id,
           },
           None,
         )
@@ -823,13 +1144,7 @@ impl Db {
       self
         .database
         .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
-        .delete_many(
-          doc! {
-            "userId": user_id,
-            "review.courseId": course_id
-          },
-          None,
-        )
+        .delete_many(doc! { "courseId": course_id, "userId": user_id }, None)
         .await?,
     )
   }
@@ -846,13 +1161,15 @@ impl Db {
         .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
         .update_many(
           doc! {
-            "review.userId": creator_id,
-            "review.courseId": course_id
+            "courseId": course_id,
+            "type": "review",
+            "payload.userId": creator_id,
           },
           UpdateModifications::Document(doc! {
             "$set": {
-              "review": Into::<Bson>::into(review),
-              "seen": false
+              "payload": Into::<Bson>::into(review),
+              "seen": false,
+              "updatedAt": DateTime::now(),
             }
           }),
           None,
@@ -864,8 +1181,7 @@ impl Db {
   pub async fn update_notification(
     &self,
     user_id: &str,
-    course_id: &str,
-    creator_id: &str,
+    id: ObjectId,
     seen: bool,
   ) -> Result<UpdateResult> {
     Ok(
@@ -873,14 +1189,11 @@ impl Db {
         .database
         .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
         .update_one(
-          doc! {
-            "userId": user_id,
-            "review.courseId": course_id,
-            "review.userId": creator_id
-          },
+          doc! { "_id": id, "userId": user_id },
           UpdateModifications::Document(doc! {
             "$set": {
-              "seen": seen
+              "seen": seen,
+              "updatedAt": DateTime::now(),
             }
           }),
           None,
@@ -889,1599 +1202,3342 @@ impl Db {
     )
   }
 
-  async fn find_reviews(&self, query: Document) -> Result<Vec<Review>> {
+  pub async fn mark_all_notifications_seen(
+    &self,
+    user_id: &str,
+  ) -> Result<UpdateResult> {
     Ok(
       self
         .database
-        .collection::<Review>(Self::REVIEW_COLLECTION)
-        .find(query, None)
-        .await?
-        .try_collect::<Vec<Review>>()
-        .await?,
-    )
-  }
-
-  async fn find_course(&self, query: Document) -> Result<Option<Course>> {
-    Ok(
-      self
-        .database
-        .collection::<Course>(Self::COURSE_COLLECTION)
-        .find_one(query, None)
+        .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
+        .update_many(
+          doc! { "userId": user_id, "seen": false },
+          UpdateModifications::Document(doc! {
+            "$set": {
+              "seen": true,
+              "updatedAt": DateTime::now(),
+            }
+          }),
+          None,
+        )
         .await?,
     )
   }
 
-  pub(crate) async fn add_course(&self, course: Course) -> Result {
-    match self.find_course(doc! { "_id": &course.id }).await? {
-      Some(found) => {
-        self
-          .update_course(
-            doc! { "_id": &course.id },
-            doc! {
-              "$set": {
-                "code": course.code,
-                "corequisites": course.corequisites,
-                "corequisitesText": course.corequisites_text,
-                "credits": course.credits,
-                "department": course.department,
-                "description": course.description,
-                "faculty": course.faculty,
-                "facultyUrl": course.faculty_url,
-                "instructors": course.instructors.combine(found.instructors),
-                "leadingTo": course.leading_to,
-                "level": course.level,
-                "logicalCorequisites": course.logical_corequisites,
-                "logicalPrerequisites": course.logical_prerequisites,
-                "prerequisites": course.prerequisites,
-                "prerequisitesText": course.prerequisites_text,
-                "restrictions": course.restrictions,
-                "schedule": course.schedule,
-                "subject": course.subject,
-                "terms": course.terms.combine(found.terms),
-                "title": course.title.clone(),
-                "titleNgrams": course.title.filter_stopwords().ngrams(),
-                "url": course.url,
-              }
-            },
-          )
-          .await?;
-      }
-      None => {
-        self
-          .database
-          .collection::<Course>(Self::COURSE_COLLECTION)
-          .insert_one(
-            Course {
-              id_ngrams: Some(course.id.ngrams()),
-              title_ngrams: Some(course.title.filter_stopwords().ngrams()),
-              ..course
-            },
-            None,
-          )
-          .await?;
-      }
-    }
-
-    Ok(())
-  }
-
-  async fn update_course(
+  pub async fn get_notification_preferences(
     &self,
-    query: Document,
-    update: Document,
-  ) -> Result<UpdateResult> {
+    user_id: &str,
+  ) -> Result<NotificationPreferences> {
     Ok(
       self
         .database
-        .collection::<Course>(Self::COURSE_COLLECTION)
-        .update_one(query, UpdateModifications::Document(update), None)
-        .await?,
+        .collection::<NotificationPreferences>(
+          Self::NOTIFICATION_PREFERENCES_COLLECTION,
+        )
+        .find_one(doc! { "userId": user_id }, None)
+        .await?
+        .unwrap_or_else(|| NotificationPreferences {
+          user_id: user_id.into(),
+          ..Default::default()
+        }),
     )
   }
 
-  async fn text_search<T>(
+  pub async fn set_notification_preferences(
     &self,
-    collection: &str,
-    query: &str,
-    limit: i64,
-  ) -> Result<Cursor<T>>
-  where
-    T: Serialize + DeserializeOwned,
-  {
+    preferences: NotificationPreferences,
+  ) -> Result<UpdateResult> {
     Ok(
       self
         .database
-        .collection::<T>(collection)
-        .find(
-          doc! {
-            "$text": {
-              "$search": query
+        .collection::<NotificationPreferences>(
+          Self::NOTIFICATION_PREFERENCES_COLLECTION,
+        )
+        .update_one(
+          doc! { "userId": &preferences.user_id },
+          UpdateModifications::Document(doc! {
+            "$set": {
+              "digest": bson::to_bson(&preferences.digest)?,
+              "mutedCourses": &preferences.muted_courses,
+              "mutedTypes": bson::to_bson(&preferences.muted_types)?,
             }
-          },
-          FindOptions::builder()
-            .sort(doc! { "score": { "$meta" : "textScore" }})
-            .limit(limit)
-            .build(),
+          }),
+          UpdateOptions::builder().upsert(true).build(),
         )
         .await?,
     )
   }
 
-  pub(crate) async fn create_index<T>(
+  pub async fn record_mail(
     &self,
-    collection: &str,
-    keys: Document,
-    weights: Document,
-  ) -> Result<CreateIndexResult>
-  where
-    T: Serialize + DeserializeOwned,
-  {
+    user_id: &str,
+    mail: &str,
+  ) -> Result<UpdateResult> {
     Ok(
       self
         .database
-        .collection::<T>(collection)
-        .create_index(
-          IndexModel::builder()
-            .keys(keys)
-            .options(IndexOptions::builder().weights(weights).build())
-            .build(),
-          None,
+        .collection::<NotificationPreferences>(
+          Self::NOTIFICATION_PREFERENCES_COLLECTION,
+        )
+        .update_one(
+          doc! { "userId": user_id },
+          UpdateModifications::Document(doc! { "$set": { "mail": mail } }),
+          UpdateOptions::builder().upsert(true).build(),
         )
         .await?,
     )
   }
 
-  pub(crate) async fn add_instructor(&self, instructor: Instructor) -> Result {
-    if self
-      .find_instructor_by_name(&instructor.name)
-      .await?
-      .is_none()
-    {
+  pub async fn digest_recipients(
+    &self,
+  ) -> Result<Vec<NotificationPreferences>> {
+    Ok(
       self
         .database
-        .collection::<Instructor>(Self::INSTRUCTOR_COLLECTION)
-        .insert_one(
-          Instructor {
-            name_ngrams: Some(instructor.name.ngrams()),
-            ..instructor
+        .collection::<NotificationPreferences>(
+          Self::NOTIFICATION_PREFERENCES_COLLECTION,
+        )
+        .find(
+          doc! {
+            "mail": { "$exists": true },
+            "digest": { "$in": ["daily", "weekly"] },
           },
           None,
         )
-        .await?;
-    };
-
-    Ok(())
-  }
-
-  async fn find_instructor(
-    &self,
-    query: Document,
-  ) -> Result<Option<Instructor>> {
-    Ok(
-      self
-        .database
-        .collection::<Instructor>(Self::INSTRUCTOR_COLLECTION)
-        .find_one(query, None)
+        .await?
+        .try_collect::<Vec<NotificationPreferences>>()
         .await?,
     )
   }
 
-  pub async fn find_instructor_by_name(
-    &self,
-    name: &str,
-  ) -> Result<Option<Instructor>> {
-    self.find_instructor(doc! { "name": name }).await
-  }
-
-  pub async fn reviews(
+  pub async fn unseen_notifications(
     &self,
-    limit: Option<i64>,
-    offset: Option<u64>,
-    filter: Option<ReviewFilter>,
-  ) -> Result<Vec<Review>> {
-    let (mut document, mut sort_document) = (Document::new(), Document::new());
-
-    if let Some(filter) = filter {
-      let ReviewFilter {
-        course_id,
-        instructor_name,
-        sorted,
-        user_id,
-      } = filter;
-
-      if let Some(course_id) = course_id {
-        document.insert("courseId", course_id);
-      }
-
-      if let Some(user_id) = user_id {
-        document.insert("userId", user_id);
-      }
-
-      if let Some(instructor_name) = instructor_name {
-        document.insert(
-          "instructors",
-          doc! { "instructors": { "$in": vec![instructor_name] } },
-        );
-      }
+    user_id: &str,
+    since: Option<DateTime>,
+  ) -> Result<Vec<Notification>> {
+    let mut query = doc! { "seen": false, "userId": user_id };
 
-      if sorted.unwrap_or(false) {
-        sort_document.insert("timestamp", -1);
-      }
+    if let Some(since) = since {
+      query.insert("createdAt", doc! { "$gt": since });
     }
 
     Ok(
       self
         .database
-        .collection::<Review>(Self::REVIEW_COLLECTION)
+        .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
         .find(
-          (!document.is_empty()).then_some(document),
+          query,
           FindOptions::builder()
-            .sort((!sort_document.is_empty()).then_some(sort_document))
-            .skip(offset)
-            .limit(limit)
+            .sort(doc! { "createdAt": 1, "_id": 1 })
             .build(),
         )
         .await?
-        .try_collect::<Vec<Review>>()
+        .try_collect::<Vec<Notification>>()
         .await?,
     )
   }
 
-  pub async fn unique_user_count(&self) -> Result<u64> {
-    // Timestamp of https://github.com/terror/mcgill.courses/pull/500
-    let rmp_scrape_epoch: chrono::DateTime<Utc> =
-      Utc.timestamp_opt(1713472800, 0).unwrap();
-
-    let reviews = self
-      .database
-      .collection::<Review>(Self::REVIEW_COLLECTION)
-      .distinct(
-        "userId",
-        doc! {
-            "timestamp": { "$gte": rmp_scrape_epoch }
-        },
-        None,
-      )
-      .await?;
-
-    Ok(reviews.len().try_into()?)
-  }
-
-  #[cfg(test)]
-  async fn instructors(&self) -> Result<Vec<Instructor>> {
+  pub async fn record_digest(
+    &self,
+    user_id: &str,
+    sent_at: DateTime,
+  ) -> Result<UpdateResult> {
     Ok(
       self
         .database
-        .collection::<Instructor>(Self::INSTRUCTOR_COLLECTION)
-        .find(None, None)
-        .await?
-        .try_collect::<Vec<Instructor>>()
+        .collection::<NotificationPreferences>(
+          Self::NOTIFICATION_PREFERENCES_COLLECTION,
+        )
+        .update_one(
+          doc! { "userId": user_id },
+          UpdateModifications::Document(doc! {
+            "$set": { "lastDigestAt": sent_at }
+          }),
+          None,
+        )
         .await?,
     )
   }
 
-  #[cfg(test)]
-  async fn subscriptions(&self) -> Result<Vec<Subscription>> {
-    Ok(
-      self
-        .database
-        .collection::<Subscription>(Self::SUBSCRIPTION_COLLECTION)
-        .find(None, None)
-        .await?
-        .try_collect::<Vec<Subscription>>()
+  pub async fn export_user(&self, user_id: &str) -> Result<UserExport> {
+    Ok(UserExport {
+      exported_at: DateTime::now(),
+      interactions: self.votes(user_id).await?,
+      notification_preferences: self
+        .get_notification_preferences(user_id)
         .await?,
-    )
+      notifications: self.get_notifications(user_id, None, None).await?,
+      reviews: self.find_reviews_by_user_id(user_id).await?,
+      sessions: self.sessions(user_id).await?,
+      subscriptions: self.get_subscriptions(user_id).await?,
+      user_id: user_id.into(),
+    })
   }
 
-  #[cfg(test)]
-  async fn notifications(&self) -> Result<Vec<Notification>> {
-    Ok(
-      self
-        .database
-        .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
-        .find(None, None)
-        .await?
-        .try_collect::<Vec<Notification>>()
-        .await?,
-    )
-  }
-}
+  pub async fn delete_user(&self, user_id: &str) -> Result {
+    let reviews = self.find_reviews_by_user_id(user_id).await?;
 
-#[cfg(test)]
-mod tests {
-  use {super::*, pretty_assertions::assert_eq, regex::Regex};
+    for review in &reviews {
+      self.delete_review(&review.course_id, user_id).await?;
+      self.delete_interactions(&review.course_id, user_id).await?;
+    }
 
-  static SEED_DIR: Dir<'_> = include_dir!("crates/db/test-seeds");
+    let votes = self.votes(user_id).await?;
 
-  fn get_content(name: &str) -> String {
-    SEED_DIR
-      .get_file(name)
-      .unwrap()
-      .contents_utf8()
-      .unwrap()
+    for vote in &votes {
+      self
+        .delete_interaction(&vote.course_id, &vote.user_id, user_id)
+        .await?;
+    }
+
+    let subscriptions = self
+      .database
+      .collection::<Subscription>(Self::SUBSCRIPTION_COLLECTION)
+      .delete_many(doc! { "userId": user_id }, None)
+      .await?;
+
+    let notifications = self
+      .database
+      .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
+      .delete_many(
+        doc! {
+          "$or": [
+            { "userId": user_id },
+            { "payload.userId": user_id },
+            { "payload.review.userId": user_id },
+          ]
+        },
+        None,
+      )
+      .await?;
+
+    self
+      .database
+      .collection::<NotificationPreferences>(
+        Self::NOTIFICATION_PREFERENCES_COLLECTION,
+      )
+      .delete_many(doc! { "userId": user_id }, None)
+      .await?;
+
+    let sessions = self.revoke_sessions(user_id, None).await?;
+
+    let api_tokens = self
+      .database
+      .collection::<ApiToken>(Self::API_TOKEN_COLLECTION)
+      .delete_many(doc! { "userId": user_id }, None)
+      .await?;
+
+    info!(
+      "Deleted user {}: {} reviews, {} votes, {} subscriptions, {} notifications, {} sessions, {} api tokens",
+      user_id,
+      reviews.len(),
+      votes.len(),
+      subscriptions.deleted_count,
+      notifications.deleted_count,
+      sessions,
+      api_tokens.deleted_count
+    );
+
+    Ok(())
+  }
+
+  pub async fn add_session(&self, metadata: SessionMetadata) -> Result {
+    let live = self
+      .stored_sessions(&metadata.user_id)
+      .await?
+      .into_iter()
+      .map(|session| session.id)
+      .collect::<Vec<String>>();
+
+    self
+      .database
+      .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+      .delete_many(
+        doc! { "userId": &metadata.user_id, "sessionId": { "$nin": live } },
+        None,
+      )
+      .await?;
+
+    self
+      .database
+      .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+      .insert_one(metadata, None)
+      .await?;
+
+    Ok(())
+  }
+
+  /// Records activity on a session at most once a minute, pushing its expiry
+  /// out to `expires_at` when given. Returns whether anything was updated.
+  pub async fn touch_session(
+    &self,
+    session_id: &str,
+    expires_at: Option<DateTime>,
+  ) -> Result<bool> {
+    let now = DateTime::now();
+
+    let result = self
+      .database
+      .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+      .update_one(
+        doc! {
+          "sessionId": session_id,
+          "lastSeenAt": {
+            "$lt": DateTime::from_millis(now.timestamp_millis() - 60_000)
+          },
+        },
+        UpdateModifications::Document(doc! {
+          "$set": { "lastSeenAt": now }
+        }),
+        None,
+      )
+      .await?;
+
+    if result.modified_count == 0 {
+      return Ok(false);
+    }
+
+    if let Some(expires_at) = expires_at {
+      self
+        .database
+        .collection::<Document>(Self::SESSION_COLLECTION)
+        .update_one(
+          doc! { "session_id": session_id },
+          UpdateModifications::Document(doc! {
+            "$set": { "expireAt": expires_at }
+          }),
+          None,
+        )
+        .await?;
+    }
+
+    Ok(true)
+  }
+
+  pub async fn sessions(&self, user_id: &str) -> Result<Vec<ActiveSession>> {
+    let sessions = self.stored_sessions(user_id).await?;
+
+    let metadata = self
+      .database
+      .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+      .find(
+        doc! {
+          "userId": user_id,
+          "sessionId": {
+            "$in": sessions.iter().map(|session| &session.id).collect::<Vec<_>>()
+          },
+        },
+        None,
+      )
+      .await?
+      .try_collect::<Vec<SessionMetadata>>()
+      .await?;
+
+    Ok(
+      sessions
+        .into_iter()
+        .map(|session| {
+          let metadata = metadata
+            .iter()
+            .find(|metadata| metadata.session_id == session.id);
+
+          ActiveSession {
+            created_at: session.created_at,
+            expires_at: session.expires_at,
+            last_seen_at: metadata.map(|metadata| metadata.last_seen_at),
+            user_agent: metadata
+              .and_then(|metadata| metadata.user_agent.clone()),
+            id: session.id,
+          }
+        })
+        .collect(),
+    )
+  }
+
+  pub async fn revoke_session(
+    &self,
+    user_id: &str,
+    session_id: &str,
+  ) -> Result<bool> {
+    let metadata = self
+      .database
+      .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+      .delete_one(doc! { "userId": user_id, "sessionId": session_id }, None)
+      .await?;
+
+    if metadata.deleted_count == 0 {
+      return Ok(false);
+    }
+
+    let result = self
+      .database
+      .collection::<Document>(Self::SESSION_COLLECTION)
+      .delete_one(doc! { "session_id": session_id }, None)
+      .await?;
+
+    Ok(result.deleted_count == 1)
+  }
+
+  /// Revokes all of a user's sessions, optionally keeping one of them.
+  pub async fn revoke_sessions(
+    &self,
+    user_id: &str,
+    except: Option<&str>,
+  ) -> Result<u64> {
+    let session_ids = self
+      .session_ids(user_id)
+      .await?
+      .into_iter()
+      .filter(|session_id| Some(session_id.as_str()) != except)
+      .collect::<Vec<String>>();
+
+    let result = self
+      .database
+      .collection::<Document>(Self::SESSION_COLLECTION)
+      .delete_many(doc! { "session_id": { "$in": &session_ids } }, None)
+      .await?;
+
+    self
+      .database
+      .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+      .delete_many(
+        doc! { "userId": user_id, "sessionId": { "$in": &session_ids } },
+        None,
+      )
+      .await?;
+
+    Ok(result.deleted_count)
+  }
+
+  /// The ids of the sessions a user signed in with, from the metadata
+  /// recorded at login.
+  async fn session_ids(&self, user_id: &str) -> Result<Vec<String>> {
+    Ok(
+      self
+        .database
+        .collection::<SessionMetadata>(Self::SESSION_METADATA_COLLECTION)
+        .find(doc! { "userId": user_id }, None)
+        .await?
+        .map_ok(|metadata| metadata.session_id)
+        .try_collect::<Vec<String>>()
+        .await?,
+    )
+  }
+
+  async fn stored_sessions(&self, user_id: &str) -> Result<Vec<StoredSession>> {
+    Ok(
+      self
+        .database
+        .collection::<StoredSession>(Self::SESSION_COLLECTION)
+        .find(
+          doc! {
+            "session_id": { "$in": self.session_ids(user_id).await? },
+            "expireAt": { "$gt": DateTime::now() },
+          },
+          FindOptions::builder()
+            .projection(
+              doc! { "_id": 0, "created": 1, "expireAt": 1, "session_id": 1 },
+            )
+            .sort(doc! { "created": 1 })
+            .build(),
+        )
+        .await?
+        .try_collect::<Vec<StoredSession>>()
+        .await?,
+    )
+  }
+
+  pub async fn add_api_token(&self, token: ApiToken) -> Result {
+    self
+      .database
+      .collection::<ApiToken>(Self::API_TOKEN_COLLECTION)
+      .insert_one(token, None)
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn api_tokens(&self, user_id: &str) -> Result<Vec<ApiToken>> {
+    Ok(
+      self
+        .database
+        .collection::<ApiToken>(Self::API_TOKEN_COLLECTION)
+        .find(
+          doc! { "userId": user_id },
+          FindOptions::builder().sort(doc! { "createdAt": 1 }).build(),
+        )
+        .await?
+        .try_collect::<Vec<ApiToken>>()
+        .await?,
+    )
+  }
+
+  /// Looks up a token by the hash of its secret, recording that it was used.
+  pub async fn use_api_token(&self, hash: &str) -> Result<Option<ApiToken>> {
+    Ok(
+      self
+        .database
+        .collection::<ApiToken>(Self::API_TOKEN_COLLECTION)
+        .find_one_and_update(
+          doc! { "hash": hash },
+          UpdateModifications::Document(doc! {
+            "$set": { "lastUsedAt": DateTime::now() }
+          }),
+          None,
+        )
+        .await?,
+    )
+  }
+
+  pub async fn revoke_api_token(
+    &self,
+    user_id: &str,
+    id: &str,
+  ) -> Result<bool> {
+    Ok(
+      self
+        .database
+        .collection::<ApiToken>(Self::API_TOKEN_COLLECTION)
+        .delete_one(doc! { "userId": user_id, "id": id }, None)
+        .await?
+        .deleted_count
+        == 1,
+    )
+  }
+
+  async fn votes(&self, user_id: &str) -> Result<Vec<Interaction>> {
+    Ok(
+      self
+        .database
+        .collection::<Interaction>(Self::INTERACTION_COLLECTION)
+        .find(doc! { "referrer": user_id }, None)
+        .await?
+        .try_collect::<Vec<Interaction>>()
+        .await?,
+    )
+  }
+
+  pub(crate) async fn migrate_notifications(&self) -> Result {
+    let collection = self
+      .database
+      .collection::<Document>(Self::NOTIFICATION_COLLECTION);
+
+    let migrated = collection
+      .update_many(
+        doc! { "type": { "$exists": false }, "review": { "$exists": true } },
+        UpdateModifications::Pipeline(vec![
+          doc! {
+            "$set": {
+              "courseId": "$review.courseId",
+              "createdAt": "$review.timestamp",
+              "payload": "$review",
+              "type": "review",
+              "updatedAt": "$review.timestamp",
+            }
+          },
+          doc! { "$unset": "review" },
+        ]),
+        None,
+      )
+      .await?;
+
+    let deleted = collection
+      .delete_many(doc! { "type": { "$exists": false } }, None)
+      .await?;
+
+    if migrated.modified_count > 0 || deleted.deleted_count > 0 {
+      info!(
+        "Migrated {} legacy notifications, deleted {} unrecognized ones",
+        migrated.modified_count, deleted.deleted_count
+      );
+    }
+
+    Ok(())
+  }
+
+  async fn find_reviews(&self, query: Document) -> Result<Vec<Review>> {
+    Ok(
+      self
+        .database
+        .collection::<Review>(Self::REVIEW_COLLECTION)
+        .find(query, None)
+        .await?
+        .try_collect::<Vec<Review>>()
+        .await?,
+    )
+  }
+
+  async fn find_course(&self, query: Document) -> Result<Option<Course>> {
+    Ok(
+      self
+        .database
+        .collection::<Course>(Self::COURSE_COLLECTION)
+        .find_one(query, None)
+        .await?,
+    )
+  }
+
+  pub(crate) async fn add_course(
+    &self,
+    course: Course,
+    source: &str,
+  ) -> Result<Vec<CourseChange>> {
+    match self.find_course(doc! { "_id": &course.id }).await? {
+      Some(found) => {
+        let updated = Course {
+          instructors: course.instructors.combine(found.instructors.clone()),
+          terms: course.terms.combine(found.terms.clone()),
+          ..course
+        };
+
+        self
+          .update_course(
+            doc! { "_id": &updated.id },
+            doc! {
+              "$set": {
+                "code": &updated.code,
+                "corequisites": &updated.corequisites,
+                "corequisitesText": &updated.corequisites_text,
+                "credits": &updated.credits,
+                "department": &updated.department,
+                "description": &updated.description,
+                "faculty": &updated.faculty,
+                "facultyUrl": &updated.faculty_url,
+                "instructors": updated.instructors.clone(),
+                "leadingTo": &updated.leading_to,
+                "level": &updated.level,
+                "logicalCorequisites": updated.logical_corequisites.clone(),
+                "logicalPrerequisites": updated.logical_prerequisites.clone(),
+                "prerequisites": &updated.prerequisites,
+                "prerequisitesText": &updated.prerequisites_text,
+                "restrictions": &updated.restrictions,
+                "schedule": updated.schedule.clone(),
+                "subject": &updated.subject,
+                "terms": &updated.terms,
+                "title": &updated.title,
+                "titleNgrams": updated.title.filter_stopwords().ngrams(),
+                "url": &updated.url,
+              }
+            },
+          )
+          .await?;
+
+        let changes = CourseChange::between(&found, &updated, source);
+
+        if !changes.is_empty() {
+          self
+            .database
+            .collection::<CourseChange>(Self::CHANGE_COLLECTION)
+            .insert_many(&changes, None)
+            .await?;
+        }
+
+        Ok(changes)
+      }
+      None => {
+        self
+          .database
+          .collection::<Course>(Self::COURSE_COLLECTION)
+          .insert_one(
+            Course {
+              id_ngrams: Some(course.id.ngrams()),
+              title_ngrams: Some(course.title.filter_stopwords().ngrams()),
+              ..course
+            },
+            None,
+          )
+          .await?;
+
+        Ok(Vec::new())
+      }
+    }
+  }
+
+  pub async fn course_changes(
+    &self,
+    course_id: &str,
+  ) -> Result<Vec<CourseChange>> {
+    Ok(
+      self
+        .database
+        .collection::<CourseChange>(Self::CHANGE_COLLECTION)
+        .find(
+          doc! { "courseId": course_id },
+          FindOptions::builder().sort(doc! { "_id": 1 }).build(),
+        )
+        .await?
+        .try_collect::<Vec<CourseChange>>()
+        .await?,
+    )
+  }
+
+  pub(crate) async fn add_course_averages(
+    &self,
+    course_averages: CourseAverages,
+  ) -> Result {
+    let CourseAverages {
+      course_id,
+      averages,
+    } = course_averages;
+
+    let Some(found) = self.find_course_by_id(&course_id).await? else {
+      warn!("Skipping averages for unknown course {}", course_id);
+      return Ok(());
+    };
+
+    let mut merged = found
+      .averages
+      .into_iter()
+      .filter(|existing| {
+        !averages.iter().any(|average| average.term == existing.term)
+      })
+      .collect::<Vec<TermAverage>>();
+
+    merged.extend(averages);
+
+    self
+      .update_course(
+        doc! { "_id": &course_id },
+        doc! { "$set": { "averages": merged } },
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  async fn update_course(
+    &self,
+    query: Document,
+    update: Document,
+  ) -> Result<UpdateResult> {
+    Ok(
+      self
+        .database
+        .collection::<Course>(Self::COURSE_COLLECTION)
+        .update_one(query, UpdateModifications::Document(update), None)
+        .await?,
+    )
+  }
+
+  async fn text_search<T>(
+    &self,
+    collection: &str,
+    query: &str,
+    limit: i64,
+  ) -> Result<Cursor<T>>
+  where
+    T: Serialize + DeserializeOwned,
+  {
+    Ok(
+      self
+        .database
+        .collection::<T>(collection)
+        .find(
+          doc! {
+            "$text": {
+              "$search": query
+            }
+          },
+          FindOptions::builder()
+            .sort(doc! { "score": { "$meta" : "textScore" }})
+            .limit(limit)
+            .build(),
+        )
+        .await?,
+    )
+  }
+
+  pub(crate) async fn create_index<T>(
+    &self,
+    collection: &str,
+    keys: Document,
+    weights: Document,
+  ) -> Result<CreateIndexResult>
+  where
+    T: Serialize + DeserializeOwned,
+  {
+    Ok(
+      self
+        .database
+        .collection::<T>(collection)
+        .create_index(
+          IndexModel::builder()
+            .keys(keys)
+            .options(IndexOptions::builder().weights(weights).build())
+            .build(),
+          None,
+        )
+        .await?,
+    )
+  }
+
+  pub(crate) async fn add_instructor(&self, instructor: Instructor) -> Result {
+    if self
+      .find_instructor_by_name(&instructor.name)
+      .await?
+      .is_none()
+    {
+      self
+        .database
+        .collection::<Instructor>(Self::INSTRUCTOR_COLLECTION)
+        .insert_one(
+          Instructor {
+            name_ngrams: Some(instructor.name.ngrams()),
+            ..instructor
+          },
+          None,
+        )
+        .await?;
+    };
+
+    Ok(())
+  }
+
+  async fn find_instructor(
+    &self,
+    query: Document,
+  ) -> Result<Option<Instructor>> {
+    Ok(
+      self
+        .database
+        .collection::<Instructor>(Self::INSTRUCTOR_COLLECTION)
+        .find_one(query, None)
+        .await?,
+    )
+  }
+
+  pub async fn find_instructor_by_name(
+    &self,
+    name: &str,
+  ) -> Result<Option<Instructor>> {
+    self.find_instructor(doc! { "name": name }).await
+  }
+
+  pub async fn reviews(
+    &self,
+    limit: Option<i64>,
+    offset: Option<u64>,
+    filter: Option<ReviewFilter>,
+  ) -> Result<Vec<Review>> {
+    let (mut document, mut sort_document) = (Document::new(), Document::new());
+
+    if let Some(filter) = filter {
+      let ReviewFilter {
+        course_id,
+        instructor_name,
+        sorted,
+        user_id,
+      } = filter;
+
+      if let Some(course_id) = course_id {
+        document.insert("courseId", course_id);
+      }
+
+      if let Some(user_id) = user_id {
+        document.insert("userId", user_id);
+      }
+
+      if let Some(instructor_name) = instructor_name {
+        document.insert(
+          "instructors",
+          doc! { "instructors": { "$in": vec![instructor_name] } },
+        );
+      }
+
+      if sorted.unwrap_or(false) {
+        sort_document.insert("timestamp", -1);
+      }
+    }
+
+    Ok(
+      self
+        .database
+        .collection::<Review>(Self::REVIEW_COLLECTION)
+        .find(
+          (!document.is_empty()).then_some(document),
+          FindOptions::builder()
+            .sort((!sort_document.is_empty()).then_some(sort_document))
+            .skip(offset)
+            .limit(limit)
+            .build(),
+        )
+        .await?
+        .try_collect::<Vec<Review>>()
+        .await?,
+    )
+  }
+
+  pub async fn unique_user_count(&self) -> Result<u64> {
+    // Timestamp of https://github.com/terror/mcgill.courses/pull/500
+    let rmp_scrape_epoch: chrono::DateTime<Utc> =
+      Utc.timestamp_opt(1713472800, 0).unwrap();
+
+    let reviews = self
+      .database
+      .collection::<Review>(Self::REVIEW_COLLECTION)
+      .distinct(
+        "userId",
+        doc! {
+            "timestamp": { "$gte": rmp_scrape_epoch }
+        },
+        None,
+      )
+      .await?;
+
+    Ok(reviews.len().try_into()?)
+  }
+
+  #[cfg(test)]
+  async fn instructors(&self) -> Result<Vec<Instructor>> {
+    Ok(
+      self
+        .database
+        .collection::<Instructor>(Self::INSTRUCTOR_COLLECTION)
+        .find(None, None)
+        .await?
+        .try_collect::<Vec<Instructor>>()
+        .await?,
+    )
+  }
+
+  #[cfg(test)]
+  async fn subscriptions(&self) -> Result<Vec<Subscription>> {
+    Ok(
+      self
+        .database
+        .collection::<Subscription>(Self::SUBSCRIPTION_COLLECTION)
+        .find(None, None)
+        .await?
+        .try_collect::<Vec<Subscription>>()
+        .await?,
+    )
+  }
+
+  #[cfg(test)]
+  async fn notifications(&self) -> Result<Vec<Notification>> {
+    Ok(
+      self
+        .database
+        .collection::<Notification>(Self::NOTIFICATION_COLLECTION)
+        .find(None, None)
+        .await?
+        .try_collect::<Vec<Notification>>()
+        .await?,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq, regex::Regex};
+
+  static SEED_DIR: Dir<'_> = include_dir!("crates/db/test-seeds");
+
+  fn get_content(name: &str) -> String {
+    SEED_DIR
+      .get_file(name)
+      .unwrap()
+      .contents_utf8()
+      .unwrap()
       .to_string()
   }
 
-  struct TestContext {
-    db: Db,
-    db_name: String,
+  struct TestContext {
+    db: Db,
+    db_name: String,
+  }
+
+  impl TestContext {
+    async fn new() -> Self {
+      static TEST_DATABASE_NUMBER: AtomicUsize = AtomicUsize::new(0);
+
+      let test_database_number =
+        TEST_DATABASE_NUMBER.fetch_add(1, Ordering::Relaxed);
+
+      let db_name = format!(
+        "mcgill-courses-test-{}-{}",
+        std::time::SystemTime::now()
+          .duration_since(std::time::SystemTime::UNIX_EPOCH)
+          .unwrap()
+          .as_millis(),
+        test_database_number,
+      );
+
+      let db = Db::connect(&db_name).await.unwrap();
+
+      TestContext { db, db_name }
+    }
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn on_disk_database_is_persistent() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 0);
+
+    db.add_course(Course::default(), "courses.json")
+      .await
+      .unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 1);
+
+    drop(db);
+
+    let db = Db::connect(&db_name).await.unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 1);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn course_seeding_is_accurate() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("before_update.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 2);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn initialization_reports_progress() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    fs::write(
+      tempdir.path().join("courses.json"),
+      get_content("before_update.json"),
+    )
+    .unwrap();
+
+    fs::write(tempdir.path().join("notes.txt"), "").unwrap();
+
+    assert_eq!(db.initialization().state, InitializationState::Idle);
+    assert!(!db.indexed().await.unwrap());
+
+    let initialize = db.initialize(InitializeOptions {
+      source: tempdir.path().into(),
+      ..Default::default()
+    });
+
+    assert_eq!(db.initialization().state, InitializationState::Pending);
+
+    initialize.await.unwrap();
+
+    db.ping().await.unwrap();
+    assert!(db.indexed().await.unwrap());
+
+    let status = db.initialization();
+
+    assert_eq!(status.state, InitializationState::Complete);
+    assert!(status.started_at.is_some() && status.finished_at.is_some());
+    assert_eq!(status.error, None);
+
+    assert_eq!(
+      status.seeds,
+      vec![
+        SeedProgress {
+          completed: 2,
+          file: "courses.json".into(),
+          kind: SeedKind::Courses,
+          state: SeedState::Complete,
+          total: 2,
+          ..Default::default()
+        },
+        SeedProgress {
+          file: "notes.txt".into(),
+          kind: SeedKind::Unknown,
+          state: SeedState::Skipped,
+          ..Default::default()
+        },
+      ]
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn course_seeding_does_not_insert_duplicates() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(
+      &source,
+      serde_json::to_string(
+        &(0..10).map(|_| Course::default()).collect::<Vec<Course>>(),
+      )
+      .unwrap(),
+    )
+    .unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 1);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn courses_get_updated_when_seeding() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("before_update.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source: source.clone(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 2);
+
+    fs::write(&source, get_content("update.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let courses = db.courses(None, None, None).await.unwrap();
+
+    assert_eq!(courses.len(), 3);
+
+    assert_eq!(
+      courses,
+      serde_json::from_str::<Vec<Course>>(&get_content("after_update.json"))
+        .unwrap()
+        .into_iter()
+        .map(|c| Course {
+          id_ngrams: Some(c.id.ngrams()),
+          title_ngrams: Some(c.title.filter_stopwords().ngrams()),
+          ..c
+        })
+        .collect::<Vec<Course>>()
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn course_changes_are_recorded_when_seeding() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let (before, after) = (
+      tempdir.path().join("courses-2022-2023.json"),
+      tempdir.path().join("courses-2023-2024.json"),
+    );
+
+    fs::write(&before, get_content("before_update.json")).unwrap();
+    fs::write(&after, get_content("update.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source: before,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert!(db.course_changes("COMP251").await.unwrap().is_empty());
+
+    db.initialize(InitializeOptions {
+      source: after.clone(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let changes = db.course_changes("COMP251").await.unwrap();
+
+    assert_eq!(
+      changes
+        .iter()
+        .map(|change| change.field.as_str())
+        .collect::<Vec<&str>>(),
+      vec![
+        "corequisites",
+        "instructors",
+        "prerequisites",
+        "restrictions",
+        "schedule",
+        "terms"
+      ]
+    );
+
+    assert!(changes
+      .iter()
+      .all(|change| change.source == "courses-2023-2024.json"));
+
+    let prerequisites = changes
+      .iter()
+      .find(|change| change.field == "prerequisites")
+      .unwrap();
+
+    assert_eq!(
+      prerequisites.old_value,
+      serde_json::json!(["COMP 250", "MATH 235", "MATH 240"])
+    );
+
+    assert_eq!(prerequisites.new_value, serde_json::json!(["COMP 250"]));
+
+    assert!(db.course_changes("MATH553").await.unwrap().is_empty());
+
+    db.initialize(InitializeOptions {
+      source: after,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.course_changes("COMP251").await.unwrap().len(), 6);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn course_averages_get_merged_when_seeding() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    fs::write(
+      tempdir.path().join("courses.json"),
+      get_content("mini.json"),
+    )
+    .unwrap();
+
+    let write_averages = |averages: Vec<CourseAverages>| {
+      fs::write(
+        tempdir.path().join("averages.json"),
+        serde_json::to_string(&averages).unwrap(),
+      )
+      .unwrap();
+    };
+
+    write_averages(vec![
+      CourseAverages {
+        course_id: "MATH240".into(),
+        averages: vec![
+          TermAverage {
+            term: "Fall 2022".into(),
+            average: "B".into(),
+          },
+          TermAverage {
+            term: "Winter 2023".into(),
+            average: "B+".into(),
+          },
+        ],
+      },
+      CourseAverages {
+        course_id: "COMP1337".into(),
+        averages: vec![TermAverage {
+          term: "Fall 2022".into(),
+          average: "A".into(),
+        }],
+      },
+    ]);
+
+    db.initialize(InitializeOptions {
+      source: tempdir.path().to_path_buf(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+      db.find_course_by_id("MATH240")
+        .await
+        .unwrap()
+        .unwrap()
+        .averages
+        .len(),
+      2
+    );
+
+    assert_eq!(db.find_course_by_id("COMP1337").await.unwrap(), None);
+
+    write_averages(vec![CourseAverages {
+      course_id: "MATH240".into(),
+      averages: vec![
+        TermAverage {
+          term: "Winter 2023".into(),
+          average: "A-".into(),
+        },
+        TermAverage {
+          term: "Fall 2023".into(),
+          average: "C+".into(),
+        },
+      ],
+    }]);
+
+    db.initialize(InitializeOptions {
+      source: tempdir.path().to_path_buf(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+      db.find_course_by_id("MATH240")
+        .await
+        .unwrap()
+        .unwrap()
+        .averages,
+      vec![
+        TermAverage {
+          term: "Fall 2022".into(),
+          average: "B".into(),
+        },
+        TermAverage {
+          term: "Winter 2023".into(),
+          average: "A-".into(),
+        },
+        TermAverage {
+          term: "Fall 2023".into(),
+          average: "C+".into(),
+        },
+      ]
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn search_is_accurate() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("search.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
+
+    let results = db.search("COMP 202").await.unwrap();
+
+    assert_eq!(results.courses.len(), 4);
+
+    let first = results.courses.first().unwrap();
+
+    assert_eq!(first.subject, "COMP");
+    assert_eq!(first.code, "202");
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn get_course_by_id() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("search.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let courses = db.courses(None, None, None).await.unwrap();
+
+    assert_eq!(courses.len(), 123);
+
+    let first = courses.first().unwrap();
+
+    assert_eq!(
+      db.find_course_by_id(&first.id).await.unwrap().unwrap(),
+      *first
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn search_course_by_id_exact() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("search.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
+
+    let results = db.search("COMP202").await.unwrap();
+
+    assert_eq!(results.courses.len(), 1);
+
+    let first = results.courses.first().unwrap();
+
+    assert_eq!(first.subject, "COMP");
+    assert_eq!(first.code, "202");
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn fuzzy_search_course_by_title() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("search.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
+
+    let results = db.search("foundations of").await.unwrap();
+
+    assert_eq!(results.courses.len(), 1);
+
+    let first = results.courses.first().unwrap();
+
+    assert_eq!(first.subject, "COMP");
+    assert_eq!(first.code, "202");
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn get_courses_with_limit() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("search.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(db.courses(Some(10), None, None).await.unwrap().len(), 10);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn get_courses_with_sort_filter() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("search.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    db.add_review(Review {
+      course_id: "COMP252".into(),
+      user_id: "1".into(),
+      rating: 5,
+      difficulty: 4,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    db.add_review(Review {
+      course_id: "COMP252".into(),
+      user_id: "2".into(),
+      rating: 4,
+      difficulty: 3,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    db.add_review(Review {
+      course_id: "COMP362".into(),
+      user_id: "1".into(),
+      rating: 3,
+      difficulty: 5,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    db.add_review(Review {
+      course_id: "COMP362".into(),
+      user_id: "2".into(),
+      rating: 4,
+      difficulty: 4,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let courses = db
+      .courses(
+        Some(10),
+        None,
+        Some(CourseFilter {
+          sort_by: Some(CourseSort {
+            sort_type: CourseSortType::Rating,
+            reverse: true,
+          }),
+          ..Default::default()
+        }),
+      )
+      .await
+      .unwrap();
+
+    assert!(
+      db.find_course_by_id("COMP252")
+        .await
+        .unwrap()
+        .unwrap()
+        .avg_rating
+        - 4.5
+        < 0.01,
+    );
+
+    assert_eq!(courses[0].id, "COMP252");
+    assert_eq!(courses[1].id, "COMP362");
+
+    let courses = db
+      .courses(
+        Some(10),
+        None,
+        Some(CourseFilter {
+          sort_by: Some(CourseSort {
+            sort_type: CourseSortType::Difficulty,
+            reverse: true,
+          }),
+          ..Default::default()
+        }),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(courses[0].id, "COMP362");
+    assert_eq!(courses[1].id, "COMP252");
+
+    db.add_review(Review {
+      course_id: "COMP400".into(),
+      user_id: "1".into(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    db.add_review(Review {
+      course_id: "COMP400".into(),
+      user_id: "2".into(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    db.add_review(Review {
+      course_id: "COMP400".into(),
+      user_id: "3".into(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let courses = db
+      .courses(
+        Some(10),
+        None,
+        Some(CourseFilter {
+          sort_by: Some(CourseSort {
+            sort_type: CourseSortType::ReviewCount,
+            reverse: true,
+          }),
+          ..Default::default()
+        }),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(courses[0].id, "COMP400");
+    assert_eq!(courses[0].review_count, 3);
+    assert_eq!(courses[1].review_count, 2);
+    assert_eq!(courses[2].review_count, 2);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn add_reviews() {
+    let TestContext { db, .. } = TestContext::new().await;
+
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
+
+    let reviews = vec![
+      Review {
+        content: "foo".into(),
+        course_id: "MATH240".into(),
+        instructors: vec![String::from("test")],
+        rating: 5,
+        difficulty: 2,
+        user_id: "1".into(),
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        course_id: "MATH240".into(),
+        instructors: vec![String::from("test")],
+        rating: 4,
+        difficulty: 3,
+        user_id: "2".into(),
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        course_id: "MATH240".into(),
+        instructors: vec![String::from("test")],
+        rating: 3,
+        difficulty: 2,
+        user_id: "3".into(),
+        ..Default::default()
+      },
+    ];
+
+    for review in &reviews {
+      db.add_review(review.clone()).await.unwrap();
+    }
+
+    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
+    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
+
+    let course = db.find_course_by_id("MATH240").await.unwrap().unwrap();
+    assert!(course.avg_rating - 4.0 < 0.001);
+    assert!(course.avg_difficulty - 2.33 < 0.1);
   }
 
-  impl TestContext {
-    async fn new() -> Self {
-      static TEST_DATABASE_NUMBER: AtomicUsize = AtomicUsize::new(0);
+  #[tokio::test(flavor = "multi_thread")]
+  async fn find_reviews_by_course_id() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-      let test_database_number =
-        TEST_DATABASE_NUMBER.fetch_add(1, Ordering::Relaxed);
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
 
-      let db_name = format!(
-        "mcgill-courses-test-{}-{}",
-        std::time::SystemTime::now()
-          .duration_since(std::time::SystemTime::UNIX_EPOCH)
-          .unwrap()
-          .as_millis(),
-        test_database_number,
-      );
+    db.add_course(
+      Course {
+        id: "MATH340".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
 
-      let db = Db::connect(&db_name).await.unwrap();
+    let reviews = vec![
+      Review {
+        content: "foo".into(),
+        user_id: "1".into(),
+        instructors: vec![String::from("test")],
+        rating: 5,
+        course_id: "MATH240".into(),
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        user_id: "2".into(),
+        instructors: vec![String::from("test")],
+        rating: 5,
+        course_id: "MATH240".into(),
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        user_id: "3".into(),
+        instructors: vec![String::from("test")],
+        rating: 5,
+        course_id: "MATH340".into(),
+        ..Default::default()
+      },
+    ];
 
-      TestContext { db, db_name }
+    for review in &reviews {
+      db.add_review(review.clone()).await.unwrap();
+    }
+
+    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
+    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
+
+    assert_eq!(
+      db.find_reviews_by_course_id("MATH240").await.unwrap(),
+      vec![
+        Review {
+          content: "foo".into(),
+          user_id: "1".into(),
+          instructors: vec![String::from("test")],
+          rating: 5,
+          course_id: "MATH240".into(),
+          ..Default::default()
+        },
+        Review {
+          content: "foo".into(),
+          course_id: "MATH240".into(),
+          instructors: vec![String::from("test")],
+          rating: 5,
+          user_id: "2".into(),
+          ..Default::default()
+        }
+      ]
+    )
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn find_reviews_by_user_id() {
+    let TestContext { db, .. } = TestContext::new().await;
+
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
+
+    db.add_course(
+      Course {
+        id: "MATH340".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
+
+    let reviews = vec![
+      Review {
+        content: "foo".into(),
+        user_id: "1".into(),
+        course_id: "MATH240".into(),
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        user_id: "2".into(),
+        course_id: "MATH240".into(),
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        user_id: "3".into(),
+        course_id: "MATH340".into(),
+        ..Default::default()
+      },
+    ];
+
+    for review in &reviews {
+      db.add_review(review.clone()).await.unwrap();
     }
+
+    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
+    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
+
+    assert_eq!(
+      db.find_reviews_by_user_id("2").await.unwrap(),
+      vec![Review {
+        content: "foo".into(),
+        user_id: "2".into(),
+        course_id: "MATH240".into(),
+        ..Default::default()
+      },]
+    )
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn on_disk_database_is_persistent() {
-    let TestContext { db, db_name } = TestContext::new().await;
+  async fn find_reviews_by_user_instructor_name() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 0);
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
 
-    db.add_course(Course::default()).await.unwrap();
+    db.add_course(
+      Course {
+        id: "MATH340".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 1);
+    let reviews = vec![
+      Review {
+        content: "foo".into(),
+        user_id: "1".into(),
+        course_id: "MATH240".into(),
+        instructors: vec![
+          String::from("test"),
+          String::from("foo"),
+          String::from("bar"),
+        ],
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        user_id: "2".into(),
+        course_id: "MATH240".into(),
+        instructors: vec![String::from("test"), String::from("foo")],
+        ..Default::default()
+      },
+      Review {
+        content: "foo".into(),
+        user_id: "3".into(),
+        course_id: "MATH340".into(),
+        instructors: vec![String::from("foo"), String::from("bar")],
+        ..Default::default()
+      },
+    ];
 
-    drop(db);
+    for review in &reviews {
+      db.add_review(review.clone()).await.unwrap();
+    }
 
-    let db = Db::connect(&db_name).await.unwrap();
+    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
+    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 1);
+    assert_eq!(
+      db.find_reviews_by_instructor_name("test").await.unwrap(),
+      vec![
+        Review {
+          content: "foo".into(),
+          user_id: "1".into(),
+          course_id: "MATH240".into(),
+          instructors: vec![
+            String::from("test"),
+            String::from("foo"),
+            String::from("bar"),
+          ],
+          ..Default::default()
+        },
+        Review {
+          content: "foo".into(),
+          user_id: "2".into(),
+          course_id: "MATH240".into(),
+          instructors: vec![String::from("test"), String::from("foo")],
+          ..Default::default()
+        },
+      ]
+    )
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn course_seeding_is_accurate() {
-    let TestContext { db, db_name } = TestContext::new().await;
-
-    let tempdir = TempDir::new(&db_name).unwrap();
-
-    let source = tempdir.path().join("courses.json");
-
-    fs::write(&source, get_content("before_update.json")).unwrap();
+  async fn review_stats() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    db.initialize(InitializeOptions {
-      source,
-      ..Default::default()
-    })
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
     .await
     .unwrap();
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 2);
-  }
-
-  #[tokio::test(flavor = "multi_thread")]
-  async fn course_seeding_does_not_insert_duplicates() {
-    let TestContext { db, db_name } = TestContext::new().await;
+    let reviews = vec![
+      Review {
+        course_id: "MATH240".into(),
+        instructors: vec!["foo".into()],
+        rating: 5,
+        difficulty: 1,
+        timestamp: DateTime::from_chrono(
+          Utc.with_ymd_and_hms(2022, 9, 1, 0, 0, 0).unwrap(),
+        ),
+        user_id: "1".into(),
+        ..Default::default()
+      },
+      Review {
+        course_id: "MATH240".into(),
+        instructors: vec!["foo".into()],
+        rating: 1,
+        difficulty: 5,
+        timestamp: DateTime::from_chrono(
+          Utc.with_ymd_and_hms(2023, 2, 1, 0, 0, 0).unwrap(),
+        ),
+        user_id: "2".into(),
+        ..Default::default()
+      },
+      Review {
+        course_id: "MATH240".into(),
+        instructors: vec!["bar".into()],
+        rating: 4,
+        difficulty: 2,
+        timestamp: DateTime::from_chrono(
+          Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap(),
+        ),
+        user_id: "3".into(),
+        ..Default::default()
+      },
+    ];
 
-    let tempdir = TempDir::new(&db_name).unwrap();
+    for review in reviews {
+      db.add_review(review).await.unwrap();
+    }
 
-    let source = tempdir.path().join("courses.json");
+    let stats = db.review_stats("MATH240", None).await.unwrap();
 
-    fs::write(
-      &source,
-      serde_json::to_string(
-        &(0..10).map(|_| Course::default()).collect::<Vec<Course>>(),
-      )
-      .unwrap(),
-    )
-    .unwrap();
+    assert_eq!(stats.review_count, 3);
+    assert_eq!(stats.rating_distribution, vec![1, 0, 0, 1, 1]);
+    assert_eq!(stats.difficulty_distribution, vec![1, 1, 0, 0, 1]);
 
-    db.initialize(InitializeOptions {
-      source,
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    assert_eq!(
+      stats.yearly_averages,
+      vec![
+        YearlyAverage {
+          academic_year: "2022-2023".into(),
+          avg_difficulty: 3.0,
+          avg_rating: 3.0,
+          review_count: 2,
+        },
+        YearlyAverage {
+          academic_year: "2023-2024".into(),
+          avg_difficulty: 2.0,
+          avg_rating: 4.0,
+          review_count: 1,
+        },
+      ]
+    );
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 1);
-  }
+    let stats = db.review_stats("MATH240", Some("bar")).await.unwrap();
 
-  #[tokio::test(flavor = "multi_thread")]
-  async fn courses_get_updated_when_seeding() {
-    let TestContext { db, db_name } = TestContext::new().await;
+    assert_eq!(stats.review_count, 1);
+    assert_eq!(stats.rating_distribution, vec![0, 0, 0, 1, 0]);
+    assert_eq!(stats.yearly_averages.len(), 1);
 
-    let tempdir = TempDir::new(&db_name).unwrap();
+    let stats = db.review_stats("COMP202", None).await.unwrap();
 
-    let source = tempdir.path().join("courses.json");
+    assert_eq!(stats.review_count, 0);
+    assert_eq!(stats.rating_distribution, vec![0; 5]);
+    assert!(stats.yearly_averages.is_empty());
+  }
 
-    fs::write(&source, get_content("before_update.json")).unwrap();
+  #[tokio::test(flavor = "multi_thread")]
+  async fn dont_add_multiple_reviews_per_user() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    db.initialize(InitializeOptions {
-      source: source.clone(),
-      ..Default::default()
-    })
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
     .await
     .unwrap();
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 2);
-
-    fs::write(&source, get_content("update.json")).unwrap();
-
-    db.initialize(InitializeOptions {
-      source,
+    let review = Review {
+      user_id: "1".into(),
+      course_id: "MATH240".into(),
       ..Default::default()
-    })
-    .await
-    .unwrap();
-
-    let courses = db.courses(None, None, None).await.unwrap();
+    };
 
-    assert_eq!(courses.len(), 3);
+    for _ in 0..10 {
+      db.add_review(review.clone()).await.unwrap();
+    }
 
-    assert_eq!(
-      courses,
-      serde_json::from_str::<Vec<Course>>(&get_content("after_update.json"))
-        .unwrap()
-        .into_iter()
-        .map(|c| Course {
-          id_ngrams: Some(c.id.ngrams()),
-          title_ngrams: Some(c.title.filter_stopwords().ngrams()),
-          ..c
-        })
-        .collect::<Vec<Course>>()
-    );
+    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 1);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn search_is_accurate() {
-    let TestContext { db, db_name } = TestContext::new().await;
-
-    let tempdir = TempDir::new(&db_name).unwrap();
-
-    let source = tempdir.path().join("courses.json");
+  async fn update_review() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    fs::write(&source, get_content("search.json")).unwrap();
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
 
-    db.initialize(InitializeOptions {
-      source,
-      ..Default::default()
+    db.add_review(Review {
+      content: "foo".into(),
+      course_id: "MATH240".into(),
+      instructors: vec![String::from("bar")],
+      rating: 5,
+      difficulty: 5,
+      user_id: "1".into(),
+      timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
+      ..Review::default()
     })
     .await
     .unwrap();
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
+    let timestamp = DateTime::from_chrono::<Utc>(Utc::now());
 
-    let results = db.search("COMP 202").await.unwrap();
+    assert_eq!(
+      db.add_review(Review {
+        content: "bar".into(),
+        course_id: "MATH240".into(),
+        instructors: vec![String::from("foo")],
+        rating: 4,
+        difficulty: 4,
+        user_id: "1".into(),
+        timestamp,
+        ..Review::default()
+      })
+      .await
+      .unwrap()
+      .modified_count,
+      1
+    );
 
-    assert_eq!(results.courses.len(), 4);
+    assert_eq!(
+      db.add_review(Review {
+        content: "bar".into(),
+        course_id: "MATH240".into(),
+        instructors: vec![String::from("foo")],
+        rating: 4,
+        difficulty: 4,
+        user_id: "2".into(),
+        ..Default::default()
+      })
+      .await
+      .unwrap()
+      .modified_count,
+      0
+    );
 
-    let first = results.courses.first().unwrap();
+    let review = db.find_review("MATH240", "1").await.unwrap().unwrap();
 
-    assert_eq!(first.subject, "COMP");
-    assert_eq!(first.code, "202");
+    assert_eq!(review.content, "bar");
+    assert_eq!(review.instructors, vec![String::from("foo")]);
+    assert_eq!(review.rating, 4);
+    assert_eq!(review.timestamp, timestamp);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn get_course_by_id() {
-    let TestContext { db, db_name } = TestContext::new().await;
-
-    let tempdir = TempDir::new(&db_name).unwrap();
-
-    let source = tempdir.path().join("courses.json");
+  async fn delete_review() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    fs::write(&source, get_content("search.json")).unwrap();
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
 
-    db.initialize(InitializeOptions {
-      source,
+    db.add_review(Review {
+      content: "foo".into(),
+      course_id: "MATH240".into(),
+      user_id: "1".into(),
       ..Default::default()
     })
     .await
     .unwrap();
 
-    let courses = db.courses(None, None, None).await.unwrap();
-
-    assert_eq!(courses.len(), 123);
+    assert!(db.delete_review("MATH240", "2").await.is_err());
 
-    let first = courses.first().unwrap();
+    assert!(db.delete_review("MATH240", "1").await.is_ok());
 
-    assert_eq!(
-      db.find_course_by_id(&first.id).await.unwrap().unwrap(),
-      *first
-    );
+    assert_eq!(db.find_review("MATH240", "1").await.unwrap(), None);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn search_course_by_id_exact() {
-    let TestContext { db, db_name } = TestContext::new().await;
-
-    let tempdir = TempDir::new(&db_name).unwrap();
-
-    let source = tempdir.path().join("courses.json");
+  async fn delete_review_then_add_again() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    fs::write(&source, get_content("search.json")).unwrap();
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
+    .await
+    .unwrap();
 
-    db.initialize(InitializeOptions {
-      source,
+    db.add_review(Review {
+      content: "foo".into(),
+      course_id: "MATH240".into(),
+      user_id: "1".into(),
       ..Default::default()
     })
     .await
     .unwrap();
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
-
-    let results = db.search("COMP202").await.unwrap();
-
-    assert_eq!(results.courses.len(), 1);
-
-    let first = results.courses.first().unwrap();
+    assert!(db.delete_review("MATH240", "1").await.is_ok());
 
-    assert_eq!(first.subject, "COMP");
-    assert_eq!(first.code, "202");
+    assert!(db
+      .add_review(Review {
+        content: "foo".into(),
+        course_id: "MATH240".into(),
+        user_id: "1".into(),
+        ..Default::default()
+      })
+      .await
+      .is_ok());
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn fuzzy_search_course_by_title() {
+  async fn filter_courses_by_subject() {
     let TestContext { db, db_name } = TestContext::new().await;
 
     let tempdir = TempDir::new(&db_name).unwrap();
 
     let source = tempdir.path().join("courses.json");
 
-    fs::write(&source, get_content("search.json")).unwrap();
+    fs::write(&source, get_content("mix.json")).unwrap();
 
     db.initialize(InitializeOptions {
       source,
+
       ..Default::default()
     })
     .await
     .unwrap();
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
+    let total = db.courses(None, None, None).await.unwrap();
 
-    let results = db.search("foundations of").await.unwrap();
+    assert_eq!(total.len(), 314);
 
-    assert_eq!(results.courses.len(), 1);
+    let filtered = db
+      .courses(
+        None,
+        None,
+        Some(CourseFilter {
+          subjects: Some(vec!["MATH".into()]),
+          ..Default::default()
+        }),
+      )
+      .await
+      .unwrap();
 
-    let first = results.courses.first().unwrap();
+    assert!(filtered.len() < total.len());
 
-    assert_eq!(first.subject, "COMP");
-    assert_eq!(first.code, "202");
+    for course in filtered {
+      assert_eq!(course.subject, "MATH");
+    }
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn get_courses_with_limit() {
+  async fn filter_courses_by_level() {
     let TestContext { db, db_name } = TestContext::new().await;
 
     let tempdir = TempDir::new(&db_name).unwrap();
 
     let source = tempdir.path().join("courses.json");
 
-    fs::write(&source, get_content("search.json")).unwrap();
+    fs::write(&source, get_content("mix.json")).unwrap();
 
     db.initialize(InitializeOptions {
       source,
+
       ..Default::default()
     })
     .await
     .unwrap();
 
-    assert_eq!(db.courses(Some(10), None, None).await.unwrap().len(), 10);
+    let total = db.courses(None, None, None).await.unwrap();
+
+    assert_eq!(total.len(), 314);
+
+    let filtered = db
+      .courses(
+        None,
+        None,
+        Some(CourseFilter {
+          levels: Some(vec!["100".into()]),
+          ..Default::default()
+        }),
+      )
+      .await
+      .unwrap();
+
+    assert!(filtered.len() < total.len());
+
+    for course in filtered {
+      assert!(course.code.starts_with('1'));
+    }
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn get_courses_with_sort_filter() {
+  async fn filter_courses_by_term() {
     let TestContext { db, db_name } = TestContext::new().await;
 
     let tempdir = TempDir::new(&db_name).unwrap();
 
     let source = tempdir.path().join("courses.json");
 
-    fs::write(&source, get_content("search.json")).unwrap();
+    fs::write(&source, get_content("mix.json")).unwrap();
 
     db.initialize(InitializeOptions {
       source,
-      ..Default::default()
-    })
-    .await
-    .unwrap();
-
-    db.add_review(Review {
-      course_id: "COMP252".into(),
-      user_id: "1".into(),
-      rating: 5,
-      difficulty: 4,
-      ..Default::default()
-    })
-    .await
-    .unwrap();
 
-    db.add_review(Review {
-      course_id: "COMP252".into(),
-      user_id: "2".into(),
-      rating: 4,
-      difficulty: 3,
       ..Default::default()
     })
     .await
     .unwrap();
 
-    db.add_review(Review {
-      course_id: "COMP362".into(),
-      user_id: "1".into(),
-      rating: 3,
-      difficulty: 5,
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    let total = db.courses(None, None, None).await.unwrap();
 
-    db.add_review(Review {
-      course_id: "COMP362".into(),
-      user_id: "2".into(),
-      rating: 4,
-      difficulty: 4,
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    assert_eq!(total.len(), 314);
 
-    let courses = db
+    let filtered = db
       .courses(
-        Some(10),
+        None,
         None,
         Some(CourseFilter {
-          sort_by: Some(CourseSort {
-            sort_type: CourseSortType::Rating,
-            reverse: true,
-          }),
+          terms: Some(vec!["Winter".into()]),
           ..Default::default()
         }),
       )
       .await
       .unwrap();
 
-    assert!(
-      db.find_course_by_id("COMP252")
-        .await
-        .unwrap()
-        .unwrap()
-        .avg_rating
-        - 4.5
-        < 0.01,
-    );
+    assert!(filtered.len() < total.len());
 
-    assert_eq!(courses[0].id, "COMP252");
-    assert_eq!(courses[1].id, "COMP362");
+    for course in filtered {
+      assert!(course
+        .terms
+        .iter()
+        .any(|term| term.starts_with(&"Winter".to_string())));
+    }
+  }
 
-    let courses = db
-      .courses(
-        Some(10),
-        None,
-        Some(CourseFilter {
-          sort_by: Some(CourseSort {
-            sort_type: CourseSortType::Difficulty,
-            reverse: true,
-          }),
-          ..Default::default()
-        }),
-      )
-      .await
-      .unwrap();
+  #[tokio::test(flavor = "multi_thread")]
+  async fn add_instructors() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    assert_eq!(courses[0].id, "COMP362");
-    assert_eq!(courses[1].id, "COMP252");
+    let instructors = vec![
+      Instructor {
+        name: "foo".into(),
+        term: "Summer 2023".into(),
+        ..Default::default()
+      },
+      Instructor {
+        name: "bar".into(),
+        term: "Summer 2023".into(),
+        ..Default::default()
+      },
+      Instructor {
+        name: "bar".into(),
+        term: "Winter 2023".into(),
+        ..Default::default()
+      },
+    ];
 
-    db.add_review(Review {
-      course_id: "COMP400".into(),
-      user_id: "1".into(),
+    for instructor in instructors {
+      db.add_instructor(instructor).await.unwrap();
+    }
+
+    assert_eq!(db.instructors().await.unwrap().len(), 2);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn search_instructor_by_name_exact() {
+    let TestContext { db, db_name } = TestContext::new().await;
+
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("search.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
       ..Default::default()
     })
     .await
     .unwrap();
 
-    db.add_review(Review {
-      course_id: "COMP400".into(),
-      user_id: "2".into(),
-      ..Default::default()
-    })
+    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
+
+    let results = db.search("Giulia Alberini").await.unwrap();
+
+    assert_eq!(results.instructors.len(), 1);
+
+    assert_eq!(results.instructors.first().unwrap().name, "Giulia Alberini");
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn review_interaction_flow() {
+    let TestContext { db, .. } = TestContext::new().await;
+
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
     .await
     .unwrap();
 
-    db.add_review(Review {
-      course_id: "COMP400".into(),
-      user_id: "3".into(),
+    let review = Review {
+      content: "foo".into(),
+      course_id: "MATH240".into(),
+      user_id: "1".into(),
       ..Default::default()
+    };
+
+    db.add_review(review.clone()).await.unwrap();
+
+    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 1);
+
+    db.add_interaction(Interaction {
+      kind: InteractionKind::Like,
+      course_id: review.course_id.clone(),
+      user_id: review.user_id.clone(),
+      referrer: "10".into(),
     })
     .await
     .unwrap();
 
-    let courses = db
-      .courses(
-        Some(10),
-        None,
-        Some(CourseFilter {
-          sort_by: Some(CourseSort {
-            sort_type: CourseSortType::ReviewCount,
-            reverse: true,
-          }),
-          ..Default::default()
-        }),
-      )
+    let interactions = db
+      .interactions_for_review(&review.course_id, &review.user_id)
       .await
       .unwrap();
 
-    assert_eq!(courses[0].id, "COMP400");
-    assert_eq!(courses[0].review_count, 3);
-    assert_eq!(courses[1].review_count, 2);
-    assert_eq!(courses[2].review_count, 2);
-  }
+    assert_eq!(interactions.len(), 1);
+    assert_eq!(interactions.first().unwrap().kind, InteractionKind::Like);
 
-  #[tokio::test(flavor = "multi_thread")]
-  async fn add_reviews() {
-    let TestContext { db, .. } = TestContext::new().await;
+    let review = db
+      .find_review(&review.course_id, &review.user_id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(review.likes, 1);
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
+    db.add_interaction(Interaction {
+      kind: InteractionKind::Dislike,
+      course_id: review.course_id.clone(),
+      user_id: review.user_id.clone(),
+      referrer: "10".into(),
     })
     .await
     .unwrap();
 
-    let reviews = vec![
-      Review {
-        content: "foo".into(),
-        course_id: "MATH240".into(),
-        instructors: vec![String::from("test")],
-        rating: 5,
-        difficulty: 2,
-        user_id: "1".into(),
-        ..Default::default()
-      },
-      Review {
-        content: "foo".into(),
-        course_id: "MATH240".into(),
-        instructors: vec![String::from("test")],
-        rating: 4,
-        difficulty: 3,
-        user_id: "2".into(),
-        ..Default::default()
-      },
-      Review {
-        content: "foo".into(),
-        course_id: "MATH240".into(),
-        instructors: vec![String::from("test")],
-        rating: 3,
-        difficulty: 2,
-        user_id: "3".into(),
-        ..Default::default()
-      },
-    ];
+    let interactions = db
+      .interactions_for_review(&review.course_id, &review.user_id)
+      .await
+      .unwrap();
 
-    for review in &reviews {
-      db.add_review(review.clone()).await.unwrap();
-    }
+    assert_eq!(interactions.len(), 1);
+    assert_eq!(interactions.first().unwrap().kind, InteractionKind::Dislike);
 
-    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
-    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
+    let review = db
+      .find_review(&review.course_id, &review.user_id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(review.likes, -1);
 
-    let course = db.find_course_by_id("MATH240").await.unwrap().unwrap();
-    assert!(course.avg_rating - 4.0 < 0.001);
-    assert!(course.avg_difficulty - 2.33 < 0.1);
+    db.delete_interaction(&review.course_id, &review.user_id, "10")
+      .await
+      .unwrap();
+
+    assert_eq!(
+      db.interactions_for_review(&review.course_id, &review.user_id)
+        .await
+        .unwrap()
+        .len(),
+      0
+    );
+
+    let review = db
+      .find_review(&review.course_id, &review.user_id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(review.likes, 0);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn find_reviews_by_course_id() {
+  async fn subscription_flow() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    let subscription = Subscription::course("MATH240", "1");
 
-    db.add_course(Course {
-      id: "MATH340".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    let reviews = vec![
-      Review {
-        content: "foo".into(),
-        user_id: "1".into(),
-        instructors: vec![String::from("test")],
-        rating: 5,
-        course_id: "MATH240".into(),
-        ..Default::default()
-      },
-      Review {
-        content: "foo".into(),
-        user_id: "2".into(),
-        instructors: vec![String::from("test")],
-        rating: 5,
-        course_id: "MATH240".into(),
-        ..Default::default()
-      },
-      Review {
-        content: "foo".into(),
-        user_id: "3".into(),
-        instructors: vec![String::from("test")],
-        rating: 5,
-        course_id: "MATH340".into(),
-        ..Default::default()
-      },
-    ];
+    assert_eq!(db.subscriptions().await.unwrap().len(), 1);
 
-    for review in &reviews {
-      db.add_review(review.clone()).await.unwrap();
-    }
+    db.delete_subscription(subscription).await.unwrap();
 
-    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
-    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
+    assert_eq!(db.subscriptions().await.unwrap().len(), 0);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn subscriptions_are_not_duplicated() {
+    let TestContext { db, .. } = TestContext::new().await;
+
+    let subscription = Subscription {
+      target: SubscriptionTarget::Subject("COMP".into()),
+      user_id: "1".into(),
+    };
+
+    db.add_subscription(subscription.clone()).await.unwrap();
+    db.add_subscription(subscription.clone()).await.unwrap();
 
     assert_eq!(
-      db.find_reviews_by_course_id("MATH240").await.unwrap(),
-      vec![
-        Review {
-          content: "foo".into(),
-          user_id: "1".into(),
-          instructors: vec![String::from("test")],
-          rating: 5,
-          course_id: "MATH240".into(),
-          ..Default::default()
-        },
-        Review {
-          content: "foo".into(),
-          course_id: "MATH240".into(),
-          instructors: vec![String::from("test")],
-          rating: 5,
-          user_id: "2".into(),
-          ..Default::default()
-        }
-      ]
-    )
+      db.subscriptions().await.unwrap(),
+      vec![subscription.clone()]
+    );
+
+    db.delete_subscription(subscription).await.unwrap();
+
+    assert_eq!(db.subscriptions().await.unwrap().len(), 0);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn find_reviews_by_user_id() {
-    let TestContext { db, .. } = TestContext::new().await;
+  async fn notify_followers_of_subjects_instructors_and_filters() {
+    let TestContext { db, db_name } = TestContext::new().await;
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    let tempdir = TempDir::new(&db_name).unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("mini.json")).unwrap();
 
-    db.add_course(Course {
-      id: "MATH340".into(),
+    db.initialize(InitializeOptions {
+      source,
       ..Default::default()
     })
     .await
     .unwrap();
 
-    let reviews = vec![
-      Review {
-        content: "foo".into(),
-        user_id: "1".into(),
-        course_id: "MATH240".into(),
-        ..Default::default()
-      },
-      Review {
-        content: "foo".into(),
-        user_id: "2".into(),
-        course_id: "MATH240".into(),
-        ..Default::default()
-      },
-      Review {
-        content: "foo".into(),
-        user_id: "3".into(),
-        course_id: "MATH340".into(),
-        ..Default::default()
-      },
+    let subscriptions = [
+      ("1", SubscriptionTarget::Subject("MATH".into())),
+      ("1", SubscriptionTarget::Course("MATH240".into())),
+      ("2", SubscriptionTarget::Instructor("Jérôme Fortier".into())),
+      ("3", SubscriptionTarget::Instructor("Luc P Devroye".into())),
+      (
+        "4",
+        SubscriptionTarget::Filter(CourseFilter {
+          levels: Some(vec!["2".into()]),
+          subjects: Some(vec!["MATH".into()]),
+          ..Default::default()
+        }),
+      ),
+      (
+        "5",
+        SubscriptionTarget::Filter(CourseFilter {
+          subjects: Some(vec!["COMP".into()]),
+          ..Default::default()
+        }),
+      ),
+      ("6", SubscriptionTarget::Subject("COMP".into())),
     ];
 
-    for review in &reviews {
-      db.add_review(review.clone()).await.unwrap();
+    for (user_id, target) in subscriptions {
+      db.add_subscription(Subscription {
+        target,
+        user_id: user_id.into(),
+      })
+      .await
+      .unwrap();
     }
 
-    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
-    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
-
-    assert_eq!(
-      db.find_reviews_by_user_id("2").await.unwrap(),
-      vec![Review {
+    let notifications = db
+      .add_notifications(Review {
         content: "foo".into(),
-        user_id: "2".into(),
         course_id: "MATH240".into(),
-        ..Default::default()
-      },]
-    )
+        instructors: vec!["Jérôme Fortier".into()],
+        user_id: "7".into(),
+        ..Review::default()
+      })
+      .await
+      .unwrap();
+
+    assert_eq!(
+      notifications
+        .iter()
+        .map(|notification| notification.user_id.as_str())
+        .sorted()
+        .collect::<Vec<&str>>(),
+      vec!["1", "2", "4"]
+    );
+
+    assert_eq!(db.notifications().await.unwrap().len(), 3);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn find_reviews_by_user_instructor_name() {
-    let TestContext { db, .. } = TestContext::new().await;
+  async fn saved_filters_notify_for_the_courses_they_list() {
+    let TestContext { db, db_name } = TestContext::new().await;
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    let tempdir = TempDir::new(&db_name).unwrap();
 
-    db.add_course(Course {
-      id: "MATH340".into(),
+    let source = tempdir.path().join("courses.json");
+
+    fs::write(&source, get_content("mini.json")).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
       ..Default::default()
     })
     .await
     .unwrap();
 
-    let reviews = vec![
-      Review {
-        content: "foo".into(),
-        user_id: "1".into(),
-        course_id: "MATH240".into(),
-        instructors: vec![
-          String::from("test"),
-          String::from("foo"),
-          String::from("bar"),
-        ],
+    let filters = [
+      CourseFilter::default(),
+      CourseFilter {
+        subjects: Some(vec!["math".into()]),
         ..Default::default()
       },
-      Review {
-        content: "foo".into(),
-        user_id: "2".into(),
-        course_id: "MATH240".into(),
-        instructors: vec![String::from("test"), String::from("foo")],
+      CourseFilter {
+        subjects: Some(vec!["MA".into(), "COMP".into()]),
+        levels: Some(vec!["24".into()]),
         ..Default::default()
       },
-      Review {
-        content: "foo".into(),
-        user_id: "3".into(),
-        course_id: "MATH340".into(),
-        instructors: vec![String::from("foo"), String::from("bar")],
+      CourseFilter {
+        levels: Some(vec!["3".into()]),
+        ..Default::default()
+      },
+      CourseFilter {
+        terms: Some(vec!["winter".into()]),
+        ..Default::default()
+      },
+      CourseFilter {
+        terms: Some(vec!["Summer".into()]),
+        ..Default::default()
+      },
+      CourseFilter {
+        query: Some("math 240".into()),
+        ..Default::default()
+      },
+      CourseFilter {
+        query: Some("algorithms".into()),
+        ..Default::default()
+      },
+      CourseFilter {
+        sort_by: Some(CourseSort {
+          sort_type: CourseSortType::Rating,
+          reverse: false,
+        }),
         ..Default::default()
       },
     ];
 
-    for review in &reviews {
-      db.add_review(review.clone()).await.unwrap();
+    for (index, filter) in filters.iter().enumerate() {
+      db.add_subscription(Subscription {
+        target: SubscriptionTarget::Filter(filter.clone()),
+        user_id: index.to_string(),
+      })
+      .await
+      .unwrap();
     }
 
-    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 3);
-    assert_eq!(db.reviews(None, None, None).await.unwrap(), reviews);
-
-    assert_eq!(
-      db.find_reviews_by_instructor_name("test").await.unwrap(),
-      vec![
-        Review {
-          content: "foo".into(),
-          user_id: "1".into(),
-          course_id: "MATH240".into(),
-          instructors: vec![
-            String::from("test"),
-            String::from("foo"),
-            String::from("bar"),
-          ],
-          ..Default::default()
-        },
-        Review {
-          content: "foo".into(),
-          user_id: "2".into(),
-          course_id: "MATH240".into(),
-          instructors: vec![String::from("test"), String::from("foo")],
-          ..Default::default()
-        },
-      ]
-    )
-  }
-
-  #[tokio::test(flavor = "multi_thread")]
-  async fn dont_add_multiple_reviews_per_user() {
-    let TestContext { db, .. } = TestContext::new().await;
-
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    for course_id in ["COMP202", "COMP252", "MATH240"] {
+      let mut expected = Vec::new();
 
-    let review = Review {
-      user_id: "1".into(),
-      course_id: "MATH240".into(),
-      ..Default::default()
-    };
+      for (index, filter) in filters.iter().enumerate() {
+        if db
+          .courses(None, None, Some(filter.clone()))
+          .await
+          .unwrap()
+          .iter()
+          .any(|course| course.id == course_id)
+        {
+          expected.push(index.to_string());
+        }
+      }
 
-    for _ in 0..10 {
-      db.add_review(review.clone()).await.unwrap();
+      assert_eq!(
+        db.subscribers(course_id, &[])
+          .await
+          .unwrap()
+          .into_iter()
+          .sorted()
+          .collect::<Vec<String>>(),
+        expected,
+        "{course_id}"
+      );
     }
-
-    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 1);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn update_review() {
+  async fn notify_many_subscribers() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
-
-    db.add_review(Review {
+    let review = Review {
       content: "foo".into(),
       course_id: "MATH240".into(),
       instructors: vec![String::from("bar")],
       rating: 5,
       difficulty: 5,
-      user_id: "1".into(),
+      user_id: "3".into(),
       timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
       ..Review::default()
-    })
-    .await
-    .unwrap();
+    };
 
-    let timestamp = DateTime::from_chrono::<Utc>(Utc::now());
+    let subscription = Subscription::course("MATH240", "1");
 
-    assert_eq!(
-      db.add_review(Review {
-        content: "bar".into(),
-        course_id: "MATH240".into(),
-        instructors: vec![String::from("foo")],
-        rating: 4,
-        difficulty: 4,
-        user_id: "1".into(),
-        timestamp,
-        ..Review::default()
-      })
-      .await
-      .unwrap()
-      .modified_count,
-      1
-    );
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    assert_eq!(
-      db.add_review(Review {
-        content: "bar".into(),
-        course_id: "MATH240".into(),
-        instructors: vec![String::from("foo")],
-        rating: 4,
-        difficulty: 4,
-        user_id: "2".into(),
-        ..Default::default()
-      })
-      .await
-      .unwrap()
-      .modified_count,
-      0
-    );
+    let subscription = Subscription::course("MATH240", "2");
 
-    let review = db.find_review("MATH240", "1").await.unwrap().unwrap();
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    assert_eq!(review.content, "bar");
-    assert_eq!(review.instructors, vec![String::from("foo")]);
-    assert_eq!(review.rating, 4);
-    assert_eq!(review.timestamp, timestamp);
+    assert_eq!(db.subscriptions().await.unwrap().len(), 2);
+
+    db.add_notifications(review).await.unwrap();
+
+    assert_eq!(db.notifications().await.unwrap().len(), 2);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn delete_review() {
+  async fn notify_empty_subscribers() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
-
-    db.add_review(Review {
+    let review = Review {
       content: "foo".into(),
       course_id: "MATH240".into(),
+      instructors: vec![String::from("bar")],
+      rating: 5,
+      difficulty: 5,
       user_id: "1".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
-
-    assert!(db.delete_review("MATH240", "2").await.is_err());
+      timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
+      ..Review::default()
+    };
 
-    assert!(db.delete_review("MATH240", "1").await.is_ok());
+    db.add_notifications(review).await.unwrap();
 
-    assert_eq!(db.find_review("MATH240", "1").await.unwrap(), None);
+    assert_eq!(db.notifications().await.unwrap().len(), 0);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn delete_review_then_add_again() {
+  async fn dont_notify_review_creator() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
-
-    db.add_review(Review {
+    let review = Review {
       content: "foo".into(),
       course_id: "MATH240".into(),
+      instructors: vec![String::from("bar")],
+      rating: 5,
+      difficulty: 5,
       user_id: "1".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
-
-    assert!(db.delete_review("MATH240", "1").await.is_ok());
-
-    assert!(db
-      .add_review(Review {
-        content: "foo".into(),
-        course_id: "MATH240".into(),
-        user_id: "1".into(),
-        ..Default::default()
-      })
-      .await
-      .is_ok());
-  }
-
-  #[tokio::test(flavor = "multi_thread")]
-  async fn filter_courses_by_subject() {
-    let TestContext { db, db_name } = TestContext::new().await;
-
-    let tempdir = TempDir::new(&db_name).unwrap();
-
-    let source = tempdir.path().join("courses.json");
-
-    fs::write(&source, get_content("mix.json")).unwrap();
+      timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
+      ..Review::default()
+    };
 
-    db.initialize(InitializeOptions {
-      source,
+    let subscription = Subscription::course("MATH240", "1");
 
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    let total = db.courses(None, None, None).await.unwrap();
+    let subscription = Subscription::course("MATH240", "2");
 
-    assert_eq!(total.len(), 314);
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    let filtered = db
-      .courses(
-        None,
-        None,
-        Some(CourseFilter {
-          subjects: Some(vec!["MATH".into()]),
-          ..Default::default()
-        }),
-      )
-      .await
-      .unwrap();
+    assert_eq!(db.subscriptions().await.unwrap().len(), 2);
 
-    assert!(filtered.len() < total.len());
+    db.add_notifications(review).await.unwrap();
 
-    for course in filtered {
-      assert_eq!(course.subject, "MATH");
-    }
+    assert_eq!(db.notifications().await.unwrap().len(), 1);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn filter_courses_by_level() {
+  async fn notify_subscribers_of_course_changes() {
     let TestContext { db, db_name } = TestContext::new().await;
 
     let tempdir = TempDir::new(&db_name).unwrap();
 
     let source = tempdir.path().join("courses.json");
 
-    fs::write(&source, get_content("mix.json")).unwrap();
+    fs::write(&source, get_content("before_update.json")).unwrap();
 
     db.initialize(InitializeOptions {
-      source,
-
+      source: source.clone(),
       ..Default::default()
     })
     .await
     .unwrap();
 
-    let total = db.courses(None, None, None).await.unwrap();
-
-    assert_eq!(total.len(), 314);
-
-    let filtered = db
-      .courses(
-        None,
-        None,
-        Some(CourseFilter {
-          levels: Some(vec!["100".into()]),
-          ..Default::default()
-        }),
-      )
-      .await
-      .unwrap();
-
-    assert!(filtered.len() < total.len());
-
-    for course in filtered {
-      assert!(course.code.starts_with('1'));
+    for (course_id, user_id) in [("COMP251", "1"), ("COMP252", "2")] {
+      db.add_subscription(Subscription::course(course_id, user_id))
+        .await
+        .unwrap();
     }
-  }
 
-  #[tokio::test(flavor = "multi_thread")]
-  async fn filter_courses_by_term() {
-    let TestContext { db, db_name } = TestContext::new().await;
+    fs::write(&source, get_content("update.json")).unwrap();
 
-    let tempdir = TempDir::new(&db_name).unwrap();
+    let published = Arc::new(Mutex::new(Vec::new()));
 
-    let source = tempdir.path().join("courses.json");
+    db.initialize_publishing(
+      InitializeOptions {
+        source,
+        ..Default::default()
+      },
+      Arc::new({
+        let published = published.clone();
+        move |notifications| published.lock().unwrap().extend(notifications)
+      }),
+    )
+    .await
+    .unwrap();
 
-    fs::write(&source, get_content("mix.json")).unwrap();
+    assert_eq!(
+      db.get_notifications("2", None, None).await.unwrap().len(),
+      0
+    );
 
-    db.initialize(InitializeOptions {
-      source,
+    let notifications = db.get_notifications("1", None, None).await.unwrap();
 
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(*published.lock().unwrap(), notifications);
+    assert_eq!(notifications[0].course_id, "COMP251");
 
-    let total = db.courses(None, None, None).await.unwrap();
+    let NotificationPayload::CourseChange { changes } =
+      &notifications[0].payload
+    else {
+      panic!("expected a course change notification");
+    };
 
-    assert_eq!(total.len(), 314);
+    assert_eq!(
+      changes
+        .iter()
+        .map(|change| change.field.as_str())
+        .collect::<Vec<&str>>(),
+      vec!["corequisites", "prerequisites", "restrictions", "schedule"]
+    );
 
-    let filtered = db
-      .courses(
-        None,
-        None,
-        Some(CourseFilter {
-          terms: Some(vec!["Winter".into()]),
-          ..Default::default()
-        }),
-      )
+    db.update_notification("1", notifications[0].id, true)
       .await
       .unwrap();
 
-    assert!(filtered.len() < total.len());
+    assert!(db.get_notifications("1", None, None).await.unwrap()[0].seen);
 
-    for course in filtered {
-      assert!(course
-        .terms
-        .iter()
-        .any(|term| term.starts_with(&"Winter".to_string())));
-    }
+    db.delete_notification("1", notifications[0].id)
+      .await
+      .unwrap();
+
+    assert_eq!(
+      db.get_notifications("1", None, None).await.unwrap().len(),
+      0
+    );
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn add_instructors() {
-    let TestContext { db, .. } = TestContext::new().await;
-
-    let instructors = vec![
-      Instructor {
-        name: "foo".into(),
-        term: "Summer 2023".into(),
-        ..Default::default()
-      },
-      Instructor {
-        name: "bar".into(),
-        term: "Summer 2023".into(),
-        ..Default::default()
-      },
-      Instructor {
-        name: "bar".into(),
-        term: "Winter 2023".into(),
-        ..Default::default()
-      },
-    ];
+  async fn only_notify_new_instructors_for_current_terms() {
+    let current = current_terms();
 
-    for instructor in instructors {
-      db.add_instructor(instructor).await.unwrap();
-    }
+    let instructor = |name: &str, term: &str| Instructor {
+      name: name.into(),
+      term: term.into(),
+      ..Default::default()
+    };
 
-    assert_eq!(db.instructors().await.unwrap().len(), 2);
-  }
+    let change = |old: Vec<Instructor>, new: Vec<Instructor>| CourseChange {
+      course_id: "MATH240".into(),
+      field: "instructors".into(),
+      old_value: serde_json::json!(old),
+      new_value: serde_json::json!(new),
+      source: "courses.json".into(),
+      timestamp: DateTime::now(),
+    };
 
-  #[tokio::test(flavor = "multi_thread")]
-  async fn search_instructor_by_name_exact() {
-    let TestContext { db, db_name } = TestContext::new().await;
+    let old = vec![instructor("foo", "Fall 2009")];
 
-    let tempdir = TempDir::new(&db_name).unwrap();
+    assert!(!change(
+      old.clone(),
+      [old.clone(), vec![instructor("bar", "Winter 2010")]].concat()
+    )
+    .is_notable(&current));
 
-    let source = tempdir.path().join("courses.json");
+    assert!(change(
+      old.clone(),
+      [old.clone(), vec![instructor("bar", &current[0])]].concat()
+    )
+    .is_notable(&current));
+  }
 
-    fs::write(&source, get_content("search.json")).unwrap();
+  #[tokio::test(flavor = "multi_thread")]
+  async fn delete_subscription() {
+    let TestContext { db, .. } = TestContext::new().await;
 
-    db.initialize(InitializeOptions {
-      source,
-      ..Default::default()
-    })
-    .await
-    .unwrap();
+    let subscription = Subscription::course("MATH240", "1");
 
-    assert_eq!(db.courses(None, None, None).await.unwrap().len(), 123);
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    let results = db.search("Giulia Alberini").await.unwrap();
+    assert_eq!(db.subscriptions().await.unwrap().len(), 1);
 
-    assert_eq!(results.instructors.len(), 1);
+    db.delete_subscription(subscription.clone()).await.unwrap();
 
-    assert_eq!(results.instructors.first().unwrap().name, "Giulia Alberini");
+    assert_eq!(db.subscriptions().await.unwrap().len(), 0);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn review_interaction_flow() {
+  async fn delete_notification() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
-    .await
-    .unwrap();
-
     let review = Review {
       content: "foo".into(),
       course_id: "MATH240".into(),
-      user_id: "1".into(),
-      ..Default::default()
+      instructors: vec![String::from("bar")],
+      rating: 5,
+      difficulty: 5,
+      user_id: "3".into(),
+      timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
+      ..Review::default()
     };
 
-    db.add_review(review.clone()).await.unwrap();
+    let subscription = Subscription::course("MATH240", "1");
 
-    assert_eq!(db.reviews(None, None, None).await.unwrap().len(), 1);
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    db.add_interaction(Interaction {
-      kind: InteractionKind::Like,
-      course_id: review.course_id.clone(),
-      user_id: review.user_id.clone(),
-      referrer: "10".into(),
-    })
-    .await
-    .unwrap();
+    let subscription = Subscription::course("MATH240", "2");
 
-    let interactions = db
-      .interactions_for_review(&review.course_id, &review.user_id)
-      .await
-      .unwrap();
+    db.add_subscription(subscription.clone()).await.unwrap();
 
-    assert_eq!(interactions.len(), 1);
-    assert_eq!(interactions.first().unwrap().kind, InteractionKind::Like);
+    assert_eq!(db.subscriptions().await.unwrap().len(), 2);
 
-    let review = db
-      .find_review(&review.course_id, &review.user_id)
-      .await
-      .unwrap()
-      .unwrap();
-    assert_eq!(review.likes, 1);
+    db.add_notifications(review.clone()).await.unwrap();
 
-    db.add_interaction(Interaction {
-      kind: InteractionKind::Dislike,
-      course_id: review.course_id.clone(),
-      user_id: review.user_id.clone(),
-      referrer: "10".into(),
-    })
-    .await
-    .unwrap();
+    assert_eq!(db.notifications().await.unwrap().len(), 2);
 
-    let interactions = db
-      .interactions_for_review(&review.course_id, &review.user_id)
-      .await
-      .unwrap();
+    let id = db.get_notifications("1", None, None).await.unwrap()[0].id;
 
-    assert_eq!(interactions.len(), 1);
-    assert_eq!(interactions.first().unwrap().kind, InteractionKind::Dislike);
+    assert_eq!(
+      db.delete_notification("2", id).await.unwrap().deleted_count,
+      0
+    );
 
-    let review = db
-      .find_review(&review.course_id, &review.user_id)
+    db.delete_notification("1", id).await.unwrap();
+
+    assert_eq!(
+      db.get_notifications("1", None, None).await.unwrap().len(),
+      0
+    );
+    assert_eq!(db.notifications().await.unwrap().len(), 1);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn paginate_notifications() {
+    let TestContext { db, .. } = TestContext::new().await;
+
+    let course_ids = ["COMP202", "COMP250", "COMP251", "MATH240"];
+
+    for course_id in course_ids {
+      db.notify_user(
+        "1",
+        course_id,
+        NotificationPayload::Review(Review {
+          course_id: course_id.into(),
+          user_id: "2".into(),
+          ..Review::default()
+        }),
+      )
       .await
-      .unwrap()
       .unwrap();
-    assert_eq!(review.likes, -1);
+    }
 
-    db.delete_interaction(&review.course_id, &review.user_id, "10")
+    let page = |limit, offset| {
+      let db = db.clone();
+
+      async move {
+        db.get_notifications("1", Some(limit), Some(offset))
+          .await
+          .unwrap()
+          .into_iter()
+          .map(|notification| notification.course_id)
+          .collect::<Vec<String>>()
+      }
+    };
+
+    assert_eq!(page(2, 0).await, vec!["MATH240", "COMP251"]);
+    assert_eq!(page(2, 2).await, vec!["COMP250", "COMP202"]);
+    assert_eq!(page(2, 4).await, Vec::<String>::new());
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn mark_all_notifications_seen() {
+    let TestContext { db, .. } = TestContext::new().await;
+
+    for user_id in ["1", "1", "2"] {
+      db.notify_user(
+        user_id,
+        "MATH240",
+        NotificationPayload::Review(Review {
+          course_id: "MATH240".into(),
+          user_id: "3".into(),
+          ..Review::default()
+        }),
+      )
       .await
       .unwrap();
+    }
 
     assert_eq!(
-      db.interactions_for_review(&review.course_id, &review.user_id)
+      db.mark_all_notifications_seen("1")
         .await
         .unwrap()
-        .len(),
-      0
+        .modified_count,
+      2
     );
 
-    let review = db
-      .find_review(&review.course_id, &review.user_id)
-      .await
-      .unwrap()
-      .unwrap();
-    assert_eq!(review.likes, 0);
+    let notifications = db.notifications().await.unwrap();
+
+    assert!(notifications
+      .iter()
+      .all(|notification| notification.seen == (notification.user_id == "1")));
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn subscription_flow() {
+  async fn muted_notifications_are_not_delivered() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
+    for (course_id, user_id) in
+      [("MATH240", "1"), ("COMP202", "1"), ("MATH240", "2")]
+    {
+      db.add_subscription(Subscription::course(course_id, user_id))
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(
+      db.get_notification_preferences("1").await.unwrap(),
+      NotificationPreferences {
+        user_id: "1".into(),
+        ..Default::default()
+      }
+    );
+
+    let preferences = NotificationPreferences {
+      muted_courses: vec!["COMP202".into()],
       user_id: "1".into(),
+      ..Default::default()
     };
 
-    db.add_subscription(subscription.clone()).await.unwrap();
-
-    assert_eq!(db.subscriptions().await.unwrap().len(), 1);
+    db.set_notification_preferences(preferences.clone())
+      .await
+      .unwrap();
 
-    db.delete_subscription(Subscription {
-      course_id: subscription.course_id,
-      user_id: subscription.user_id,
+    db.set_notification_preferences(NotificationPreferences {
+      muted_types: vec![NotificationType::Review],
+      user_id: "2".into(),
+      ..Default::default()
     })
     .await
     .unwrap();
 
-    assert_eq!(db.subscriptions().await.unwrap().len(), 0);
+    assert_eq!(
+      db.get_notification_preferences("1").await.unwrap(),
+      preferences
+    );
+
+    for course_id in ["MATH240", "COMP202"] {
+      db.add_notifications(Review {
+        course_id: course_id.into(),
+        user_id: "3".into(),
+        ..Review::default()
+      })
+      .await
+      .unwrap();
+    }
+
+    let notifications = db.notifications().await.unwrap();
+
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].user_id, "1");
+    assert_eq!(notifications[0].course_id, "MATH240");
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn notify_many_subscribers() {
+  async fn legacy_notifications_are_migrated() {
     let TestContext { db, .. } = TestContext::new().await;
 
     let review = Review {
       content: "foo".into(),
       course_id: "MATH240".into(),
-      instructors: vec![String::from("bar")],
-      rating: 5,
-      difficulty: 5,
-      user_id: "3".into(),
+      user_id: "2".into(),
       timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
       ..Review::default()
     };
 
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
-      user_id: "1".into(),
-    };
-
-    db.add_subscription(subscription.clone()).await.unwrap();
-
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
-      user_id: "2".into(),
-    };
-
-    db.add_subscription(subscription.clone()).await.unwrap();
+    db.database
+      .collection::<Document>(Db::NOTIFICATION_COLLECTION)
+      .insert_many(
+        vec![
+          doc! {
+            "review": Into::<Bson>::into(review.clone()),
+            "seen": true,
+            "userId": "1",
+          },
+          doc! { "seen": false, "userId": "1" },
+        ],
+        None,
+      )
+      .await
+      .unwrap();
 
-    assert_eq!(db.subscriptions().await.unwrap().len(), 2);
+    db.migrate_notifications().await.unwrap();
 
-    db.add_notifications(review).await.unwrap();
+    let notifications = db.notifications().await.unwrap();
 
-    assert_eq!(db.notifications().await.unwrap().len(), 2);
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].course_id, "MATH240");
+    assert_eq!(notifications[0].created_at, review.timestamp);
+    assert_eq!(
+      notifications[0].payload,
+      NotificationPayload::Review(review)
+    );
+    assert!(notifications[0].seen);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn notify_empty_subscribers() {
+  async fn export_and_delete_user() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    let review = Review {
-      content: "foo".into(),
-      course_id: "MATH240".into(),
-      instructors: vec![String::from("bar")],
-      rating: 5,
-      difficulty: 5,
-      user_id: "1".into(),
-      timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
-      ..Review::default()
-    };
+    for id in ["COMP202", "MATH240"] {
+      db.add_course(
+        Course {
+          id: id.into(),
+          ..Default::default()
+        },
+        "courses.json",
+      )
+      .await
+      .unwrap();
+    }
 
-    db.add_notifications(review).await.unwrap();
+    let reviews = [
+      ("MATH240", "a", 5, 1),
+      ("COMP202", "a", 3, 3),
+      ("MATH240", "b", 1, 5),
+    ]
+    .map(|(course_id, user_id, rating, difficulty)| Review {
+      course_id: course_id.into(),
+      difficulty,
+      rating,
+      user_id: user_id.into(),
+      ..Default::default()
+    });
 
-    assert_eq!(db.notifications().await.unwrap().len(), 0);
-  }
+    for (user_id, course_id) in [("a", "MATH240"), ("b", "MATH240")] {
+      db.add_subscription(Subscription::course(course_id, user_id))
+        .await
+        .unwrap();
+    }
 
-  #[tokio::test(flavor = "multi_thread")]
-  async fn dont_notify_review_creator() {
-    let TestContext { db, .. } = TestContext::new().await;
+    for review in &reviews {
+      db.add_review(review.clone()).await.unwrap();
+      db.add_notifications(review.clone()).await.unwrap();
+    }
 
-    let review = Review {
-      content: "foo".into(),
-      course_id: "MATH240".into(),
-      instructors: vec![String::from("bar")],
-      rating: 5,
-      difficulty: 5,
-      user_id: "1".into(),
-      timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
-      ..Review::default()
-    };
+    for (user_id, referrer) in [("a", "b"), ("b", "a")] {
+      db.add_interaction(Interaction {
+        kind: InteractionKind::Like,
+        course_id: "MATH240".into(),
+        user_id: user_id.into(),
+        referrer: referrer.into(),
+      })
+      .await
+      .unwrap();
+    }
 
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
-      user_id: "1".into(),
-    };
+    db.record_mail("a", "a@mail.mcgill.ca").await.unwrap();
 
-    db.add_subscription(subscription.clone()).await.unwrap();
+    db.database
+      .collection::<Document>(Db::SESSION_COLLECTION)
+      .insert_many(
+        ["a", "ab"].map(|user_id| {
+          doc! {
+            "session_id": user_id,
+            "session": {
+              "id": user_id,
+              "data": {
+                "user": serde_json::json!({ "id": user_id, "mail": "" })
+                  .to_string(),
+              },
+            },
+            "expireAt": DateTime::from_millis(
+              DateTime::now().timestamp_millis() + 60_000
+            ),
+            "created": DateTime::now(),
+          }
+        }),
+        None,
+      )
+      .await
+      .unwrap();
 
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
-      user_id: "2".into(),
-    };
+    for user_id in ["a", "ab"] {
+      db.add_session(SessionMetadata {
+        last_seen_at: DateTime::now(),
+        session_id: user_id.into(),
+        user_agent: None,
+        user_id: user_id.into(),
+      })
+      .await
+      .unwrap();
+    }
 
-    db.add_subscription(subscription.clone()).await.unwrap();
+    let export = db.export_user("a").await.unwrap();
 
-    assert_eq!(db.subscriptions().await.unwrap().len(), 2);
+    assert_eq!(export.user_id, "a");
+    assert_eq!(export.reviews.len(), 2);
+    assert_eq!(
+      export.interactions,
+      vec![Interaction {
+        kind: InteractionKind::Like,
+        course_id: "MATH240".into(),
+        user_id: "b".into(),
+        referrer: "a".into(),
+      }]
+    );
+    assert_eq!(
+      export.subscriptions,
+      vec![Subscription::course("MATH240", "a")]
+    );
+    assert_eq!(export.notifications.len(), 1);
+    assert_eq!(
+      export.notification_preferences.mail,
+      Some("a@mail.mcgill.ca".into())
+    );
+    assert_eq!(export.sessions.len(), 1);
 
-    db.add_notifications(review).await.unwrap();
+    db.delete_user("a").await.unwrap();
 
-    assert_eq!(db.notifications().await.unwrap().len(), 1);
+    let export = db.export_user("a").await.unwrap();
+
+    assert!(export.reviews.is_empty());
+    assert!(export.interactions.is_empty());
+    assert!(export.subscriptions.is_empty());
+    assert!(export.notifications.is_empty());
+    assert!(export.sessions.is_empty());
+    assert_eq!(export.notification_preferences.mail, None);
+
+    let course = db.find_course_by_id("MATH240").await.unwrap().unwrap();
+
+    assert_eq!(course.review_count, 1);
+    assert_eq!(course.avg_rating, 1.0);
+    assert_eq!(course.avg_difficulty, 5.0);
+
+    let course = db.find_course_by_id("COMP202").await.unwrap().unwrap();
+
+    assert_eq!(course.review_count, 0);
+    assert_eq!(course.avg_rating, 0.0);
+    assert_eq!(course.avg_difficulty, 0.0);
+
+    assert_eq!(
+      db.find_review("MATH240", "b").await.unwrap().unwrap().likes,
+      0
+    );
+
+    assert!(db
+      .interactions_for_review("MATH240", "a")
+      .await
+      .unwrap()
+      .is_empty());
+
+    assert_eq!(
+      db.subscriptions().await.unwrap(),
+      vec![Subscription::course("MATH240", "b")]
+    );
+
+    assert!(db.notifications().await.unwrap().is_empty());
+
+    assert_eq!(
+      db.database
+        .collection::<Document>(Db::SESSION_COLLECTION)
+        .count_documents(None, None)
+        .await
+        .unwrap(),
+      1
+    );
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn delete_subscription() {
+  async fn list_and_revoke_sessions() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
-      user_id: "1".into(),
-    };
+    let in_a_minute =
+      DateTime::from_millis(DateTime::now().timestamp_millis() + 60_000);
 
-    db.add_subscription(subscription.clone()).await.unwrap();
+    db.database
+      .collection::<Document>(Db::SESSION_COLLECTION)
+      .insert_many(
+        [
+          ("1", "a", in_a_minute),
+          ("2", "a", in_a_minute),
+          ("3", "b", in_a_minute),
+          ("4", "a", DateTime::now()),
+        ]
+        .map(|(session_id, user_id, expire_at)| {
+          doc! {
+            "session_id": session_id,
+            "session": {
+              "id": session_id,
+              "data": {
+                "user": serde_json::json!({ "id": user_id, "mail": "" })
+                  .to_string(),
+              },
+            },
+            "expireAt": expire_at,
+            "created": DateTime::now(),
+          }
+        }),
+        None,
+      )
+      .await
+      .unwrap();
 
-    assert_eq!(db.subscriptions().await.unwrap().len(), 1);
+    let an_hour_ago =
+      DateTime::from_millis(DateTime::now().timestamp_millis() - 3_600_000);
+
+    for (session_id, user_id, user_agent) in [
+      ("1", "a", Some("curl")),
+      ("2", "a", None),
+      ("3", "b", Some("curl")),
+      ("4", "a", Some("curl")),
+    ] {
+      db.add_session(SessionMetadata {
+        last_seen_at: an_hour_ago,
+        session_id: session_id.into(),
+        user_agent: user_agent.map(Into::into),
+        user_id: user_id.into(),
+      })
+      .await
+      .unwrap();
+    }
 
-    db.delete_subscription(subscription.clone()).await.unwrap();
+    let sessions = db.sessions("a").await.unwrap();
 
-    assert_eq!(db.subscriptions().await.unwrap().len(), 0);
+    assert_eq!(
+      sessions
+        .iter()
+        .map(|session| (session.id.as_str(), session.user_agent.as_deref()))
+        .collect::<Vec<_>>(),
+      vec![("1", Some("curl")), ("2", None)]
+    );
+
+    assert_eq!(sessions[0].last_seen_at, Some(an_hour_ago));
+
+    let expires_at =
+      DateTime::from_millis(DateTime::now().timestamp_millis() + 3_600_000);
+
+    assert!(db.touch_session("1", Some(expires_at)).await.unwrap());
+    assert!(!db.touch_session("1", Some(expires_at)).await.unwrap());
+
+    let session = db.sessions("a").await.unwrap().remove(0);
+
+    assert!(session.last_seen_at.unwrap() > an_hour_ago);
+    assert_eq!(session.expires_at, expires_at);
+
+    assert!(!db.revoke_session("a", "3").await.unwrap());
+    assert!(db.revoke_session("a", "2").await.unwrap());
+
+    assert_eq!(db.sessions("b").await.unwrap().len(), 1);
+
+    assert_eq!(db.revoke_sessions("a", Some("1")).await.unwrap(), 1);
+    assert_eq!(db.sessions("a").await.unwrap().len(), 1);
+
+    assert_eq!(db.revoke_sessions("a", None).await.unwrap(), 1);
+    assert!(db.sessions("a").await.unwrap().is_empty());
+    assert_eq!(db.sessions("b").await.unwrap().len(), 1);
   }
 
   #[tokio::test(flavor = "multi_thread")]
-  async fn delete_notification() {
+  async fn api_tokens() {
     let TestContext { db, .. } = TestContext::new().await;
 
-    let review = Review {
-      content: "foo".into(),
-      course_id: "MATH240".into(),
-      instructors: vec![String::from("bar")],
-      rating: 5,
-      difficulty: 5,
-      user_id: "3".into(),
-      timestamp: DateTime::from_chrono::<Utc>(Utc::now()),
-      ..Review::default()
+    let token = |id: &str, user_id: &str| ApiToken {
+      created_at: DateTime::now(),
+      hash: format!("hash-{id}"),
+      id: id.into(),
+      last_used_at: None,
+      mail: format!("{user_id}@mail.mcgill.ca"),
+      name: "bot".into(),
+      rate_limit: 60,
+      scopes: vec![TokenScope::ReviewsRead],
+      user_id: user_id.into(),
     };
 
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
-      user_id: "1".into(),
-    };
+    for (id, user_id) in [("1", "a"), ("2", "a"), ("3", "b")] {
+      db.add_api_token(token(id, user_id)).await.unwrap();
+    }
 
-    db.add_subscription(subscription.clone()).await.unwrap();
+    assert_eq!(db.api_tokens("a").await.unwrap().len(), 2);
 
-    let subscription = Subscription {
-      course_id: "MATH240".into(),
-      user_id: "2".into(),
-    };
+    assert_eq!(db.use_api_token("hash-unknown").await.unwrap(), None);
 
-    db.add_subscription(subscription.clone()).await.unwrap();
+    let used = db.use_api_token("hash-1").await.unwrap().unwrap();
 
-    assert_eq!(db.subscriptions().await.unwrap().len(), 2);
+    assert_eq!(used.id, "1");
+    assert_eq!(used.user_id, "a");
 
-    db.add_notifications(review.clone()).await.unwrap();
+    assert!(db.api_tokens("a").await.unwrap()[0].last_used_at.is_some());
 
-    assert_eq!(db.notifications().await.unwrap().len(), 2);
+    assert!(!db.revoke_api_token("a", "3").await.unwrap());
+    assert!(db.revoke_api_token("a", "1").await.unwrap());
 
-    db.delete_notification("1", &review.course_id)
-      .await
-      .unwrap();
+    assert_eq!(db.use_api_token("hash-1").await.unwrap(), None);
 
-    assert_eq!(db.get_notifications("1").await.unwrap().len(), 0);
-    assert_eq!(db.notifications().await.unwrap().len(), 1);
+    db.delete_user("a").await.unwrap();
+
+    assert!(db.api_tokens("a").await.unwrap().is_empty());
+    assert_eq!(db.api_tokens("b").await.unwrap().len(), 1);
   }
 
   #[tokio::test(flavor = "multi_thread")]
@@ -2551,17 +4607,23 @@ mod tests {
 
     let after_epoch = rmp_scrape_epoch + chrono::Duration::hours(1);
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
     .await
     .unwrap();
 
-    db.add_course(Course {
-      id: "COMP202".into(),
-      ..Default::default()
-    })
+    db.add_course(
+      Course {
+        id: "COMP202".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
     .await
     .unwrap();
 
@@ -2617,10 +4679,13 @@ mod tests {
 
     assert_eq!(db.course_count().await.unwrap(), 0);
 
-    db.add_course(Course {
-      id: "MATH240".into(),
-      ..Default::default()
-    })
+    db.add_course(
+      Course {
+        id: "MATH240".into(),
+        ..Default::default()
+      },
+      "courses.json",
+    )
     .await
     .unwrap();
 
@@ -1,25 +1,114 @@
 use super::*;
 
-#[derive(Debug)]
+/// Receives the notifications created while seeding.
+pub type Publish = Arc<dyn Fn(Vec<Notification>) + Send + Sync>;
+
 pub(crate) struct Initializer {
   db: Db,
   options: InitializeOptions,
+  publish: Publish,
 }
 
 impl Initializer {
   const COURSE_COLLECTION: &'static str = "courses";
   const INSTRUCTOR_COLLECTION: &'static str = "instructors";
 
-  pub(crate) fn new(db: Db, options: InitializeOptions) -> Self {
-    Self { db, options }
+  pub(crate) fn new(
+    db: Db,
+    options: InitializeOptions,
+    publish: Publish,
+  ) -> Self {
+    Self {
+      db,
+      options,
+      publish,
+    }
+  }
+
+  /// Errors kept per seed file, so a bad file can't fill up memory.
+  const MAX_SEED_ERRORS: usize = 10;
+
+  pub(crate) async fn run(self) -> Result {
+    self.db.update_initialization(|status| {
+      status.started_at = Some(DateTime::now());
+      status.state = InitializationState::Indexing;
+    });
+
+    let result = self.initialize().await;
+
+    self.db.update_initialization(|status| {
+      status.finished_at = Some(DateTime::now());
+
+      match &result {
+        Ok(()) => status.state = InitializationState::Complete,
+        Err(error) => {
+          status.error = Some(format!("{error:#}"));
+          status.state = InitializationState::Failed;
+        }
+      }
+    });
+
+    result
   }
 
-  pub(crate) async fn run(&self) -> Result {
+  async fn initialize(&self) -> Result {
     self.index().await?;
+    self.db.migrate_notifications().await?;
+
+    self.db.update_initialization(|status| {
+      status.state = InitializationState::Seeding
+    });
+
     self.seed().await?;
+
     Ok(())
   }
 
+  fn record(db: &Db, seed: usize, result: &Result) {
+    db.update_initialization(|status| {
+      let progress = &mut status.seeds[seed];
+
+      let kind = match progress.kind {
+        SeedKind::Averages => "averages",
+        SeedKind::Courses => "courses",
+        SeedKind::Reviews => "reviews",
+        SeedKind::Unknown => "unknown",
+      };
+
+      SEEDED_ITEMS
+        .with_label_values(&[
+          kind,
+          if result.is_ok() { "success" } else { "failure" },
+        ])
+        .inc();
+
+      match result {
+        Ok(()) => progress.completed += 1,
+        Err(error) => {
+          progress.failed += 1;
+
+          if progress.errors.len() < Self::MAX_SEED_ERRORS {
+            progress.errors.push(format!("{error:#}"));
+          }
+        }
+      }
+    });
+  }
+
+  fn set_seed_state(&self, seed: usize, state: SeedState) {
+    self
+      .db
+      .update_initialization(|status| status.seeds[seed].state = state);
+  }
+
+  fn skipped(&self, seed: &Seed) -> bool {
+    match seed {
+      Seed::Averages(_) | Seed::Courses(_) => self.options.skip_courses,
+      Seed::Reviews(_) => self.options.skip_reviews,
+      Seed::Unknown(_) => true,
+    }
+  }
+
   fn collect(&self) -> Result<Vec<Seed>> {
     Ok(if self.options.source.is_file() {
       vec![Seed::from_content(
@@ -87,26 +176,39 @@ impl Initializer {
     F: Fn(Db, Item) -> Fut,
   >(
     &self,
+    seed: usize,
     items: Vec<Item>,
     runner: F,
   ) -> Result {
+    let run = |db: Db, item: Item| {
+      let future = runner(db.clone(), item);
+
+      async move {
+        let result = future.await;
+        Self::record(&db, seed, &result);
+        result
+      }
+    };
+
     let tasks =
       self
         .options
         .multithreaded
         .then_some(items.clone().into_iter().map(|item| {
           let db = self.db.clone();
-          tokio::task::spawn(runner(db, item))
+          tokio::task::spawn(run(db, item))
         }));
 
     if let Some(tasks) = tasks {
       join_all(tasks)
         .await
         .into_iter()
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
     } else {
       for item in items {
-        runner(self.db.clone(), item).await?;
+        run(self.db.clone(), item).await?;
       }
     }
 
@@ -132,24 +234,64 @@ impl Initializer {
       }
     }
 
-    for seed in seeds {
-      match seed {
-        Seed::Courses((path, courses)) if !self.options.skip_courses => {
+    seeds.sort_by_key(|seed| matches!(seed, Seed::Averages(_)));
+
+    self.db.update_initialization(|status| {
+      status.seeds = seeds
+        .iter()
+        .map(|seed| SeedProgress {
+          state: if self.skipped(seed) {
+            SeedState::Skipped
+          } else {
+            SeedState::Pending
+          },
+          ..seed.progress()
+        })
+        .collect();
+    });
+
+    for (index, seed) in seeds.into_iter().enumerate() {
+      if self.skipped(&seed) {
+        if let Seed::Unknown(path) = seed {
+          warn!(
+            "Unknown seed type encountered from {}, continuing...",
+            path.display()
+          );
+        }
+
+        continue;
+      }
+
+      self.set_seed_state(index, SeedState::Running);
+
+      let result = match seed {
+        Seed::Courses((path, courses)) => {
           info!("Seeding courses from {}...", path.display());
 
-          let runner = |db: Db, item: Course| async move {
-            db.add_course(item.clone()).await?;
+          let source = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
 
-            for instructor in item.instructors {
-              db.add_instructor(instructor).await?;
-            }
+          let runner = |db: Db, item: Course| {
+            let (source, publish) = (source.clone(), self.publish.clone());
 
-            Ok(())
+            async move {
+              let changes = db.add_course(item.clone(), &source).await?;
+
+              publish(db.add_course_change_notifications(changes).await?);
+
+              for instructor in item.instructors {
+                db.add_instructor(instructor).await?;
+              }
+
+              Ok(())
+            }
           };
 
-          self.populate(courses, runner).await?;
+          self.populate(index, courses, runner).await
         }
-        Seed::Reviews((path, reviews)) if !self.options.skip_reviews => {
+        Seed::Reviews((path, reviews)) => {
           info!("Seeding reviews from {}...", path.display());
 
           let runner = |db: Db, item: Review| async move {
@@ -157,16 +299,31 @@ impl Initializer {
             Ok(())
           };
 
-          self.populate(reviews, runner).await?;
+          self.populate(index, reviews, runner).await
         }
-        Seed::Unknown(path) => {
-          warn!(
-            "Unknown seed type encountered from {}, continuing...",
-            path.display()
-          );
+        Seed::Averages((path, averages)) => {
+          info!("Seeding course averages from {}...", path.display());
+
+          let runner = |db: Db, item: CourseAverages| async move {
+            db.add_course_averages(item).await?;
+            Ok(())
+          };
+
+          self.populate(index, averages, runner).await
         }
-        _ => continue,
-      }
+        Seed::Unknown(_) => continue,
+      };
+
+      self.set_seed_state(
+        index,
+        if result.is_ok() {
+          SeedState::Complete
+        } else {
+          SeedState::Failed
+        },
+      );
+
+      result?;
     }
 
     info!("Seeding complete.");
@@ -0,0 +1,34 @@
+use super::*;
+
+lazy_static! {
+  pub static ref COMMAND_DURATION: HistogramVec = register_histogram_vec!(
+    "mongodb_command_duration_seconds",
+    "Time taken by MongoDB commands",
+    &["command", "outcome"]
+  )
+  .unwrap();
+  pub static ref SEEDED_ITEMS: IntCounterVec = register_int_counter_vec!(
+    "seeded_items_total",
+    "Items written while seeding the database",
+    &["kind", "outcome"]
+  )
+  .unwrap();
+}
+
+/// Times every command the driver sends.
+#[derive(Debug)]
+pub(crate) struct CommandTimer;
+
+impl CommandEventHandler for CommandTimer {
+  fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+    COMMAND_DURATION
+      .with_label_values(&[&event.command_name, "success"])
+      .observe(event.duration.as_secs_f64());
+  }
+
+  fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+    COMMAND_DURATION
+      .with_label_values(&[&event.command_name, "failure"])
+      .observe(event.duration.as_secs_f64());
+  }
+}
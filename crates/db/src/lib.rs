@@ -1,6 +1,6 @@
 use {
   anyhow::anyhow,
-  bson::Bson,
+  bson::{oid::ObjectId, Bson, DateTime},
   chrono::{Datelike, TimeZone, Utc},
   combine::Combine,
   futures::Future,
@@ -10,28 +10,46 @@ use {
   lazy_static::lazy_static,
   log::{info, warn},
   model::{
-    Course, CourseFilter, CourseSortType, InitializeOptions, Instructor,
-    Interaction, InteractionKind, Notification, Review, ReviewFilter,
-    SearchResults, Subscription,
+    ActiveSession, ApiToken, Course, CourseAverages, CourseChange,
+    CourseFilter, CourseSort, CourseSortType, InitializationState,
+    InitializationStatus, InitializeOptions, Instructor, Interaction,
+    InteractionKind, Notification, NotificationPayload,
+    NotificationPreferences, Review, ReviewFilter, ReviewStats, SearchResults,
+    SeedKind, SeedProgress, SeedState, SessionMetadata, StoredSession,
+    Subscription, SubscriptionTarget, TermAverage, UserExport,
   },
   mongodb::{
     bson::{doc, Document},
+    event::command::{
+      CommandEventHandler, CommandFailedEvent, CommandSucceededEvent,
+    },
     options::UpdateModifications,
     options::{ClientOptions, FindOptions, IndexOptions, UpdateOptions},
-    results::{CreateIndexResult, DeleteResult, InsertOneResult, UpdateResult},
+    results::{CreateIndexResult, DeleteResult, UpdateResult},
     Client, Cursor, Database, IndexModel,
   },
   mongodb::{options::FindOneAndUpdateOptions, ClientSession, Collection},
+  prometheus::{
+    register_histogram_vec, register_int_counter_vec, HistogramVec,
+    IntCounterVec,
+  },
   serde::{de::DeserializeOwned, Serialize},
-  std::{collections::HashSet, env, fs, path::PathBuf},
-  {initializer::Initializer, seed::Seed, str_ext::StrExt, utils::*},
+  std::{
+    collections::HashSet,
+    env, fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+  },
+  {
+    initializer::Initializer, instrumentation::CommandTimer, seed::Seed,
+    str_ext::StrExt, utils::*,
+  },
 };
 
 #[cfg(test)]
 use {
-  bson::DateTime,
   include_dir::{include_dir, Dir},
-  model::CourseSort,
+  model::{NotificationType, TokenScope, YearlyAverage},
   std::sync::atomic::{AtomicUsize, Ordering},
   tempdir::TempDir,
 };
@@ -40,8 +58,13 @@ type Result<T = (), E = anyhow::Error> = std::result::Result<T, E>;
 
 mod db;
 mod initializer;
+mod instrumentation;
 mod seed;
 mod str_ext;
 mod utils;
 
-pub use crate::db::Db;
+pub use crate::{
+  db::Db,
+  initializer::Publish,
+  instrumentation::{COMMAND_DURATION, SEEDED_ITEMS},
+};
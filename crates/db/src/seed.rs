@@ -2,6 +2,7 @@ use super::*;
 
 #[derive(Debug, Clone)]
 pub(crate) enum Seed {
+  Averages((PathBuf, Vec<CourseAverages>)),
   Courses((PathBuf, Vec<Course>)),
   Reviews((PathBuf, Vec<Review>)),
   Unknown(PathBuf),
@@ -12,10 +13,37 @@ impl Seed {
     match (
       serde_json::from_str::<Vec<Course>>(&content).ok(),
       serde_json::from_str::<Vec<Review>>(&content).ok(),
+      serde_json::from_str::<Vec<CourseAverages>>(&content).ok(),
     ) {
-      (Some(courses), _) => Self::Courses((path, courses)),
-      (_, Some(reviews)) => Self::Reviews((path, reviews)),
+      (Some(courses), _, _) => Self::Courses((path, courses)),
+      (_, Some(reviews), _) => Self::Reviews((path, reviews)),
+      (_, _, Some(averages)) => Self::Averages((path, averages)),
       _ => Self::Unknown(path),
     }
   }
+
+  pub(crate) fn progress(&self) -> SeedProgress {
+    let (path, kind, total) = match self {
+      Self::Averages((path, averages)) => {
+        (path, SeedKind::Averages, averages.len())
+      }
+      Self::Courses((path, courses)) => {
+        (path, SeedKind::Courses, courses.len())
+      }
+      Self::Reviews((path, reviews)) => {
+        (path, SeedKind::Reviews, reviews.len())
+      }
+      Self::Unknown(path) => (path, SeedKind::Unknown, 0),
+    };
+
+    SeedProgress {
+      file: path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default(),
+      kind,
+      total: total as u64,
+      ..Default::default()
+    }
+  }
 }
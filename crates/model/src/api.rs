@@ -0,0 +1,335 @@
+use super::*;
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct GetCoursesParams {
+  pub limit: Option<i64>,
+  pub offset: Option<u64>,
+  pub with_course_count: Option<bool>,
+}
+
+/// The course listing as a `GET`, so it can be revalidated, with the
+/// `CourseFilter` as JSON.
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct ListCoursesParams {
+  pub filter: Option<String>,
+  pub limit: Option<i64>,
+  pub offset: Option<u64>,
+  pub with_course_count: Option<bool>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCoursesPayload {
+  pub courses: Vec<Course>,
+  pub course_count: Option<u64>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct GetCourseParams {
+  pub with_reviews: Option<bool>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct GetCourseWithReviewsPayload {
+  pub course: Course,
+  pub reviews: Vec<Review>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct GetCourseStatsParams {
+  pub instructor_name: Option<String>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct GetInstructorPayload {
+  pub instructor: Option<Instructor>,
+  pub reviews: Vec<Review>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct GetInteractionKindParams {
+  pub course_id: String,
+  pub user_id: String,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct GetInteractionKindPayload {
+  pub kind: Option<InteractionKind>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct GetUserInteractionForCoursePayload {
+  pub course_id: String,
+  pub referrer: String,
+  pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AddInteractionBody {
+  pub kind: InteractionKind,
+  pub course_id: String,
+  pub user_id: String,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct DeleteInteractionBody {
+  pub course_id: String,
+  pub user_id: String,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct GetNotificationsParams {
+  pub limit: Option<i64>,
+  pub offset: Option<u64>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct UpdateNotificationBody {
+  pub seen: bool,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotificationPreferencesBody {
+  #[serde(default)]
+  pub digest: DigestFrequency,
+  #[serde(default)]
+  pub muted_courses: Vec<String>,
+  #[serde(default)]
+  pub muted_types: Vec<NotificationType>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct GetReviewsParams {
+  pub course_id: Option<String>,
+  pub instructor_name: Option<String>,
+  pub limit: Option<i64>,
+  pub offset: Option<u64>,
+  pub sorted: Option<bool>,
+  /// A reviewer's pseudonym, which only matches the signed in user's own
+  /// reviews.
+  pub user_id: Option<String>,
+  pub with_user_count: Option<bool>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReviewsPayload {
+  pub reviews: Vec<Review>,
+  pub unique_user_count: Option<u64>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct AddOrUpdateReviewBody {
+  pub content: String,
+  pub course_id: String,
+  pub instructors: Vec<String>,
+  pub rating: u32,
+  pub difficulty: u32,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct DeleteReviewBody {
+  pub course_id: String,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct SearchParams {
+  pub query: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct SessionPayload {
+  #[serde(flatten)]
+  pub session: ActiveSession,
+  pub current: bool,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct GetSessionsPayload {
+  pub sessions: Vec<SessionPayload>,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteSessionsParams {
+  /// Keep the session making the request.
+  #[serde(default)]
+  pub others: bool,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, IntoParams,
+)]
+#[into_params(parameter_in = Query)]
+pub struct GetSubscriptionParams {
+  pub course_id: Option<String>,
+}
+
+/// Exactly one of the fields must be set.
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct AddOrDeleteSubscriptionBody {
+  pub course_id: Option<String>,
+  pub filter: Option<CourseFilter>,
+  pub instructor: Option<String>,
+  pub subject: Option<String>,
+}
+
+impl From<SubscriptionTarget> for AddOrDeleteSubscriptionBody {
+  fn from(target: SubscriptionTarget) -> Self {
+    match target {
+      SubscriptionTarget::Course(course_id) => Self {
+        course_id: Some(course_id),
+        ..Self::default()
+      },
+      SubscriptionTarget::Filter(filter) => Self {
+        filter: Some(filter),
+        ..Self::default()
+      },
+      SubscriptionTarget::Instructor(instructor) => Self {
+        instructor: Some(instructor),
+        ..Self::default()
+      },
+      SubscriptionTarget::Subject(subject) => Self {
+        subject: Some(subject),
+        ..Self::default()
+      },
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTokenPayload {
+  #[schema(value_type = DateTimeSchema)]
+  pub created_at: DateTime,
+  pub id: String,
+  #[schema(value_type = Option<DateTimeSchema>)]
+  pub last_used_at: Option<DateTime>,
+  pub name: String,
+  pub rate_limit: u32,
+  pub scopes: Vec<TokenScope>,
+}
+
+impl From<ApiToken> for ApiTokenPayload {
+  fn from(token: ApiToken) -> Self {
+    Self {
+      created_at: token.created_at,
+      id: token.id,
+      last_used_at: token.last_used_at,
+      name: token.name,
+      rate_limit: token.rate_limit,
+      scopes: token.scopes,
+    }
+  }
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct AddApiTokenBody {
+  pub name: String,
+  /// Requests per minute, defaulting to 60.
+  pub rate_limit: Option<u32>,
+  pub scopes: Vec<TokenScope>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AddApiTokenPayload {
+  #[serde(flatten)]
+  pub api_token: ApiTokenPayload,
+  /// The secret, which is only ever returned here.
+  pub token: String,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct CurrentUser {
+  pub id: String,
+  pub mail: String,
+  pub pseudonym: String,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct UserResponse {
+  pub user: Option<CurrentUser>,
+}
+
+/// Returned with a `Retry-After` header when a request goes over a rate limit.
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitedPayload {
+  /// The policy that turned the request away, e.g. `reviews` or `token`.
+  pub limiter: String,
+  pub message: String,
+  /// Seconds to wait before trying again.
+  pub retry_after: u64,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
+pub struct ReadinessPayload {
+  pub database: bool,
+  pub indexes: bool,
+  pub initialization: InitializationState,
+  pub ready: bool,
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema,
+)]
+pub enum TokenScope {
+  #[serde(rename = "interactions:read")]
+  InteractionsRead,
+  #[serde(rename = "interactions:write")]
+  InteractionsWrite,
+  #[serde(rename = "notifications:read")]
+  NotificationsRead,
+  #[serde(rename = "notifications:write")]
+  NotificationsWrite,
+  #[serde(rename = "reviews:read")]
+  ReviewsRead,
+  #[serde(rename = "reviews:write")]
+  ReviewsWrite,
+  #[serde(rename = "subscriptions:read")]
+  SubscriptionsRead,
+  #[serde(rename = "subscriptions:write")]
+  SubscriptionsWrite,
+  #[serde(rename = "user:read")]
+  UserRead,
+}
+
+/// A personal access token. Only the SHA-256 hash of the secret is stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+  pub created_at: DateTime,
+  pub hash: String,
+  pub id: String,
+  pub last_used_at: Option<DateTime>,
+  pub mail: String,
+  pub name: String,
+  /// Requests allowed per minute.
+  pub rate_limit: u32,
+  pub scopes: Vec<TokenScope>,
+  pub user_id: String,
+}
@@ -1,6 +1,8 @@
 use super::*;
 
-#[derive(Clone, Debug, Default, Deserialize, Derivative, Serialize)]
+#[derive(
+  Clone, Debug, Default, Deserialize, Derivative, Serialize, ToSchema,
+)]
 #[derivative(Eq, Hash, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Course {
@@ -29,6 +31,8 @@ pub struct Course {
   pub logical_corequisites: Option<ReqNode>,
   pub restrictions: Option<String>,
   pub schedule: Option<Vec<Schedule>>,
+  #[serde(default)]
+  pub averages: Vec<TermAverage>,
   #[derivative(PartialEq = "ignore")]
   #[derivative(Hash = "ignore")]
   #[serde(default = "zero_f32")]
@@ -0,0 +1,8 @@
+use super::*;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseAverages {
+  pub course_id: String,
+  pub averages: Vec<TermAverage>,
+}
@@ -0,0 +1,93 @@
+use super::*;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CourseChange {
+  pub course_id: String,
+  pub field: String,
+  pub old_value: Value,
+  pub new_value: Value,
+  pub source: String,
+  #[schema(value_type = DateTimeSchema)]
+  pub timestamp: DateTime,
+}
+
+impl CourseChange {
+  pub fn is_notable(&self, current_terms: &[String]) -> bool {
+    match self.field.as_str() {
+      "corequisites" | "prerequisites" | "restrictions" | "schedule" => true,
+      "instructors" => {
+        let parse = |value: &Value| {
+          serde_json::from_value::<Vec<Instructor>>(value.clone())
+            .unwrap_or_default()
+        };
+
+        let old = parse(&self.old_value);
+
+        parse(&self.new_value).into_iter().any(|instructor| {
+          current_terms.contains(&instructor.term) && !old.contains(&instructor)
+        })
+      }
+      _ => false,
+    }
+  }
+
+  pub fn between(old: &Course, new: &Course, source: &str) -> Vec<Self> {
+    let timestamp = DateTime::now();
+
+    [
+      (
+        "corequisites",
+        json!(old.corequisites),
+        json!(new.corequisites),
+      ),
+      (
+        "corequisitesText",
+        json!(old.corequisites_text),
+        json!(new.corequisites_text),
+      ),
+      ("credits", json!(old.credits), json!(new.credits)),
+      ("department", json!(old.department), json!(new.department)),
+      (
+        "description",
+        json!(old.description),
+        json!(new.description),
+      ),
+      ("faculty", json!(old.faculty), json!(new.faculty)),
+      (
+        "instructors",
+        json!(old.instructors),
+        json!(new.instructors),
+      ),
+      (
+        "prerequisites",
+        json!(old.prerequisites),
+        json!(new.prerequisites),
+      ),
+      (
+        "prerequisitesText",
+        json!(old.prerequisites_text),
+        json!(new.prerequisites_text),
+      ),
+      (
+        "restrictions",
+        json!(old.restrictions),
+        json!(new.restrictions),
+      ),
+      ("schedule", json!(old.schedule), json!(new.schedule)),
+      ("terms", json!(old.terms), json!(new.terms)),
+      ("title", json!(old.title), json!(new.title)),
+    ]
+    .into_iter()
+    .filter(|(_, old_value, new_value)| old_value != new_value)
+    .map(|(field, old_value, new_value)| Self {
+      course_id: new.id.clone(),
+      field: field.into(),
+      old_value,
+      new_value,
+      source: source.into(),
+      timestamp,
+    })
+    .collect()
+  }
+}
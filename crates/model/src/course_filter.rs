@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum CourseSortType {
   Difficulty,
@@ -8,14 +8,16 @@ pub enum CourseSortType {
   ReviewCount,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CourseSort {
   pub sort_type: CourseSortType,
   pub reverse: bool,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(
+  Debug, Clone, Default, PartialEq, Deserialize, Serialize, ToSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct CourseFilter {
   pub levels: Option<Vec<String>>,
@@ -0,0 +1,84 @@
+use super::*;
+
+/// How far along the database initialization started by `serve --initialize`
+/// is.
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum InitializationState {
+  /// No initialization was started by this process.
+  #[default]
+  Idle,
+  Pending,
+  Indexing,
+  Seeding,
+  Complete,
+  Failed,
+}
+
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SeedKind {
+  Averages,
+  Courses,
+  Reviews,
+  #[default]
+  Unknown,
+}
+
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SeedState {
+  #[default]
+  Pending,
+  Running,
+  Complete,
+  /// Excluded by the initialize options, or not a recognized seed.
+  Skipped,
+  Failed,
+}
+
+/// Progress through a single seed file.
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedProgress {
+  pub completed: u64,
+  /// The first few errors seen while seeding, if any.
+  pub errors: Vec<String>,
+  pub failed: u64,
+  pub file: String,
+  pub kind: SeedKind,
+  pub state: SeedState,
+  pub total: u64,
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializationStatus {
+  pub error: Option<String>,
+  #[schema(value_type = Option<DateTimeSchema>)]
+  pub finished_at: Option<DateTime>,
+  pub seeds: Vec<SeedProgress>,
+  #[schema(value_type = Option<DateTimeSchema>)]
+  pub started_at: Option<DateTime>,
+  pub state: InitializationState,
+}
+
+impl InitializationStatus {
+  /// Whether nothing is left to seed, so the data can be served.
+  pub fn is_settled(&self) -> bool {
+    matches!(
+      self.state,
+      InitializationState::Idle | InitializationState::Complete
+    )
+  }
+}
@@ -1,6 +1,8 @@
 use super::*;
 
-#[derive(Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[derive(
+  Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq, ToSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub enum InteractionKind {
   Like,
@@ -22,7 +24,9 @@ impl Into<Bson> for InteractionKind {
   }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[derive(
+  Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq, ToSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct Interaction {
   pub kind: InteractionKind,
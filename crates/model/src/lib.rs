@@ -1,42 +1,77 @@
 use {
-  bson::{doc, Bson, DateTime},
+  bson::{doc, oid::ObjectId, Bson, DateTime},
+  chrono::Datelike,
   combine::Combine,
   derivative::Derivative,
   serde::{Deserialize, Serialize},
+  serde_json::{json, Value},
   std::{
+    borrow::Cow,
+    collections::BTreeMap,
     fmt::{self, Display, Formatter},
     path::PathBuf,
   },
+  utoipa::{
+    openapi::{self, schema::Type, ObjectBuilder, RefOr},
+    IntoParams, PartialSchema, ToSchema,
+  },
 };
 
+mod api;
+mod api_token;
 mod course;
+mod course_averages;
+mod course_change;
 mod course_filter;
 mod course_listing;
 mod course_page;
+mod initialization;
 mod initialize_options;
 mod instructor;
 mod interaction;
 mod notification;
+mod notification_preferences;
 mod requirements;
 mod review;
 mod review_filter;
+mod review_stats;
 mod schedule;
+mod schema;
 mod search_results;
+mod session;
 mod subscription;
+mod term_average;
+mod user_export;
 
 pub use crate::{
+  api::*,
+  api_token::{ApiToken, TokenScope},
   course::Course,
+  course_averages::CourseAverages,
+  course_change::CourseChange,
   course_filter::{CourseFilter, CourseSort, CourseSortType},
   course_listing::CourseListing,
   course_page::CoursePage,
+  initialization::{
+    InitializationState, InitializationStatus, SeedKind, SeedProgress,
+    SeedState,
+  },
   initialize_options::InitializeOptions,
   instructor::Instructor,
   interaction::{Interaction, InteractionKind},
-  notification::Notification,
+  notification::{
+    ModerationOutcome, Notification, NotificationPayload, NotificationType,
+  },
+  notification_preferences::{DigestFrequency, NotificationPreferences},
   requirements::{Operator, ReqNode, Requirement, Requirements},
   review::Review,
   review_filter::ReviewFilter,
+  review_stats::{ReviewStats, YearlyAverage},
   schedule::*,
+  schema::{DateTimeSchema, ObjectIdSchema},
   search_results::SearchResults,
-  subscription::Subscription,
+  session::{ActiveSession, SessionMetadata, StoredSession},
+  subscription::{Subscription, SubscriptionTarget},
+  term_average::TermAverage,
+  user_export::UserExport,
 };
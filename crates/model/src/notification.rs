@@ -1,9 +1,89 @@
 use super::*;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationType {
+  CourseChange,
+  Moderation,
+  Review,
+  ReviewReply,
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum ModerationOutcome {
+  Approved,
+  Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+pub enum NotificationPayload {
+  CourseChange {
+    changes: Vec<CourseChange>,
+  },
+  Moderation {
+    outcome: ModerationOutcome,
+    reason: Option<String>,
+    review: Review,
+  },
+  Review(Review),
+  #[serde(rename_all = "camelCase")]
+  ReviewReply {
+    content: String,
+    review: Review,
+    user_id: String,
+  },
+}
+
+impl NotificationPayload {
+  pub fn notification_type(&self) -> NotificationType {
+    match self {
+      Self::CourseChange { .. } => NotificationType::CourseChange,
+      Self::Moderation { .. } => NotificationType::Moderation,
+      Self::Review(_) => NotificationType::Review,
+      Self::ReviewReply { .. } => NotificationType::ReviewReply,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Notification {
-  pub review: Review,
+  #[serde(rename = "_id")]
+  #[schema(value_type = ObjectIdSchema)]
+  pub id: ObjectId,
+  pub course_id: String,
+  #[schema(value_type = DateTimeSchema)]
+  pub created_at: DateTime,
+  #[serde(flatten)]
+  pub payload: NotificationPayload,
   pub seen: bool,
+  #[schema(value_type = DateTimeSchema)]
+  pub updated_at: DateTime,
   pub user_id: String,
 }
+
+impl Notification {
+  pub fn new(
+    user_id: &str,
+    course_id: &str,
+    payload: NotificationPayload,
+  ) -> Self {
+    let now = DateTime::now();
+
+    Self {
+      id: ObjectId::new(),
+      course_id: course_id.into(),
+      created_at: now,
+      payload,
+      seen: false,
+      updated_at: now,
+      user_id: user_id.into(),
+    }
+  }
+}
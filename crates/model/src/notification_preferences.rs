@@ -0,0 +1,61 @@
+use super::*;
+
+#[derive(
+  Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum DigestFrequency {
+  /// Users opt in to mail, which is recorded as a side effect of subscribing.
+  #[default]
+  Never,
+  Daily,
+  Weekly,
+}
+
+impl DigestFrequency {
+  pub fn period(&self) -> Option<chrono::Duration> {
+    match self {
+      Self::Never => None,
+      Self::Daily => Some(chrono::Duration::days(1)),
+      Self::Weekly => Some(chrono::Duration::weeks(1)),
+    }
+  }
+}
+
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferences {
+  #[serde(default)]
+  pub digest: DigestFrequency,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  #[schema(value_type = Option<DateTimeSchema>)]
+  pub last_digest_at: Option<DateTime>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub mail: Option<String>,
+  #[serde(default)]
+  pub muted_courses: Vec<String>,
+  #[serde(default)]
+  pub muted_types: Vec<NotificationType>,
+  pub user_id: String,
+}
+
+impl NotificationPreferences {
+  pub fn allows(&self, notification: &Notification) -> bool {
+    !self.muted_courses.contains(&notification.course_id)
+      && !self
+        .muted_types
+        .contains(&notification.payload.notification_type())
+  }
+
+  pub fn digest_due(&self, now: DateTime) -> bool {
+    match (self.digest.period(), self.last_digest_at) {
+      (None, _) => false,
+      (Some(_), None) => true,
+      (Some(period), Some(last)) => {
+        now.to_chrono() - last.to_chrono() >= period
+      }
+    }
+  }
+}
@@ -19,7 +19,16 @@ impl From<&str> for Requirement {
 }
 
 #[derive(
-  Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, Ord, PartialOrd,
+  Debug,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Clone,
+  Hash,
+  Ord,
+  PartialOrd,
+  ToSchema,
 )]
 pub enum Operator {
   #[serde(rename = "AND")]
@@ -38,13 +47,24 @@ impl Into<Bson> for Operator {
 }
 
 #[derive(
-  Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash, Ord, PartialOrd,
+  Debug,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Clone,
+  Hash,
+  Ord,
+  PartialOrd,
+  ToSchema,
 )]
 #[serde(untagged)]
 pub enum ReqNode {
+  /// A course code.
   Course(String),
   Group {
     operator: Operator,
+    #[schema(no_recursion)]
     groups: Vec<ReqNode>,
   },
 }
@@ -1,6 +1,8 @@
 use super::*;
 
-#[derive(Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[derive(
+  Clone, Debug, Serialize, Deserialize, Hash, Eq, PartialEq, ToSchema,
+)]
 #[serde(rename_all = "camelCase")]
 pub struct Review {
   pub content: String,
@@ -8,6 +10,7 @@ pub struct Review {
   pub instructors: Vec<String>,
   pub rating: u32,
   pub difficulty: u32,
+  #[schema(value_type = DateTimeSchema)]
   pub timestamp: DateTime,
   pub user_id: String,
   pub likes: i32,
@@ -0,0 +1,85 @@
+use super::*;
+
+#[derive(
+  Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct YearlyAverage {
+  pub academic_year: String,
+  pub avg_difficulty: f32,
+  pub avg_rating: f32,
+  pub review_count: u64,
+}
+
+#[derive(
+  Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewStats {
+  pub difficulty_distribution: Vec<u64>,
+  pub rating_distribution: Vec<u64>,
+  pub review_count: u64,
+  pub yearly_averages: Vec<YearlyAverage>,
+}
+
+impl ReviewStats {
+  pub const MAX_SCORE: u32 = 5;
+
+  fn academic_year(timestamp: DateTime) -> String {
+    let timestamp = timestamp.to_chrono();
+
+    let (month, year) = (timestamp.month(), timestamp.year());
+
+    if month >= 8 {
+      format!("{}-{}", year, year + 1)
+    } else {
+      format!("{}-{}", year - 1, year)
+    }
+  }
+
+  fn bucket(distribution: &mut [u64], score: u32) {
+    if (1..=Self::MAX_SCORE).contains(&score) {
+      distribution[(score - 1) as usize] += 1;
+    }
+  }
+}
+
+impl From<Vec<Review>> for ReviewStats {
+  fn from(reviews: Vec<Review>) -> Self {
+    let mut stats = ReviewStats {
+      difficulty_distribution: vec![0; Self::MAX_SCORE as usize],
+      rating_distribution: vec![0; Self::MAX_SCORE as usize],
+      review_count: reviews.len() as u64,
+      yearly_averages: Vec::new(),
+    };
+
+    let mut years = BTreeMap::<String, (u64, u64, u64)>::new();
+
+    for review in reviews {
+      Self::bucket(&mut stats.rating_distribution, review.rating);
+      Self::bucket(&mut stats.difficulty_distribution, review.difficulty);
+
+      let entry = years
+        .entry(Self::academic_year(review.timestamp))
+        .or_default();
+
+      entry.0 += 1;
+      entry.1 += review.rating as u64;
+      entry.2 += review.difficulty as u64;
+    }
+
+    stats.yearly_averages = years
+      .into_iter()
+      .map(
+        |(academic_year, (count, rating, difficulty))| YearlyAverage {
+          academic_year,
+          avg_difficulty: difficulty as f32 / count as f32,
+          avg_rating: rating as f32 / count as f32,
+          review_count: count,
+        },
+      )
+      .collect();
+
+    stats
+  }
+}
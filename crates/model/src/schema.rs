@@ -0,0 +1,57 @@
+use super::*;
+
+/// Schema for `bson::DateTime` fields, which serialize as MongoDB extended
+/// JSON, used with `#[schema(value_type = DateTimeSchema)]`.
+pub struct DateTimeSchema;
+
+impl PartialSchema for DateTimeSchema {
+  fn schema() -> RefOr<openapi::schema::Schema> {
+    ObjectBuilder::new()
+      .description(Some(
+        "MongoDB extended JSON date, in milliseconds since the epoch",
+      ))
+      .property(
+        "$date",
+        ObjectBuilder::new()
+          .property(
+            "$numberLong",
+            ObjectBuilder::new().schema_type(Type::String),
+          )
+          .required("$numberLong"),
+      )
+      .required("$date")
+      .examples([json!({ "$date": { "$numberLong": "0" } })])
+      .into()
+  }
+}
+
+impl ToSchema for DateTimeSchema {
+  fn name() -> Cow<'static, str> {
+    "DateTime".into()
+  }
+}
+
+/// Schema for `ObjectId` fields, used with
+/// `#[schema(value_type = ObjectIdSchema)]`.
+pub struct ObjectIdSchema;
+
+impl PartialSchema for ObjectIdSchema {
+  fn schema() -> RefOr<openapi::schema::Schema> {
+    ObjectBuilder::new()
+      .property(
+        "$oid",
+        ObjectBuilder::new()
+          .schema_type(Type::String)
+          .pattern(Some("^[0-9a-f]{24}$")),
+      )
+      .required("$oid")
+      .examples([json!({ "$oid": "000000000000000000000000" })])
+      .into()
+  }
+}
+
+impl ToSchema for ObjectIdSchema {
+  fn name() -> Cow<'static, str> {
+    "ObjectId".into()
+  }
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct SearchResults {
   pub courses: Vec<Course>,
   pub instructors: Vec<Instructor>,
@@ -0,0 +1,35 @@
+use super::*;
+
+/// What we know about a login session beyond the session store itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMetadata {
+  pub last_seen_at: DateTime,
+  pub session_id: String,
+  pub user_agent: Option<String>,
+  pub user_id: String,
+}
+
+/// A session as stored by `async-mongodb-session`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredSession {
+  #[serde(rename = "created")]
+  pub created_at: DateTime,
+  #[serde(rename = "expireAt")]
+  pub expires_at: DateTime,
+  #[serde(rename = "session_id")]
+  pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSession {
+  #[schema(value_type = DateTimeSchema)]
+  pub created_at: DateTime,
+  #[schema(value_type = DateTimeSchema)]
+  pub expires_at: DateTime,
+  pub id: String,
+  #[schema(value_type = Option<DateTimeSchema>)]
+  pub last_seen_at: Option<DateTime>,
+  pub user_agent: Option<String>,
+}
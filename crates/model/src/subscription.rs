@@ -1,8 +1,30 @@
 use super::*;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum SubscriptionTarget {
+  #[serde(rename = "courseId")]
+  Course(String),
+  #[serde(rename = "filter")]
+  Filter(CourseFilter),
+  #[serde(rename = "instructor")]
+  Instructor(String),
+  #[serde(rename = "subject")]
+  Subject(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Subscription {
-  pub course_id: String,
+  #[serde(flatten)]
+  pub target: SubscriptionTarget,
   pub user_id: String,
 }
+
+impl Subscription {
+  pub fn course(course_id: &str, user_id: &str) -> Self {
+    Self {
+      target: SubscriptionTarget::Course(course_id.into()),
+      user_id: user_id.into(),
+    }
+  }
+}
@@ -0,0 +1,29 @@
+use super::*;
+
+#[derive(
+  Clone,
+  Debug,
+  Default,
+  Deserialize,
+  Eq,
+  Hash,
+  Ord,
+  PartialEq,
+  PartialOrd,
+  Serialize,
+  ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct TermAverage {
+  pub term: String,
+  pub average: String,
+}
+
+impl Into<Bson> for TermAverage {
+  fn into(self) -> bson::Bson {
+    Bson::Document(doc! {
+      "term": self.term,
+      "average": self.average,
+    })
+  }
+}
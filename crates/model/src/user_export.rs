@@ -0,0 +1,15 @@
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserExport {
+  #[schema(value_type = DateTimeSchema)]
+  pub exported_at: DateTime,
+  pub interactions: Vec<Interaction>,
+  pub notification_preferences: NotificationPreferences,
+  pub notifications: Vec<Notification>,
+  pub reviews: Vec<Review>,
+  pub sessions: Vec<ActiveSession>,
+  pub subscriptions: Vec<Subscription>,
+  pub user_id: String,
+}
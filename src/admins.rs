@@ -0,0 +1,43 @@
+use super::*;
+
+/// Mail addresses of users allowed to see server internals, such as the
+/// progress of database initialization.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Admins(Arc<HashSet<String>>);
+
+impl Admins {
+  pub(crate) fn new<'a>(mails: impl IntoIterator<Item = &'a str>) -> Self {
+    Self(Arc::new(
+      mails
+        .into_iter()
+        .map(str::trim)
+        .filter(|mail| !mail.is_empty())
+        .map(str::to_lowercase)
+        .collect(),
+    ))
+  }
+
+  pub(crate) fn from_config(config: &Config) -> Self {
+    Self::new(config.admins.mails.iter().map(String::as_str))
+  }
+
+  pub(crate) fn contains(&self, user: &User) -> bool {
+    self.0.contains(&user.mail().to_lowercase())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn admins_match_mail_case_insensitively() {
+    let admins =
+      Admins::new("Admin@mail.mcgill.ca, ,other@mcgill.ca".split(','));
+
+    assert!(admins.contains(&User::new("a", "admin@mail.mcgill.ca")));
+    assert!(admins.contains(&User::new("b", "OTHER@mcgill.ca")));
+    assert!(!admins.contains(&User::new("c", "student@mail.mcgill.ca")));
+    assert!(!Admins::default().contains(&User::new("d", "")));
+  }
+}
@@ -0,0 +1,87 @@
+use super::*;
+
+#[derive(Clone, Debug)]
+pub(crate) struct AllowedOrigins(Arc<Vec<Origin>>);
+
+impl AllowedOrigins {
+  pub(crate) fn new(origins: Vec<Origin>) -> Self {
+    Self(Arc::new(origins))
+  }
+
+  pub(crate) fn from_config(
+    config: &Config,
+    redirect_uri: &Url,
+  ) -> Result<Self> {
+    let mut origins = vec![redirect_uri.origin()];
+
+    for origin in &config.auth.allowed_origins {
+      origins.push(
+        Url::parse(origin)
+          .map_err(|error| {
+            anyhow!(
+              "Invalid origin `{origin}` in `auth.allowed_origins`: {error}"
+            )
+          })?
+          .origin(),
+      );
+    }
+
+    Ok(Self::new(origins))
+  }
+
+  pub(crate) fn iter(&self) -> impl Iterator<Item = &Origin> {
+    self.0.iter()
+  }
+
+  pub(crate) fn check(&self, redirect: &str) -> Option<Url> {
+    let url = Url::parse(redirect).ok()?;
+
+    (matches!(url.scheme(), "http" | "https") && self.0.contains(&url.origin()))
+      .then_some(url)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn origins() -> AllowedOrigins {
+    AllowedOrigins::new(
+      ["https://mcgill.courses", "http://localhost:5173"]
+        .into_iter()
+        .map(|origin| Url::parse(origin).unwrap().origin())
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn allowed_redirects() {
+    for redirect in [
+      "https://mcgill.courses",
+      "https://mcgill.courses/course/comp-202?tab=reviews",
+      "http://localhost:5173/profile",
+    ] {
+      assert_eq!(
+        origins().check(redirect).map(|url| url.to_string()),
+        Some(Url::parse(redirect).unwrap().to_string())
+      );
+    }
+  }
+
+  #[test]
+  fn rejected_redirects() {
+    for redirect in [
+      "https://evil.com",
+      "https://mcgill.courses.evil.com",
+      "http://mcgill.courses",
+      "https://mcgill.courses:8443",
+      "http://localhost:8000",
+      "javascript:alert(1)",
+      "//evil.com",
+      "/profile",
+      "",
+    ] {
+      assert_eq!(origins().check(redirect), None, "{redirect}");
+    }
+  }
+}
@@ -0,0 +1,230 @@
+use super::*;
+
+pub(crate) const TOKEN_PREFIX: &str = "mcc_";
+
+const DEFAULT_RATE_LIMIT: u32 = 60;
+
+const MAX_RATE_LIMIT: u32 = 600;
+
+pub(crate) fn hash(token: &str) -> String {
+  hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// The scope a token needs to call `method` on `path`, or `None` if the route
+/// can't be used with a token at all.
+pub(crate) fn required_scope(
+  method: &Method,
+  path: &str,
+) -> Option<TokenScope> {
+  let route = path
+    .strip_prefix(API_V1)
+    .or_else(|| path.strip_prefix("/api"))?;
+
+  // The export holds the account's sessions, votes and subscriptions, so
+  // like managing the account it needs a signed in browser
+  if route == "/user/export" {
+    return None;
+  }
+
+  let resource = route.strip_prefix('/')?.split('/').next()?;
+
+  let read = method == Method::GET;
+
+  Some(match (resource, read) {
+    ("interactions", true) => TokenScope::InteractionsRead,
+    ("interactions", false) => TokenScope::InteractionsWrite,
+    ("notifications", true) => TokenScope::NotificationsRead,
+    ("notifications", false) => TokenScope::NotificationsWrite,
+    ("reviews", true) => TokenScope::ReviewsRead,
+    ("reviews", false) => TokenScope::ReviewsWrite,
+    ("subscriptions", true) => TokenScope::SubscriptionsRead,
+    ("subscriptions", false) => TokenScope::SubscriptionsWrite,
+    ("user", true) => TokenScope::UserRead,
+    _ => return None,
+  })
+}
+
+/// List personal access tokens
+#[utoipa::path(
+  get,
+  path = "/api/v1/tokens",
+  responses(
+    (
+      status = 200,
+      description = "The tokens, without their secrets",
+      body = Vec<ApiTokenPayload>,
+    ),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn get_api_tokens(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  Ok(Json(
+    db.api_tokens(&user.id())
+      .await?
+      .into_iter()
+      .map(ApiTokenPayload::from)
+      .collect::<Vec<ApiTokenPayload>>(),
+  ))
+}
+
+/// Create a personal access token
+#[utoipa::path(
+  post,
+  path = "/api/v1/tokens",
+  request_body = AddApiTokenBody,
+  responses(
+    (
+      status = 201,
+      description = "The token, including its secret",
+      body = AddApiTokenPayload,
+    ),
+    (status = 400, description = "Invalid name, scopes or rate limit"),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn add_api_token(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+  Json(body): Json<AddApiTokenBody>,
+) -> Result<Response> {
+  let name = body.name.trim();
+
+  if name.is_empty() || body.scopes.is_empty() {
+    return Ok(
+      (
+        StatusCode::BAD_REQUEST,
+        "tokens need a name and at least one scope",
+      )
+        .into_response(),
+    );
+  }
+
+  let mut scopes = Vec::new();
+
+  for scope in body.scopes {
+    if !scopes.contains(&scope) {
+      scopes.push(scope);
+    }
+  }
+
+  let rate_limit = body.rate_limit.unwrap_or(DEFAULT_RATE_LIMIT);
+
+  if !(1..=MAX_RATE_LIMIT).contains(&rate_limit) {
+    return Ok(
+      (
+        StatusCode::BAD_REQUEST,
+        format!("rate limit must be between 1 and {MAX_RATE_LIMIT} requests per minute"),
+      )
+        .into_response(),
+    );
+  }
+
+  let token =
+    format!("{TOKEN_PREFIX}{}", hex::encode(rand::random::<[u8; 32]>()));
+
+  let api_token = ApiToken {
+    created_at: bson::DateTime::now(),
+    hash: hash(&token),
+    id: hex::encode(rand::random::<[u8; 8]>()),
+    last_used_at: None,
+    mail: user.mail().into(),
+    name: name.into(),
+    rate_limit,
+    scopes,
+    user_id: user.id(),
+  };
+
+  info!(
+    "Adding api token {} for user {}",
+    api_token.id, api_token.user_id
+  );
+
+  db.add_api_token(api_token.clone()).await?;
+
+  Ok(
+    (
+      StatusCode::CREATED,
+      Json(AddApiTokenPayload {
+        api_token: api_token.into(),
+        token,
+      }),
+    )
+      .into_response(),
+  )
+}
+
+/// Revoke a personal access token
+#[utoipa::path(
+  delete,
+  path = "/api/v1/tokens/{id}",
+  params(("id" = String, Path)),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 404, description = "Not found"),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn delete_api_token(
+  user: User,
+  Path(id): Path<String>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<StatusCode> {
+  Ok(if db.revoke_api_token(&user.id(), &id).await? {
+    StatusCode::OK
+  } else {
+    StatusCode::NOT_FOUND
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn scopes_for_routes() {
+    for (method, path, scope) in [
+      (Method::GET, "/api/reviews", Some(TokenScope::ReviewsRead)),
+      (Method::POST, "/api/reviews", Some(TokenScope::ReviewsWrite)),
+      (
+        Method::GET,
+        "/api/notifications/stream",
+        Some(TokenScope::NotificationsRead),
+      ),
+      (
+        Method::PUT,
+        "/api/notifications/1",
+        Some(TokenScope::NotificationsWrite),
+      ),
+      (
+        Method::DELETE,
+        "/api/subscriptions",
+        Some(TokenScope::SubscriptionsWrite),
+      ),
+      (Method::GET, "/api/user", Some(TokenScope::UserRead)),
+      (Method::DELETE, "/api/user", None),
+      (Method::GET, "/api/user/export", None),
+      (Method::GET, "/api/v1/user/export", None),
+      (Method::GET, "/api/tokens", None),
+      (Method::POST, "/api/tokens", None),
+      (Method::GET, "/api/sessions", None),
+      (
+        Method::GET,
+        "/api/v1/reviews",
+        Some(TokenScope::ReviewsRead),
+      ),
+      (
+        Method::POST,
+        "/api/v1/interactions",
+        Some(TokenScope::InteractionsWrite),
+      ),
+      (Method::GET, "/api/v1/tokens", None),
+      (Method::GET, "/api/v1", None),
+      (Method::GET, "/api", None),
+    ] {
+      assert_eq!(required_scope(&method, path), scope, "{method} {path}");
+    }
+  }
+}
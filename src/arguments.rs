@@ -10,7 +10,10 @@ pub(crate) struct Arguments {
 
 impl Arguments {
   pub(crate) async fn run(self) -> Result {
+    logger::init(self.options.log_format);
+
     match self.subcommand {
+      Subcommand::Import(importer) => importer.run(self.options.source),
       Subcommand::Load(loader) => loader.run(self.options.source),
       Subcommand::Serve(server) => server.run(self.options.source).await,
     }
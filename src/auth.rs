@@ -2,6 +2,10 @@ use super::*;
 
 pub(crate) const COOKIE_NAME: &str = "session";
 
+pub(crate) const LOGIN_COOKIE_NAME: &str = "login";
+
+const LOGIN_EXPIRY: Duration = Duration::from_secs(60 * 10);
+
 pub struct AuthRedirect;
 
 impl IntoResponse for AuthRedirect {
@@ -10,119 +14,222 @@ impl IntoResponse for AuthRedirect {
   }
 }
 
-#[derive(Debug, Deserialize)]
+pub enum AuthRejection {
+  InsufficientScope,
+  InvalidToken,
+  RateLimited(RateLimited),
+  Redirect,
+  Unavailable,
+}
+
+impl From<AuthRedirect> for AuthRejection {
+  fn from(_: AuthRedirect) -> Self {
+    Self::Redirect
+  }
+}
+
+impl From<RateLimited> for AuthRejection {
+  fn from(rejection: RateLimited) -> Self {
+    Self::RateLimited(rejection)
+  }
+}
+
+impl IntoResponse for AuthRejection {
+  fn into_response(self) -> Response {
+    match self {
+      Self::InsufficientScope => (
+        StatusCode::FORBIDDEN,
+        [(
+          header::WWW_AUTHENTICATE,
+          "Bearer error=\"insufficient_scope\"",
+        )],
+        "token does not have the scope for this route",
+      )
+        .into_response(),
+      Self::InvalidToken => (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer error=\"invalid_token\"")],
+        "token is invalid or has been revoked",
+      )
+        .into_response(),
+      Self::RateLimited(rejection) => rejection.into_response(),
+      Self::Redirect => AuthRedirect.into_response(),
+      Self::Unavailable => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AuthRequest {
   code: String,
   state: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LoginRequest {
   redirect: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LogoutRequest {
   redirect: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(unused)]
-struct AccessTokenResponse {
-  access_token: String,
-  expires_in: u64,
-  ext_expires_in: u64,
-  scope: String,
-  token_type: String,
-}
-
-pub(crate) async fn microsoft_auth(
+/// Start signing in
+#[utoipa::path(
+  get,
+  path = "/api/auth/login",
+  params(LoginRequest),
+  responses(
+    (
+      status = 303,
+      description = "To the identity provider",
+      headers(("Location" = String)),
+    ),
+    (status = 400, description = "The redirect is not an allowed origin"),
+  ),
+)]
+pub(crate) async fn login(
   Query(query): Query<LoginRequest>,
-  AppState(client): AppState<BasicClient>,
-) -> impl IntoResponse {
-  Redirect::to(
-    client
-      .authorize_url(|| {
-        CsrfToken::new(format!(
-          "{}{}",
-          CsrfToken::new_random().secret(),
-          &STANDARD.encode(query.redirect)
-        ))
-      })
-      .add_scope(Scope::new(String::from("openid")))
-      .add_scope(Scope::new(String::from("User.Read")))
-      .url()
-      .0
-      .as_ref(),
-  )
+  AppState(state): AppState<State>,
+) -> Result<Response> {
+  let Some(redirect) = state.allowed_origins.check(&query.redirect) else {
+    return Ok((StatusCode::BAD_REQUEST, INVALID_REDIRECT).into_response());
+  };
+
+  let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
+
+  let csrf_token = CsrfToken::new_random();
+
+  let url = state
+    .identity_provider
+    .authorize_url(csrf_token.secret(), &challenge);
+
+  let mut session = Session::new();
+
+  session.expire_in(LOGIN_EXPIRY);
+
+  session.insert("csrf", csrf_token.secret())?;
+  session.insert("redirect", redirect.as_str())?;
+  session.insert("verifier", verifier.secret())?;
+
+  let mut headers = HeaderMap::new();
+
+  headers.insert(
+    SET_COOKIE,
+    format!(
+      "{}={}; HttpOnly; SameSite=Lax; Path=/api/auth; Max-Age={}",
+      LOGIN_COOKIE_NAME,
+      state
+        .session_store
+        .store_session(session)
+        .await?
+        .ok_or(anyhow!("Failed to store login session"))?,
+      LOGIN_EXPIRY.as_secs()
+    )
+    .parse()?,
+  );
+
+  Ok((headers, Redirect::to(url.as_str())).into_response())
 }
 
+/// Finish signing in with the identity provider
+#[utoipa::path(
+  get,
+  path = "/api/auth/authorized",
+  params(AuthRequest),
+  responses(
+    (
+      status = 303,
+      description = "Back to the page that started the login",
+      headers(("Location" = String)),
+    ),
+    (status = 400, description = "The login state does not match"),
+  ),
+)]
 pub(crate) async fn login_authorized(
   Query(query): Query<AuthRequest>,
+  cookies: Option<TypedHeader<Cookie>>,
+  user_agent: Option<TypedHeader<UserAgent>>,
   AppState(state): AppState<State>,
-) -> Result<impl IntoResponse> {
-  debug!("Fetching token from oauth client...");
-
-  let params = [
-    ("client_id", &state.oauth_client.client_id().to_string()),
-    ("client_secret", &state.client_secret),
-    ("code", &query.code),
-    ("grant_type", &"authorization_code".into()),
-    (
-      "redirect_uri",
-      state
-        .oauth_client
-        .redirect_url()
-        .expect("Missing redirect url"),
-    ),
-    ("scope", &"User.Read".into()),
-  ];
-
-  let response = state
-    .request_client
-    .post("https://login.microsoftonline.com/organizations/oauth2/v2.0/token")
-    .form(&params)
-    .header("Accept", "application/x-www-form-urlencoded")
-    .send()
-    .await?
-    .json::<AccessTokenResponse>()
-    .await?;
+) -> Result<Response> {
+  let login = match cookies
+    .as_ref()
+    .and_then(|cookies| cookies.get(LOGIN_COOKIE_NAME))
+  {
+    Some(cookie) => state.session_store.load_session(cookie.into()).await?,
+    None => None,
+  };
+
+  let Some(login) = login else {
+    return Ok((StatusCode::BAD_REQUEST, INVALID_LOGIN).into_response());
+  };
 
-  debug!("Fetching user data from Microsoft...");
+  state.session_store.destroy_session(login.clone()).await?;
+
+  let (Some(csrf), Some(redirect), Some(verifier)) = (
+    login.get::<String>("csrf"),
+    login.get::<String>("redirect"),
+    login.get::<String>("verifier"),
+  ) else {
+    return Ok((StatusCode::BAD_REQUEST, INVALID_LOGIN).into_response());
+  };
+
+  if !constant_time_eq(csrf.as_bytes(), query.state.as_bytes()) {
+    warn!("Rejecting login callback with mismatched state");
+    return Ok((StatusCode::BAD_REQUEST, INVALID_LOGIN).into_response());
+  }
 
-  let user: User = state
-    .request_client
-    .get("https://graph.microsoft.com/v1.0/me")
-    .bearer_auth(response.access_token)
-    .send()
-    .await?
-    .json()
+  let user = state
+    .identity_provider
+    .user(&state.request_client, &query.code, &verifier)
     .await?;
 
-  let url =
-    Url::parse(&String::from_utf8(STANDARD.decode(&query.state[22..])?)?)?;
+  let url = Url::parse(&redirect)?;
+
+  let mut headers = HeaderMap::new();
+
+  headers.append(
+    SET_COOKIE,
+    format!("{}=; Max-Age=0; Path=/api/auth", LOGIN_COOKIE_NAME).parse()?,
+  );
 
   if !user.mail().ends_with("mcgill.ca") {
-    return Ok((
-      HeaderMap::new(),
-      Redirect::to(&format!(
-        "{}?err=invalidMail",
-        url.origin().ascii_serialization()
-      )),
-    ));
+    return Ok(
+      (
+        headers,
+        Redirect::to(&format!(
+          "{}?err=invalidMail",
+          url.origin().ascii_serialization()
+        )),
+      )
+        .into_response(),
+    );
   }
 
   let mut session = Session::new();
 
-  session.expire_in(Duration::from_secs(60 * 60 * 24 * 7));
+  session.expire_in(state.session_policy.expiry);
 
   debug!("Inserting user data into session...");
 
-  session.insert("user", user)?;
+  state
+    .db
+    .add_session(SessionMetadata {
+      last_seen_at: bson::DateTime::now(),
+      session_id: session.id().into(),
+      user_agent: user_agent
+        .map(|TypedHeader(user_agent)| user_agent.to_string()),
+      user_id: user.clone().id(),
+    })
+    .await?;
 
-  let mut headers = HeaderMap::new();
+  session.insert("user", user)?;
 
-  headers.insert(
+  headers.append(
     SET_COOKIE,
     format!(
       "{}={}; SameSite=Lax; Path=/",
@@ -136,27 +243,54 @@ pub(crate) async fn login_authorized(
     .parse()?,
   );
 
-  Ok((headers, Redirect::to(url.as_ref())))
+  Ok((headers, Redirect::to(url.as_ref())).into_response())
 }
 
+/// Sign out of the current session
+#[utoipa::path(
+  get,
+  path = "/api/auth/logout",
+  params(LogoutRequest),
+  responses(
+    (
+      status = 303,
+      description = "To `redirect`",
+      headers(("Location" = String)),
+    ),
+  ),
+)]
 pub(crate) async fn logout(
   Query(query): Query<LogoutRequest>,
   TypedHeader(cookies): TypedHeader<Cookie>,
+  AppState(allowed_origins): AppState<AllowedOrigins>,
   AppState(session_store): AppState<MongodbSessionStore>,
-) -> Result<impl IntoResponse> {
+) -> Result<Response> {
+  let Some(redirect) = allowed_origins.check(&query.redirect) else {
+    return Ok((StatusCode::BAD_REQUEST, INVALID_REDIRECT).into_response());
+  };
+
   let cookie = match cookies.get(COOKIE_NAME) {
     Some(c) => c,
-    None => return Ok(Redirect::to(&query.redirect)),
+    None => return Ok(Redirect::to(redirect.as_str()).into_response()),
   };
 
   let session = match session_store.load_session(cookie.to_string()).await? {
     Some(s) => s,
-    None => return Ok(Redirect::to(&query.redirect)),
+    None => return Ok(Redirect::to(redirect.as_str()).into_response()),
   };
 
   debug!("Destroying session...");
 
   session_store.destroy_session(session).await?;
 
-  Ok(Redirect::to(&query.redirect))
+  Ok(Redirect::to(redirect.as_str()).into_response())
+}
+
+const INVALID_LOGIN: &str = "login state is missing, expired or invalid";
+
+const INVALID_REDIRECT: &str = "redirect origin is not allowed";
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  a.len() == b.len()
+    && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
 }
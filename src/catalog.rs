@@ -0,0 +1,167 @@
+use super::*;
+
+pub(crate) const CATALOG_VERSION: HeaderName =
+  HeaderName::from_static("x-catalog-version");
+
+/// Identifies the course data being served, from the hash of the source the
+/// database is seeded from, when it's on disk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CatalogVersion(Option<Arc<str>>);
+
+impl CatalogVersion {
+  pub(crate) fn from_hash(hash: &[u8]) -> Self {
+    Self(Some(hex::encode(&hash[..hash.len().min(8)]).into()))
+  }
+
+  /// A weak validator for `body`, since compression changes the bytes sent.
+  fn etag(&self, body: &[u8]) -> String {
+    let digest = hex::encode(&Sha256::digest(body)[..8]);
+
+    match &self.0 {
+      Some(version) => format!("W/\"{version}-{digest}\""),
+      None => format!("W/\"{digest}\""),
+    }
+  }
+}
+
+impl FromRef<State> for CatalogVersion {
+  fn from_ref(state: &State) -> Self {
+    state.catalog_version.clone()
+  }
+}
+
+/// Whether `method` on `route`, a route under either api root, serves
+/// catalog data that can be revalidated. Only `GET`s are, so the course
+/// listing is tagged when its filter is in the query rather than the body.
+pub(crate) fn is_cached(method: &Method, route: &str) -> bool {
+  let Some(route) = route
+    .strip_prefix(API_V1)
+    .or_else(|| route.strip_prefix("/api"))
+  else {
+    return false;
+  };
+
+  method == Method::GET
+    && matches!(route, "/courses" | "/courses/:id" | "/search")
+}
+
+/// Compares `etag` against an `If-None-Match` header, weakly as RFC 9110
+/// requires for `GET`.
+fn matches(if_none_match: &HeaderValue, etag: &str) -> bool {
+  let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+
+  if_none_match.to_str().is_ok_and(|value| {
+    value
+      .split(',')
+      .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+  })
+}
+
+/// Tags catalog responses with an `ETag`, and answers `304 Not Modified`
+/// when the client already has them. Ratings change with every review, so
+/// clients are told to revalidate rather than reuse responses for a while.
+pub(crate) async fn cache(
+  AppState(version): AppState<CatalogVersion>,
+  matched_path: Option<MatchedPath>,
+  request: Request<Body>,
+  next: Next,
+) -> Result<Response> {
+  if !matched_path
+    .is_some_and(|path| is_cached(request.method(), path.as_str()))
+  {
+    return Ok(next.run(request).await);
+  }
+
+  let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+  let response = next.run(request).await;
+
+  if response.status() != StatusCode::OK {
+    return Ok(response);
+  }
+
+  let (mut parts, body) = response.into_parts();
+
+  let body = axum::body::to_bytes(body, usize::MAX)
+    .await
+    .map_err(|error| anyhow!(error))?;
+
+  let etag = version.etag(&body);
+
+  let mut headers = HeaderMap::new();
+
+  headers.insert(header::ETAG, HeaderValue::from_str(&etag)?);
+
+  headers.insert(
+    header::CACHE_CONTROL,
+    HeaderValue::from_static("public, no-cache"),
+  );
+
+  if let Some(version) = &version.0 {
+    headers.insert(CATALOG_VERSION, HeaderValue::from_str(version)?);
+  }
+
+  if if_none_match.is_some_and(|value| matches(&value, &etag)) {
+    return Ok((StatusCode::NOT_MODIFIED, headers).into_response());
+  }
+
+  parts.headers.extend(headers);
+
+  Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn cached_routes() {
+    for (method, route, cached) in [
+      (Method::GET, "/api/v1/courses", true),
+      (Method::GET, "/api/v1/courses/:id", true),
+      (Method::GET, "/api/courses/:id", true),
+      (Method::GET, "/api/v1/search", true),
+      (Method::POST, "/api/v1/courses", false),
+      (Method::GET, "/api/v1/reviews", false),
+      (Method::GET, "/search", false),
+    ] {
+      assert_eq!(is_cached(&method, route), cached, "{method} {route}");
+    }
+  }
+
+  #[test]
+  fn etags() {
+    let version = CatalogVersion::from_hash(&Sha256::digest(b"seed"));
+
+    let etag = version.etag(b"{}");
+
+    assert!(etag.starts_with(&format!("W/\"{}-", version.0.unwrap())));
+    assert_eq!(etag.len(), "W/\"-\"".len() + 32);
+
+    assert_ne!(CatalogVersion::default().etag(b"{}"), etag);
+    assert_ne!(
+      CatalogVersion::default().etag(b"{}"),
+      CatalogVersion::default().etag(b"[]")
+    );
+  }
+
+  #[test]
+  fn if_none_match() {
+    let etag = "W/\"abc\"";
+
+    for (header, matched) in [
+      ("W/\"abc\"", true),
+      ("\"abc\"", true),
+      ("\"xyz\", W/\"abc\"", true),
+      ("*", true),
+      ("\"xyz\"", false),
+      ("", false),
+    ] {
+      assert_eq!(
+        matches(&HeaderValue::from_static(header), etag),
+        matched,
+        "{header}"
+      );
+    }
+  }
+}
@@ -0,0 +1,709 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+  Boolean,
+  Integer,
+  /// Separated by commas or whitespace when set from the environment.
+  List,
+  String,
+}
+
+impl Kind {
+  /// `value` from the environment, as it would be written in the
+  /// configuration file.
+  fn parse(self, value: &str) -> Result<toml::Value, String> {
+    match self {
+      Self::Boolean => match value {
+        "true" => Ok(toml::Value::Boolean(true)),
+        "false" => Ok(toml::Value::Boolean(false)),
+        _ => Err(format!("expected `true` or `false`, found `{value}`")),
+      },
+      Self::Integer => value
+        .parse::<i64>()
+        .ok()
+        .filter(|value| *value >= 0)
+        .map(toml::Value::Integer)
+        .ok_or_else(|| {
+          format!("expected a non-negative integer, found `{value}`")
+        }),
+      Self::List => Ok(toml::Value::Array(
+        value
+          .split(|c: char| c == ',' || c.is_whitespace())
+          .filter(|item| !item.is_empty())
+          .map(|item| toml::Value::String(item.into()))
+          .collect(),
+      )),
+      Self::String => Ok(toml::Value::String(value.into())),
+    }
+  }
+}
+
+/// An environment variable and the setting it overrides.
+#[derive(Debug)]
+struct Variable {
+  env: &'static str,
+  key: &'static str,
+  kind: Kind,
+}
+
+const fn variable(
+  key: &'static str,
+  env: &'static str,
+  kind: Kind,
+) -> Variable {
+  Variable { env, key, kind }
+}
+
+/// Every setting that can be overridden from the environment, by its key in
+/// the configuration file.
+const VARIABLES: &[Variable] = &[
+  variable("admins.mails", "ADMIN_MAILS", Kind::List),
+  variable("auth.allowed_origins", "ALLOWED_ORIGINS", Kind::List),
+  variable("auth.identity_provider", "IDENTITY_PROVIDER", Kind::String),
+  variable("auth.pseudonym_key", "PSEUDONYM_KEY", Kind::String),
+  variable("dev.redirect_uri", "DEV_REDIRECT_URI", Kind::String),
+  variable("dev.user_id", "DEV_USER_ID", Kind::String),
+  variable("dev.user_mail", "DEV_USER_MAIL", Kind::String),
+  variable("mail.dir", "MAIL_DIR", Kind::String),
+  variable("mail.from", "MAIL_FROM", Kind::String),
+  variable("mail.transport", "MAIL_TRANSPORT", Kind::String),
+  variable("metrics.token", "METRICS_TOKEN", Kind::String),
+  variable("microsoft.client_id", "MS_CLIENT_ID", Kind::String),
+  variable("microsoft.client_secret", "MS_CLIENT_SECRET", Kind::String),
+  variable("microsoft.redirect_uri", "MS_REDIRECT_URI", Kind::String),
+  variable("mongodb.url", "MONGODB_URL", Kind::String),
+  variable("oidc.client_id", "OIDC_CLIENT_ID", Kind::String),
+  variable("oidc.client_secret", "OIDC_CLIENT_SECRET", Kind::String),
+  variable("oidc.issuer", "OIDC_ISSUER", Kind::String),
+  variable("oidc.redirect_uri", "OIDC_REDIRECT_URI", Kind::String),
+  variable("oidc.scopes", "OIDC_SCOPES", Kind::List),
+  variable("rate_limit.burst", "RATE_LIMIT_BURST", Kind::Integer),
+  variable("rate_limit.enabled", "RATE_LIMIT_ENABLED", Kind::Boolean),
+  variable(
+    "rate_limit.interactions",
+    "RATE_LIMIT_INTERACTIONS",
+    Kind::Integer,
+  ),
+  variable(
+    "rate_limit.period_ms",
+    "RATE_LIMIT_PERIOD_MS",
+    Kind::Integer,
+  ),
+  variable("rate_limit.reviews", "RATE_LIMIT_REVIEWS", Kind::Integer),
+  variable("rate_limit.search", "RATE_LIMIT_SEARCH", Kind::Integer),
+  variable("s3.bucket", "S3_BUCKET", Kind::String),
+  variable(
+    "security.content_security_policy",
+    "CONTENT_SECURITY_POLICY",
+    Kind::String,
+  ),
+  variable("security.hsts", "HSTS", Kind::Boolean),
+  variable("server.asset_dir", "ASSET_DIR", Kind::String),
+  variable("server.db_name", "DB_NAME", Kind::String),
+  variable("server.environment", "ENV", Kind::String),
+  variable("server.port", "PORT", Kind::Integer),
+  variable("sessions.expiry_days", "SESSION_EXPIRY_DAYS", Kind::Integer),
+  variable("sessions.sliding", "SESSION_SLIDING_EXPIRY", Kind::Boolean),
+  variable("smtp.host", "SMTP_HOST", Kind::String),
+  variable("smtp.password", "SMTP_PASSWORD", Kind::String),
+  variable("smtp.port", "SMTP_PORT", Kind::Integer),
+  variable("smtp.security", "SMTP_SECURITY", Kind::String),
+  variable("smtp.username", "SMTP_USERNAME", Kind::String),
+];
+
+/// The string setting at `config.section.field`, or an error naming both
+/// ways to set it if it isn't.
+macro_rules! require {
+  ($config:ident.$section:ident.$field:ident) => {
+    $config.$section.$field.as_deref().ok_or_else(|| {
+      Config::missing(concat!(stringify!($section), ".", stringify!($field)))
+    })
+  };
+}
+
+pub(crate) use require;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AdminsConfig {
+  pub(crate) mails: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AuthConfig {
+  pub(crate) allowed_origins: Vec<String>,
+  pub(crate) identity_provider: IdentityProviderKind,
+  pub(crate) pseudonym_key: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct DevConfig {
+  pub(crate) redirect_uri: Option<String>,
+  pub(crate) user_id: String,
+  pub(crate) user_mail: String,
+}
+
+impl Default for DevConfig {
+  fn default() -> Self {
+    Self {
+      redirect_uri: None,
+      user_id: "dev".into(),
+      user_mail: "dev@mail.mcgill.ca".into(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct MailConfig {
+  pub(crate) dir: PathBuf,
+  pub(crate) from: String,
+  pub(crate) transport: Option<MailTransport>,
+}
+
+impl Default for MailConfig {
+  fn default() -> Self {
+    Self {
+      dir: "mail".into(),
+      from: "mcgill.courses <noreply@mcgill.courses>".into(),
+      transport: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct MetricsConfig {
+  pub(crate) token: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct MicrosoftConfig {
+  pub(crate) client_id: Option<String>,
+  pub(crate) client_secret: Option<String>,
+  pub(crate) redirect_uri: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct MongodbConfig {
+  pub(crate) url: String,
+}
+
+impl Default for MongodbConfig {
+  fn default() -> Self {
+    Self {
+      url: "mongodb://localhost:27017/?directConnection=true&replicaSet=rs0"
+        .into(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct OidcConfig {
+  pub(crate) client_id: Option<String>,
+  pub(crate) client_secret: Option<String>,
+  pub(crate) issuer: Option<String>,
+  pub(crate) redirect_uri: Option<String>,
+  pub(crate) scopes: Vec<String>,
+}
+
+impl Default for OidcConfig {
+  fn default() -> Self {
+    Self {
+      client_id: None,
+      client_secret: None,
+      issuer: None,
+      redirect_uri: None,
+      scopes: vec!["openid".into(), "email".into()],
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct RateLimitConfig {
+  pub(crate) burst: u32,
+  pub(crate) enabled: bool,
+  pub(crate) interactions: u32,
+  pub(crate) period_ms: u64,
+  pub(crate) reviews: u32,
+  pub(crate) search: u32,
+}
+
+impl Default for RateLimitConfig {
+  fn default() -> Self {
+    Self {
+      burst: 100,
+      enabled: true,
+      interactions: 30,
+      period_ms: 10,
+      reviews: 10,
+      search: 120,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct S3Config {
+  pub(crate) bucket: String,
+}
+
+impl Default for S3Config {
+  fn default() -> Self {
+    Self {
+      bucket: "mcgill.courses".into(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct SecurityConfig {
+  pub(crate) content_security_policy: String,
+  /// Defaults to whether the server runs in production.
+  pub(crate) hsts: Option<bool>,
+}
+
+impl Default for SecurityConfig {
+  fn default() -> Self {
+    Self {
+      content_security_policy: concat!(
+        "default-src 'self'; ",
+        "script-src 'self' 'unsafe-inline' https://www.googletagmanager.com; ",
+        "style-src 'self' 'unsafe-inline'; ",
+        "img-src 'self' data: https:; ",
+        "connect-src 'self' https://*.google-analytics.com ",
+        "https://*.analytics.google.com https://www.googletagmanager.com; ",
+        "frame-ancestors 'none'; base-uri 'self'; form-action 'self'",
+      )
+      .into(),
+      hsts: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ServerConfig {
+  pub(crate) asset_dir: Option<PathBuf>,
+  pub(crate) db_name: String,
+  pub(crate) environment: Option<String>,
+  pub(crate) port: u16,
+}
+
+impl Default for ServerConfig {
+  fn default() -> Self {
+    Self {
+      asset_dir: None,
+      db_name: "admin".into(),
+      environment: None,
+      port: 8000,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct SessionsConfig {
+  pub(crate) expiry_days: u64,
+  pub(crate) sliding: bool,
+}
+
+impl Default for SessionsConfig {
+  fn default() -> Self {
+    Self {
+      expiry_days: 7,
+      sliding: false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct SmtpConfig {
+  pub(crate) host: Option<String>,
+  pub(crate) password: Option<String>,
+  /// Defaults to the usual port for `security`.
+  pub(crate) port: Option<u16>,
+  pub(crate) security: SmtpSecurity,
+  pub(crate) username: Option<String>,
+}
+
+/// Settings that replenish one request every `period`, up to `burst` at once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RateLimit {
+  pub(crate) burst: u32,
+  pub(crate) period: Duration,
+}
+
+/// Server settings, layered from lowest to highest precedence: defaults, the
+/// configuration file, environment variables and command line flags, which
+/// the caller sets on the result.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+  pub(crate) admins: AdminsConfig,
+  pub(crate) auth: AuthConfig,
+  pub(crate) dev: DevConfig,
+  pub(crate) mail: MailConfig,
+  pub(crate) metrics: MetricsConfig,
+  pub(crate) microsoft: MicrosoftConfig,
+  pub(crate) mongodb: MongodbConfig,
+  pub(crate) oidc: OidcConfig,
+  pub(crate) rate_limit: RateLimitConfig,
+  pub(crate) s3: S3Config,
+  pub(crate) security: SecurityConfig,
+  pub(crate) server: ServerConfig,
+  pub(crate) sessions: SessionsConfig,
+  pub(crate) smtp: SmtpConfig,
+}
+
+impl Config {
+  /// Reads the configuration file at `path`, if any, and overrides it with
+  /// the environment.
+  pub(crate) fn load(path: Option<&std::path::Path>) -> Result<Self> {
+    let file = match path {
+      Some(path) => Some((
+        path.display().to_string(),
+        fs::read_to_string(path).map_err(|error| {
+          anyhow!("Failed to read config file {}: {error}", path.display())
+        })?,
+      )),
+      None => None,
+    };
+
+    Self::from_sources(
+      file
+        .as_ref()
+        .map(|(name, source)| (name.as_str(), source.as_str())),
+      &env::vars().collect(),
+    )
+  }
+
+  pub(crate) fn from_sources(
+    file: Option<(&str, &str)>,
+    env: &HashMap<String, String>,
+  ) -> Result<Self> {
+    let mut table = toml::Table::new();
+
+    let mut errors = Vec::new();
+
+    if let Some((name, source)) = file {
+      // Checked on its own first, so mistakes are reported with their line
+      match toml::from_str::<Self>(source) {
+        Ok(_) => table = source.parse().expect("file was parsed above"),
+        Err(error) => {
+          let message = match error.message().trim() {
+            "" => "invalid TOML".into(),
+            message => message.replace('\n', ", "),
+          };
+
+          errors.push(match error.span() {
+            Some(span) => format!(
+              "{name}:{}: {message}",
+              source[..span.start].matches('\n').count() + 1
+            ),
+            None => format!("{name}: {message}"),
+          })
+        }
+      }
+    }
+
+    for variable in VARIABLES {
+      match env.get(variable.env).map(String::as_str) {
+        None | Some("") => {}
+        Some(value) => match variable.kind.parse(value).and_then(|value| {
+          // Checked on its own too, so mistakes are reported with the
+          // variable they came from
+          let mut setting = toml::Table::new();
+          Self::insert(&mut setting, variable.key, value.clone());
+          toml::Value::Table(setting)
+            .try_into::<Self>()
+            .map_err(|error| error.message().to_owned())?;
+          Ok(value)
+        }) {
+          Ok(value) => Self::insert(&mut table, variable.key, value),
+          Err(error) => {
+            errors.push(format!("invalid {}, {error}", variable.env))
+          }
+        },
+      }
+    }
+
+    if !errors.is_empty() {
+      return Err(Self::invalid(errors));
+    }
+
+    let config = toml::Value::Table(table)
+      .try_into::<Self>()
+      .map_err(|error| Self::invalid(vec![error.message().to_owned()]))?;
+
+    if let Err(error) = config.rate_limit() {
+      return Err(Self::invalid(vec![error.to_string()]));
+    }
+
+    Ok(config)
+  }
+
+  fn invalid(errors: Vec<String>) -> Error {
+    Error(anyhow!("Invalid configuration:\n  {}", errors.join("\n  ")))
+  }
+
+  /// Sets `value` at the dotted `key`, creating tables along the way.
+  fn insert(table: &mut toml::Table, key: &str, value: toml::Value) {
+    match key.split_once('.') {
+      Some((section, key)) => {
+        let section = table
+          .entry(section)
+          .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+
+        if let toml::Value::Table(section) = section {
+          Self::insert(section, key, value);
+        }
+      }
+      None => {
+        table.insert(key.into(), value);
+      }
+    }
+  }
+
+  /// The error for a required setting at `key` that isn't set.
+  pub(crate) fn missing(key: &str) -> Error {
+    Error(anyhow!(
+      "Missing `{key}`, set it in the config file or with {}",
+      VARIABLES
+        .iter()
+        .find(|variable| variable.key == key)
+        .map(|variable| variable.env)
+        .unwrap_or("?")
+    ))
+  }
+
+  pub(crate) fn production(&self) -> bool {
+    self.server.environment.as_deref() == Some("production")
+  }
+
+  /// The global rate limit, or `None` if it's turned off.
+  pub(crate) fn rate_limit(&self) -> Result<Option<RateLimit>> {
+    let RateLimitConfig {
+      burst,
+      enabled,
+      period_ms,
+      ..
+    } = self.rate_limit;
+
+    if !enabled {
+      return Ok(None);
+    }
+
+    if burst == 0 || period_ms == 0 {
+      return Err(Error(anyhow!(
+        "`rate_limit.burst` and `rate_limit.period_ms` must be positive"
+      )));
+    }
+
+    Ok(Some(RateLimit {
+      burst,
+      period: Duration::from_millis(period_ms),
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+    vars
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn defaults() {
+    let config = Config::from_sources(None, &HashMap::new()).unwrap();
+
+    assert_eq!(config, Config::default());
+
+    assert_eq!(config.server.port, 8000);
+    assert_eq!(config.s3.bucket, "mcgill.courses");
+    assert_eq!(config.oidc.scopes, ["openid", "email"]);
+    assert_eq!(config.mail.transport, None);
+    assert!(!config.production());
+    assert_eq!(
+      config.rate_limit().unwrap(),
+      Some(RateLimit {
+        burst: 100,
+        period: Duration::from_millis(10),
+      })
+    );
+  }
+
+  #[test]
+  fn layers() {
+    let config = Config::from_sources(
+      Some((
+        "config.toml",
+        r#"
+[server]
+port = 9000
+db_name = "from-file"
+environment = "production"
+
+[admins]
+mails = ["file@mail.mcgill.ca"]
+
+[mail]
+transport = "file"
+
+[rate_limit]
+enabled = false
+"#,
+      )),
+      &env(&[
+        ("DB_NAME", "from-env"),
+        ("ADMIN_MAILS", "a@mail.mcgill.ca, b@mail.mcgill.ca"),
+        ("MAIL_TRANSPORT", ""),
+        ("SMTP_SECURITY", "tls"),
+      ]),
+    )
+    .unwrap();
+
+    assert_eq!(config.server.port, 9000);
+    assert_eq!(config.server.db_name, "from-env");
+    assert_eq!(
+      config.admins.mails,
+      ["a@mail.mcgill.ca", "b@mail.mcgill.ca"]
+    );
+    assert_eq!(config.mail.transport, Some(MailTransport::File));
+    assert_eq!(config.smtp.security, SmtpSecurity::Tls);
+    assert!(config.production());
+    assert_eq!(config.rate_limit().unwrap(), None);
+  }
+
+  #[test]
+  fn errors_are_collected() {
+    assert_eq!(
+      Config::from_sources(
+        Some(("config.toml", "[server]\nport = \"80\"")),
+        &env(&[("SESSION_SLIDING_EXPIRY", "yes"), ("PORT", "-1")]),
+      )
+      .unwrap_err()
+      .to_string(),
+      [
+        "Invalid configuration:",
+        "  config.toml:2: invalid type: string \"80\", expected u16",
+        "  invalid PORT, expected a non-negative integer, found `-1`",
+        "  invalid SESSION_SLIDING_EXPIRY, expected `true` or `false`, found `yes`",
+      ]
+      .join("\n")
+    );
+
+    assert_eq!(
+      Config::from_sources(
+        Some(("config.toml", "[server]\nport = [80")),
+        &HashMap::new(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "Invalid configuration:\n  config.toml:2: invalid array, expected `]`"
+    );
+
+    assert_eq!(
+      Config::from_sources(
+        None,
+        &env(&[
+          ("PORT", "70000"),
+          ("RATE_LIMIT_BURST", "0"),
+          ("SMTP_SECURITY", "ssl"),
+        ])
+      )
+      .unwrap_err()
+      .to_string(),
+      [
+        "Invalid configuration:",
+        "  invalid PORT, invalid value: integer `70000`, expected u16",
+        "  invalid SMTP_SECURITY, unknown variant `ssl`, expected one of `none`, `starttls`, `tls`",
+      ]
+      .join("\n")
+    );
+
+    assert_eq!(
+      Config::from_sources(None, &env(&[("RATE_LIMIT_BURST", "0")]))
+        .unwrap_err()
+        .to_string(),
+      "Invalid configuration:\n  `rate_limit.burst` and `rate_limit.period_ms` must be positive"
+    );
+  }
+
+  #[test]
+  fn unknown_settings_are_rejected() {
+    assert_eq!(
+      Config::from_sources(
+        Some(("config.toml", "[server]\nport = 80\nprot = 80")),
+        &HashMap::new(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "Invalid configuration:\n  config.toml:3: unknown field `prot`, \
+       expected one of `asset_dir`, `db_name`, `environment`, `port`"
+    );
+  }
+
+  #[test]
+  fn missing_settings_name_their_variable() {
+    let config = Config::default();
+
+    assert_eq!(
+      require!(config.microsoft.client_id)
+        .unwrap_err()
+        .to_string(),
+      "Missing `microsoft.client_id`, set it in the config file or with MS_CLIENT_ID"
+    );
+  }
+
+  #[test]
+  fn variables_are_unique_sorted_and_known() {
+    for pair in VARIABLES.windows(2) {
+      assert!(pair[0].key < pair[1].key, "{}", pair[1].key);
+    }
+
+    for variable in VARIABLES {
+      assert_eq!(
+        VARIABLES
+          .iter()
+          .filter(|other| other.env == variable.env)
+          .count(),
+        1,
+        "{}",
+        variable.env
+      );
+
+      let value = match variable.kind {
+        Kind::Boolean => "true",
+        Kind::Integer => "1",
+        Kind::List => "a",
+        Kind::String => match variable.key {
+          "auth.identity_provider" => "dev",
+          "mail.transport" => "memory",
+          "smtp.security" => "none",
+          _ => "a",
+        },
+      };
+
+      assert!(
+        Config::from_sources(None, &env(&[(variable.env, value)])).is_ok(),
+        "{}",
+        variable.env
+      );
+    }
+  }
+}
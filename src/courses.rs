@@ -1,41 +1,122 @@
 use super::*;
 
-#[derive(Deserialize)]
-pub(crate) struct GetCoursesParams {
-  limit: Option<i64>,
-  offset: Option<u64>,
-  with_course_count: Option<bool>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct GetCoursesPayload {
-  pub(crate) courses: Vec<Course>,
-  pub(crate) course_count: Option<u64>,
-}
-
+/// List courses matching a filter
+#[utoipa::path(
+  post,
+  path = "/api/v1/courses",
+  params(GetCoursesParams),
+  request_body = CourseFilter,
+  responses(
+    (
+      status = 200,
+      description = "The matching courses",
+      body = GetCoursesPayload,
+    ),
+  ),
+)]
 pub(crate) async fn get_courses(
   Query(params): Query<GetCoursesParams>,
   AppState(db): AppState<Arc<Db>>,
   filter: Json<CourseFilter>,
 ) -> Result<impl IntoResponse> {
-  Ok(Json(GetCoursesPayload {
-    courses: db
-      .courses(params.limit, params.offset, Some(filter.0))
+  Ok(Json(
+    courses(
+      &db,
+      params.limit,
+      params.offset,
+      params.with_course_count,
+      filter.0,
+    )
+    .await?,
+  ))
+}
+
+/// List courses matching a filter
+#[utoipa::path(
+  get,
+  path = "/api/v1/courses",
+  params(ListCoursesParams),
+  responses(
+    (
+      status = 200,
+      description = "The matching courses",
+      body = GetCoursesPayload,
+    ),
+    (status = 400, description = "`filter` is not a course filter"),
+  ),
+)]
+pub(crate) async fn list_courses(
+  Query(params): Query<ListCoursesParams>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<Response> {
+  let filter = match params.filter.as_deref().map(serde_json::from_str) {
+    Some(Ok(filter)) => filter,
+    Some(Err(error)) => {
+      return Ok(
+        (
+          StatusCode::BAD_REQUEST,
+          format!("Invalid `filter`: {error}"),
+        )
+          .into_response(),
+      )
+    }
+    None => CourseFilter::default(),
+  };
+
+  Ok(
+    Json(
+      courses(
+        &db,
+        params.limit,
+        params.offset,
+        params.with_course_count,
+        filter,
+      )
       .await?,
-    course_count: if params.with_course_count.unwrap_or(false) {
+    )
+    .into_response(),
+  )
+}
+
+async fn courses(
+  db: &Db,
+  limit: Option<i64>,
+  offset: Option<u64>,
+  with_course_count: Option<bool>,
+  filter: CourseFilter,
+) -> Result<GetCoursesPayload> {
+  Ok(GetCoursesPayload {
+    courses: db.courses(limit, offset, Some(filter)).await?,
+    course_count: if with_course_count.unwrap_or(false) {
       Some(db.course_count().await?)
     } else {
       None
     },
-  }))
+  })
 }
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct GetCourseParams {
-  with_reviews: Option<bool>,
+/// A course, along with its reviews when asked for.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub(crate) enum GetCoursePayload {
+  Course(Course),
+  WithReviews(GetCourseWithReviewsPayload),
 }
 
+/// Get a course
+#[utoipa::path(
+  get,
+  path = "/api/v1/courses/{id}",
+  params(("id" = String, Path), GetCourseParams),
+  responses(
+    (
+      status = 200,
+      description = "The course, along with its reviews when `with_reviews` is set",
+      body = GetCoursePayload,
+    ),
+    (status = 404, description = "Not found"),
+  ),
+)]
 pub(crate) async fn get_course_by_id(
   Path(id): Path<String>,
   Query(params): Query<GetCourseParams>,
@@ -43,22 +124,80 @@ pub(crate) async fn get_course_by_id(
 ) -> Result<impl IntoResponse> {
   Ok(match state.db.find_course_by_id(&id).await? {
     Some(course) => {
-      let mut reviews = params
-        .with_reviews
-        .unwrap_or(false)
-        .then_some(state.db.find_reviews_by_course_id(&id).await?);
+      let reviews = params.with_reviews.unwrap_or(false).then_some(
+        state
+          .pseudonyms
+          .reviews(state.db.find_reviews_by_course_id(&id).await?),
+      );
 
-      if let Some(ref mut reviews) = reviews {
+      if let Some(mut reviews) = reviews {
         reviews.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
 
         return Ok((
           StatusCode::OK,
-          Json(Some(json!({ "course": course, "reviews": reviews }))),
+          Json(Some(GetCoursePayload::WithReviews(
+            GetCourseWithReviewsPayload { course, reviews },
+          ))),
         ));
       }
 
-      (StatusCode::OK, Json(Some(json!(course))))
+      (StatusCode::OK, Json(Some(GetCoursePayload::Course(course))))
     }
     None => (StatusCode::NOT_FOUND, Json(None)),
   })
 }
+
+/// Get review statistics
+#[utoipa::path(
+  get,
+  path = "/api/v1/courses/{id}/stats",
+  params(("id" = String, Path), GetCourseStatsParams),
+  responses(
+    (
+      status = 200,
+      description = "Rating and difficulty statistics",
+      body = ReviewStats,
+    ),
+    (status = 404, description = "Not found"),
+  ),
+)]
+pub(crate) async fn get_course_stats(
+  Path(id): Path<String>,
+  Query(params): Query<GetCourseStatsParams>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  Ok(match db.find_course_by_id(&id).await? {
+    Some(_) => (
+      StatusCode::OK,
+      Json(Some(
+        db.review_stats(&id, params.instructor_name.as_deref())
+          .await?,
+      )),
+    ),
+    None => (StatusCode::NOT_FOUND, Json(None)),
+  })
+}
+
+/// List changes to a course
+#[utoipa::path(
+  get,
+  path = "/api/v1/courses/{id}/changes",
+  params(("id" = String, Path)),
+  responses(
+    (
+      status = 200,
+      description = "The course's changes, newest first",
+      body = Vec<CourseChange>,
+    ),
+    (status = 404, description = "Not found"),
+  ),
+)]
+pub(crate) async fn get_course_changes(
+  Path(id): Path<String>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  Ok(match db.find_course_by_id(&id).await? {
+    Some(_) => (StatusCode::OK, Json(Some(db.course_changes(&id).await?))),
+    None => (StatusCode::NOT_FOUND, Json(None)),
+  })
+}
@@ -0,0 +1,217 @@
+use super::*;
+
+/// Marks a route, or some fields of its response, as going away.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Deprecation {
+  /// Response fields being removed, or empty if it's the whole route.
+  pub(crate) fields: &'static [&'static str],
+  pub(crate) since: NaiveDate,
+  /// When it will stop working, once that's been decided.
+  pub(crate) sunset: Option<NaiveDate>,
+}
+
+/// Routes, in axum's syntax, that are going away, which are marked as such in
+/// the OpenAPI document too.
+const DEPRECATIONS: &[(Method, &str, Deprecation)] = &[];
+
+impl Deprecation {
+  /// How `method` on the route `path`, in axum's syntax, is deprecated.
+  pub(crate) fn for_route(method: &Method, path: &str) -> Option<Self> {
+    DEPRECATIONS
+      .iter()
+      .find(|(deprecated, route, _)| deprecated == method && *route == path)
+      .map(|(_, _, deprecation)| *deprecation)
+  }
+
+  /// The unversioned `/api` routes, kept as an alias of `/api/v1` while
+  /// clients migrate.
+  fn unversioned() -> Self {
+    Self {
+      fields: &[],
+      since: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+      sunset: None,
+    }
+  }
+
+  fn timestamp(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).unwrap().and_utc()
+  }
+
+  /// Adds `Deprecation` and `Sunset` headers (RFC 9745 and RFC 8594) for a
+  /// deprecated route, or `Deprecated-Fields` if only some of its fields are,
+  /// linking to the OpenAPI document that describes them.
+  pub(crate) fn apply(&self, headers: &mut HeaderMap) -> Result {
+    if self.fields.is_empty() {
+      headers.insert(
+        DEPRECATION,
+        format!("@{}", Self::timestamp(self.since).timestamp()).parse()?,
+      );
+
+      if let Some(sunset) = self.sunset {
+        headers.insert(
+          SUNSET,
+          Self::timestamp(sunset)
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string()
+            .parse()?,
+        );
+      }
+    } else {
+      headers.insert(DEPRECATED_FIELDS, self.fields.join(", ").parse()?);
+    }
+
+    let link = HeaderValue::from_str(&format!(
+      "<{API_V1}/openapi.json>; rel=\"deprecation\""
+    ))?;
+
+    if !headers
+      .get_all(header::LINK)
+      .iter()
+      .any(|value| *value == link)
+    {
+      headers.append(header::LINK, link);
+    }
+
+    Ok(())
+  }
+}
+
+const DEPRECATED_FIELDS: HeaderName =
+  HeaderName::from_static("deprecated-fields");
+
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// Adds deprecation headers to responses from the unversioned `/api` alias
+/// and from routes in `DEPRECATIONS`.
+pub(crate) async fn headers(
+  matched_path: Option<MatchedPath>,
+  request: Request<Body>,
+  next: Next,
+) -> Result<Response> {
+  let method = request.method().clone();
+
+  let uri = request.uri().clone();
+
+  let mut response = next.run(request).await;
+
+  let Some(matched_path) = matched_path else {
+    return Ok(response);
+  };
+
+  let (route, alias) = match alias_route(matched_path.as_str()) {
+    Some(route) => (format!("{API_V1}{route}"), true),
+    None => (matched_path.as_str().to_string(), false),
+  };
+
+  let mut deprecations = Deprecation::for_route(&method, &route)
+    .into_iter()
+    .collect::<Vec<Deprecation>>();
+
+  if alias
+    && !deprecations
+      .iter()
+      .any(|deprecation| deprecation.fields.is_empty())
+  {
+    deprecations.push(Deprecation::unversioned());
+  }
+
+  let headers = response.headers_mut();
+
+  for deprecation in deprecations {
+    deprecation.apply(headers)?;
+  }
+
+  if alias {
+    let successor = format!(
+      "{API_V1}{}",
+      uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or_default()
+        .trim_start_matches("/api")
+    );
+
+    headers.append(
+      header::LINK,
+      format!("<{successor}>; rel=\"successor-version\"").parse()?,
+    );
+  }
+
+  Ok(response)
+}
+
+/// The route relative to the api root if `path` is one of the unversioned
+/// aliases.
+fn alias_route(path: &str) -> Option<&str> {
+  if path.starts_with(&format!("{API_V1}/")) || path.starts_with("/api/auth/") {
+    return None;
+  }
+
+  path
+    .strip_prefix("/api")
+    .filter(|path| path.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+  }
+
+  #[test]
+  fn route_deprecation_headers() {
+    let mut headers = HeaderMap::new();
+
+    Deprecation {
+      fields: &[],
+      since: date(2026, 1, 1),
+      sunset: Some(date(2026, 7, 1)),
+    }
+    .apply(&mut headers)
+    .unwrap();
+
+    assert_eq!(headers[DEPRECATION], "@1767225600");
+    assert_eq!(headers[SUNSET], "Wed, 01 Jul 2026 00:00:00 GMT");
+    assert_eq!(
+      headers[header::LINK],
+      "</api/v1/openapi.json>; rel=\"deprecation\""
+    );
+    assert!(!headers.contains_key(DEPRECATED_FIELDS));
+  }
+
+  #[test]
+  fn field_deprecation_headers() {
+    let mut headers = HeaderMap::new();
+
+    Deprecation {
+      fields: &["uniqueUserCount", "reviews"],
+      since: date(2026, 1, 1),
+      sunset: None,
+    }
+    .apply(&mut headers)
+    .unwrap();
+
+    assert_eq!(headers[DEPRECATED_FIELDS], "uniqueUserCount, reviews");
+    assert!(!headers.contains_key(DEPRECATION));
+    assert!(!headers.contains_key(SUNSET));
+
+    Deprecation::unversioned().apply(&mut headers).unwrap();
+
+    assert_eq!(headers[DEPRECATION], "@1792368000");
+    assert_eq!(headers.get_all(header::LINK).iter().count(), 1);
+  }
+
+  #[test]
+  fn alias_routes() {
+    assert_eq!(alias_route("/api/reviews"), Some("/reviews"));
+    assert_eq!(alias_route("/api/courses/:id"), Some("/courses/:id"));
+    assert_eq!(alias_route("/api/v1/reviews"), None);
+    assert_eq!(alias_route("/api/auth/login"), None);
+    assert_eq!(alias_route("/apis"), None);
+    assert_eq!(alias_route("/.well-known/x"), None);
+  }
+}
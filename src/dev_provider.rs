@@ -0,0 +1,125 @@
+use super::*;
+
+/// Signs everyone in as a single configured user without contacting any
+/// external service, for running and testing the app offline.
+#[derive(Debug)]
+pub(crate) struct DevProvider {
+  redirect_url: Url,
+  user: User,
+}
+
+impl DevProvider {
+  const DEFAULT_REDIRECT_URI: &'static str =
+    "http://localhost:8000/api/auth/authorized";
+
+  pub(crate) fn new(user: User, redirect_url: Url) -> Self {
+    Self { redirect_url, user }
+  }
+
+  pub(crate) fn from_config(config: &Config) -> Result<Self> {
+    if config.production() {
+      return Err(Error(anyhow!(
+        "The dev identity provider cannot be used in production"
+      )));
+    }
+
+    let provider = Self::new(
+      User::new(&config.dev.user_id, &config.dev.user_mail),
+      Url::parse(
+        config
+          .dev
+          .redirect_uri
+          .as_deref()
+          .unwrap_or(Self::DEFAULT_REDIRECT_URI),
+      )?,
+    );
+
+    warn!(
+      "Using the dev identity provider, every login signs in as {}",
+      provider.user.mail()
+    );
+
+    Ok(provider)
+  }
+}
+
+#[async_trait]
+impl IdentityProvider for DevProvider {
+  fn authorize_url(&self, state: &str, challenge: &PkceCodeChallenge) -> Url {
+    let mut url = self.redirect_url.clone();
+
+    url
+      .query_pairs_mut()
+      .append_pair("code", challenge.as_str())
+      .append_pair("state", state);
+
+    url
+  }
+
+  fn redirect_url(&self) -> &Url {
+    &self.redirect_url
+  }
+
+  async fn user(
+    &self,
+    _client: &reqwest::Client,
+    code: &str,
+    verifier: &str,
+  ) -> Result<User> {
+    let challenge = PkceCodeChallenge::from_code_verifier_sha256(
+      &PkceCodeVerifier::new(verifier.into()),
+    );
+
+    if challenge.as_str() != code {
+      return Err(Error(anyhow!("Code does not match the PKCE verifier")));
+    }
+
+    Ok(self.user.clone())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn provider() -> DevProvider {
+    DevProvider::new(
+      User::new("dev", "dev@mail.mcgill.ca"),
+      Url::parse(DevProvider::DEFAULT_REDIRECT_URI).unwrap(),
+    )
+  }
+
+  #[tokio::test]
+  async fn code_is_bound_to_verifier() {
+    let provider = provider();
+
+    let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
+
+    let url = provider.authorize_url("state", &challenge);
+
+    let params = url
+      .query_pairs()
+      .into_owned()
+      .collect::<BTreeMap<String, String>>();
+
+    assert_eq!(params["state"], "state");
+
+    let client = reqwest::Client::new();
+
+    assert_eq!(
+      provider
+        .user(&client, &params["code"], verifier.secret())
+        .await
+        .unwrap()
+        .id(),
+      "dev"
+    );
+
+    let (_, other) = PkceCodeChallenge::new_random_sha256();
+
+    assert!(provider
+      .user(&client, &params["code"], other.secret())
+      .await
+      .is_err());
+  }
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+#[derive(Debug)]
+pub(crate) struct FileMailer {
+  dir: PathBuf,
+}
+
+impl FileMailer {
+  pub(crate) fn new(dir: PathBuf) -> Self {
+    Self { dir }
+  }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+  async fn send(&self, mail: &Mail) -> Result {
+    let now = Utc::now();
+
+    let message = mail.message(now)?;
+
+    fs::create_dir_all(&self.dir)?;
+
+    let path = self.dir.join(format!(
+      "{}-{}.eml",
+      now.format("%Y%m%dT%H%M%S%.6f"),
+      Mail::mailbox(&mail.to)?.email
+    ));
+
+    info!("Writing mail for {} to {}", mail.to, path.display());
+
+    fs::write(path, message.formatted())?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn writes_rendered_mail() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mailer = FileMailer::new(dir.path().join("mail"));
+
+    mailer
+      .send(&Mail {
+        body: "foo".into(),
+        from: "noreply@mcgill.courses".into(),
+        subject: "bar".into(),
+        to: "a@mail.mcgill.ca".into(),
+      })
+      .await
+      .unwrap();
+
+    let entries = fs::read_dir(dir.path().join("mail"))
+      .unwrap()
+      .collect::<Result<Vec<_>, _>>()
+      .unwrap();
+
+    assert_eq!(entries.len(), 1);
+
+    let content = fs::read_to_string(entries[0].path()).unwrap();
+
+    assert!(content.contains("To: a@mail.mcgill.ca\r\n"));
+    assert!(content.ends_with("\r\n\r\nfoo"));
+  }
+}
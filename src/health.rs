@@ -0,0 +1,91 @@
+use super::*;
+
+/// How long readiness checks wait on the database before giving up.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Check that the server is up
+#[utoipa::path(
+  get,
+  path = "/healthz",
+  responses((status = 200, description = "The server is up")),
+)]
+pub(crate) async fn healthz() -> &'static str {
+  "ok"
+}
+
+/// Check that the server can serve data
+#[utoipa::path(
+  get,
+  path = "/readyz",
+  responses(
+    (
+      status = 200,
+      description = "The database is reachable, indexed and initialized",
+      body = ReadinessPayload,
+    ),
+    (
+      status = 503,
+      description = "Some of the checks failed",
+      body = ReadinessPayload,
+    ),
+  ),
+)]
+pub(crate) async fn readyz(
+  AppState(db): AppState<Arc<Db>>,
+) -> impl IntoResponse {
+  let database = matches!(
+    tokio::time::timeout(READINESS_TIMEOUT, db.ping()).await,
+    Ok(Ok(()))
+  );
+
+  let indexes = database
+    && matches!(
+      tokio::time::timeout(READINESS_TIMEOUT, db.indexed()).await,
+      Ok(Ok(true))
+    );
+
+  let initialization = db.initialization();
+
+  let ready = database && indexes && initialization.is_settled();
+
+  (
+    if ready {
+      StatusCode::OK
+    } else {
+      StatusCode::SERVICE_UNAVAILABLE
+    },
+    Json(ReadinessPayload {
+      database,
+      indexes,
+      initialization: initialization.state,
+      ready,
+    }),
+  )
+}
+
+/// Get the progress of database initialization
+#[utoipa::path(
+  get,
+  path = "/api/v1/admin/initialization",
+  responses(
+    (
+      status = 200,
+      description = "Progress through each seed file",
+      body = InitializationStatus,
+    ),
+    (status = 403, description = "The user is not an admin"),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn get_initialization(
+  user: User,
+  AppState(admins): AppState<Admins>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Response {
+  if !admins.contains(&user) {
+    return (StatusCode::FORBIDDEN, "only admins can view initialization")
+      .into_response();
+  }
+
+  Json(db.initialization()).into_response()
+}
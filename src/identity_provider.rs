@@ -0,0 +1,93 @@
+use super::*;
+
+#[async_trait]
+pub(crate) trait IdentityProvider: fmt::Debug + Send + Sync {
+  /// Where to send the browser to sign in, carrying `state` and the PKCE
+  /// challenge back to `redirect_url` once the user has authenticated.
+  fn authorize_url(&self, state: &str, challenge: &PkceCodeChallenge) -> Url;
+
+  fn redirect_url(&self) -> &Url;
+
+  async fn user(
+    &self,
+    client: &reqwest::Client,
+    code: &str,
+    verifier: &str,
+  ) -> Result<User>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum IdentityProviderKind {
+  Dev,
+  #[default]
+  Microsoft,
+  Oidc,
+}
+
+pub(crate) async fn from_config(
+  config: &Config,
+  client: &reqwest::Client,
+) -> Result<Arc<dyn IdentityProvider>> {
+  Ok(match config.auth.identity_provider {
+    IdentityProviderKind::Dev => Arc::new(DevProvider::from_config(config)?),
+    IdentityProviderKind::Microsoft => {
+      Arc::new(MicrosoftProvider::from_config(config)?)
+    }
+    IdentityProviderKind::Oidc => {
+      Arc::new(OidcProvider::from_config(config, client).await?)
+    }
+  })
+}
+
+#[derive(Debug, Deserialize)]
+struct AccessTokenResponse {
+  access_token: String,
+}
+
+/// Exchanges an authorization code for an access token using the client
+/// credentials and redirect url configured on `oauth_client`.
+pub(crate) async fn exchange_code(
+  client: &reqwest::Client,
+  oauth_client: &BasicClient,
+  client_secret: &str,
+  code: &str,
+  verifier: &str,
+  scope: Option<&str>,
+) -> Result<String> {
+  let mut params = vec![
+    ("client_id", oauth_client.client_id().to_string()),
+    ("client_secret", client_secret.to_string()),
+    ("code", code.to_string()),
+    ("code_verifier", verifier.to_string()),
+    ("grant_type", "authorization_code".to_string()),
+    (
+      "redirect_uri",
+      oauth_client
+        .redirect_url()
+        .ok_or(anyhow!("Missing redirect url"))?
+        .to_string(),
+    ),
+  ];
+
+  if let Some(scope) = scope {
+    params.push(("scope", scope.to_string()));
+  }
+
+  let token_url = oauth_client
+    .token_url()
+    .ok_or(anyhow!("Missing token url"))?;
+
+  Ok(
+    client
+      .post(token_url.as_str())
+      .form(&params)
+      .header("Accept", "application/json")
+      .send()
+      .await?
+      .error_for_status()?
+      .json::<AccessTokenResponse>()
+      .await?
+      .access_token,
+  )
+}
@@ -0,0 +1,305 @@
+use super::*;
+
+#[derive(Parser)]
+pub(crate) struct Importer {
+  #[clap(long, help = "CSV export of the course averages spreadsheet")]
+  input: PathBuf,
+  #[clap(
+    long,
+    default_value = "2",
+    help = "Number of header rows to skip before the data"
+  )]
+  skip_rows: usize,
+}
+
+impl Importer {
+  /// McGill's letter grades, which have no `C-`, as used by the sheet's
+  /// average column.
+  const GRADES: [&'static str; 9] =
+    ["A", "A-", "B+", "B", "B-", "C+", "C", "D", "F"];
+
+  pub(crate) fn run(&self, source: PathBuf) -> Result {
+    if !source.is_dir() {
+      return Err(Error(anyhow!(
+        "{} must be a seed directory to import averages into",
+        source.display()
+      )));
+    }
+
+    info!("Parsing course averages from {}...", self.input.display());
+
+    let averages = self.parse(&fs::read_to_string(&self.input)?)?;
+
+    let output = source.join("averages.json");
+
+    info!(
+      "Writing averages for {} courses to {}...",
+      averages.len(),
+      output.display()
+    );
+
+    fs::write(output, serde_json::to_string_pretty(&averages)?)?;
+
+    Ok(())
+  }
+
+  fn parse(&self, content: &str) -> Result<Vec<CourseAverages>> {
+    let mut averages = BTreeMap::<String, Vec<TermAverage>>::new();
+
+    for (index, line) in content.lines().enumerate().skip(self.skip_rows) {
+      let row = Self::split(line);
+
+      if row.iter().all(|field| field.trim().is_empty()) {
+        continue;
+      }
+
+      let line = index + 1;
+
+      let field = |column: usize| -> &str {
+        row
+          .get(column)
+          .map(|field| field.trim())
+          .unwrap_or_default()
+      };
+
+      let course_id = Self::course_id(field(1)).ok_or_else(|| {
+        anyhow!("Invalid course id `{}` on line {}", field(1), line)
+      })?;
+
+      let term = Self::term(field(2)).ok_or_else(|| {
+        anyhow!("Invalid term `{}` on line {}", field(2), line)
+      })?;
+
+      let average = field(3).to_uppercase();
+
+      if !Self::GRADES.contains(&average.as_str()) {
+        return Err(Error(anyhow!(
+          "Invalid average `{}` on line {}",
+          field(3),
+          line
+        )));
+      }
+
+      let entry = averages.entry(course_id).or_default();
+
+      if entry.iter().any(|existing| existing.term == term) {
+        warn!(
+          "Duplicate average for {} on line {}, replacing...",
+          term, line
+        );
+        entry.retain(|existing| existing.term != term);
+      }
+
+      entry.push(TermAverage { term, average });
+    }
+
+    Ok(
+      averages
+        .into_iter()
+        .map(|(course_id, averages)| CourseAverages {
+          course_id,
+          averages,
+        })
+        .collect(),
+    )
+  }
+
+  fn split(line: &str) -> Vec<String> {
+    let (mut fields, mut field, mut quoted) =
+      (Vec::new(), String::new(), false);
+
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+      match (c, quoted) {
+        ('"', true) if chars.peek() == Some(&'"') => {
+          field.push('"');
+          chars.next();
+        }
+        ('"', _) => quoted = !quoted,
+        (',', false) => fields.push(std::mem::take(&mut field)),
+        _ => field.push(c),
+      }
+    }
+
+    fields.push(field);
+
+    fields
+  }
+
+  fn course_id(value: &str) -> Option<String> {
+    let id = value
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .collect::<String>()
+      .to_uppercase();
+
+    let (subject, rest) = (id.get(..4)?, id.get(4..)?);
+
+    let (code, suffix) = (rest.get(..3)?, rest.get(3..)?);
+
+    let valid = subject.chars().all(|c| c.is_ascii_alphabetic())
+      && code.chars().all(|c| c.is_ascii_digit())
+      && match suffix.as_bytes() {
+        [] => true,
+        [letter, digit] => {
+          letter.is_ascii_alphabetic() && digit.is_ascii_digit()
+        }
+        _ => false,
+      };
+
+    valid.then_some(id)
+  }
+
+  fn term(value: &str) -> Option<String> {
+    let value = value.to_lowercase();
+
+    let (season, year) = match value.split_once(' ') {
+      Some((season, year)) => (season, year),
+      None => (value.get(..1)?, value.get(1..)?),
+    };
+
+    let season = match season {
+      "f" | "fall" => "Fall",
+      "w" | "winter" => "Winter",
+      "s" | "summer" => "Summer",
+      _ => return None,
+    };
+
+    (year.len() == 4 && year.chars().all(|c| c.is_ascii_digit()))
+      .then(|| format!("{season} {year}"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn importer() -> Importer {
+    Importer {
+      input: PathBuf::new(),
+      skip_rows: 2,
+    }
+  }
+
+  #[test]
+  fn parse_averages() {
+    let content = [
+      "Crowdsourced averages,,,,,",
+      "Timestamp,Course,Term,Average,Instructor,Notes",
+      "1/1/2024,COMP 202,W2024,B+,,",
+      "1/2/2024,comp202,F2023,A-,,",
+      "1/3/2024,MATH240,Winter 2024,b,\"Vetta, Adrian\",",
+      ",,,,,",
+      "1/4/2024,COMP396D1,S2023,A,,",
+    ]
+    .join("\n");
+
+    assert_eq!(
+      importer().parse(&content).unwrap(),
+      vec![
+        CourseAverages {
+          course_id: "COMP202".into(),
+          averages: vec![
+            TermAverage {
+              term: "Winter 2024".into(),
+              average: "B+".into(),
+            },
+            TermAverage {
+              term: "Fall 2023".into(),
+              average: "A-".into(),
+            },
+          ],
+        },
+        CourseAverages {
+          course_id: "COMP396D1".into(),
+          averages: vec![TermAverage {
+            term: "Summer 2023".into(),
+            average: "A".into(),
+          }],
+        },
+        CourseAverages {
+          course_id: "MATH240".into(),
+          averages: vec![TermAverage {
+            term: "Winter 2024".into(),
+            average: "B".into(),
+          }],
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn duplicate_terms_are_replaced() {
+    let content = [
+      "",
+      "",
+      "1/1/2024,COMP202,W2024,B+,,",
+      "1/2/2024,COMP202,W2024,B,,",
+    ]
+    .join("\n");
+
+    assert_eq!(
+      importer().parse(&content).unwrap(),
+      vec![CourseAverages {
+        course_id: "COMP202".into(),
+        averages: vec![TermAverage {
+          term: "Winter 2024".into(),
+          average: "B".into(),
+        }],
+      }]
+    );
+  }
+
+  #[test]
+  fn invalid_rows_are_rejected() {
+    let cases = [
+      (
+        "1/1/2024,COMP20,W2024,B+",
+        "Invalid course id `COMP20` on line 3",
+      ),
+      (
+        "1/1/2024,COMP202,X2024,B+",
+        "Invalid term `X2024` on line 3",
+      ),
+      ("1/1/2024,COMP202,W24,B+", "Invalid term `W24` on line 3"),
+      ("1/1/2024,COMP202,W2024,E", "Invalid average `E` on line 3"),
+      (
+        "1/1/2024,COMP202,W2024,C-",
+        "Invalid average `C-` on line 3",
+      ),
+    ];
+
+    for (row, error) in cases {
+      assert_eq!(
+        importer()
+          .parse(&format!("\n\n{row}"))
+          .unwrap_err()
+          .to_string(),
+        error
+      );
+    }
+  }
+
+  #[test]
+  fn terms_ignore_case() {
+    for (value, term) in [
+      ("W2024", Some("Winter 2024")),
+      ("f2023", Some("Fall 2023")),
+      ("Fall 2023", Some("Fall 2023")),
+      ("fall 2023", Some("Fall 2023")),
+      ("SUMMER 2023", Some("Summer 2023")),
+      ("autumn 2023", None),
+    ] {
+      assert_eq!(Importer::term(value).as_deref(), term, "{value}");
+    }
+  }
+
+  #[test]
+  fn split_quoted_fields() {
+    assert_eq!(
+      Importer::split("a,\"b, c\",\"d \"\"e\"\"\","),
+      vec!["a", "b, c", "d \"e\"", ""]
+    );
+  }
+}
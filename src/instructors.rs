@@ -1,14 +1,22 @@
 use super::*;
 
-#[derive(Debug, Deserialize, Serialize)]
-pub(crate) struct GetInstructorPayload {
-  pub(crate) instructor: Option<Instructor>,
-  pub(crate) reviews: Vec<Review>,
-}
-
+/// Get an instructor
+#[utoipa::path(
+  get,
+  path = "/api/v1/instructors/{name}",
+  params(("name" = String, Path)),
+  responses(
+    (
+      status = 200,
+      description = "The instructor and their reviews",
+      body = GetInstructorPayload,
+    ),
+  ),
+)]
 pub(crate) async fn get_instructor(
   Path(name): Path<String>,
   AppState(db): AppState<Arc<Db>>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
 ) -> Result<impl IntoResponse> {
   info!("Fetching instructor for name: {}", name);
 
@@ -20,7 +28,7 @@ pub(crate) async fn get_instructor(
       Some(ins) => {
         let mut reviews = db.find_reviews_by_instructor_name(&ins.name).await?;
         reviews.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-        reviews
+        pseudonyms.reviews(reviews)
       }
       None => vec![],
     },
@@ -1,39 +1,61 @@
 use super::*;
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct GetInteractionKindParams {
-  pub(crate) course_id: String,
-  pub(crate) user_id: String,
-  pub(crate) referrer: String,
-}
-
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
-pub(crate) struct GetInteractionKindPayload {
-  pub(crate) kind: Option<InteractionKind>,
-}
-
+/// Get how the user interacted with a review
+#[utoipa::path(
+  get,
+  path = "/api/v1/interactions",
+  params(GetInteractionKindParams),
+  responses(
+    (
+      status = 200,
+      description = "The interaction, if any",
+      body = GetInteractionKindPayload,
+    ),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn get_interaction_kind(
+  user: User,
   params: Query<GetInteractionKindParams>,
   AppState(db): AppState<Arc<Db>>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
 ) -> Result<impl IntoResponse> {
-  let kind = db
-    .interaction_kind(&params.course_id, &params.user_id, &params.referrer)
-    .await?;
+  let kind = match pseudonyms
+    .author(&db, &params.course_id, &params.user_id)
+    .await?
+  {
+    Some(author) => {
+      db.interaction_kind(&params.course_id, &author, &user.id())
+        .await?
+    }
+    None => None,
+  };
 
   Ok(Json(GetInteractionKindPayload { kind }))
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
-pub(crate) struct GetUserInteractionForCoursePayload {
-  pub(crate) course_id: String,
-  pub(crate) referrer: String,
-  pub(crate) interactions: Vec<Interaction>,
-}
-
+/// List the user's interactions on a course's reviews
+#[utoipa::path(
+  get,
+  path = "/api/v1/interactions/{course_id}",
+  params(("course_id" = String, Path)),
+  responses(
+    (
+      status = 200,
+      description = "The interactions",
+      body = GetUserInteractionForCoursePayload,
+    ),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn get_user_interactions_for_course(
-  Path((course_id, referrer)): Path<(String, String)>,
+  user: User,
+  Path(course_id): Path<String>,
   AppState(db): AppState<Arc<Db>>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
 ) -> Result<impl IntoResponse> {
+  let referrer = user.id();
+
   info!(
     "Fetching review interactions from {} for course {}",
     referrer, course_id
@@ -42,9 +64,10 @@ pub(crate) async fn get_user_interactions_for_course(
   Ok(Json(GetUserInteractionForCoursePayload {
     course_id: course_id.clone(),
     referrer: referrer.clone(),
-    interactions: db
-      .user_interactions_for_course(&course_id, &referrer)
-      .await?,
+    interactions: pseudonyms.interactions(
+      db.user_interactions_for_course(&course_id, &referrer)
+        .await?,
+    ),
   }))
 }
 
@@ -54,54 +77,84 @@ pub(crate) struct GetCourseReviewsInteractionPayload {
   pub(crate) interactions: Vec<Interaction>,
 }
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct AddInteractionBody {
-  kind: InteractionKind,
-  course_id: String,
-  user_id: String,
-  referrer: String,
-}
-
+/// Like or dislike a review
+#[utoipa::path(
+  post,
+  path = "/api/v1/interactions",
+  request_body = AddInteractionBody,
+  responses(
+    (status = 200, description = "Success"),
+    (status = 403, description = "Reviews can't be liked by their author"),
+    (status = 404, description = "Not found"),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn add_interaction(
   AppState(db): AppState<Arc<Db>>,
-  _user: User,
+  AppState(pseudonyms): AppState<Pseudonyms>,
+  user: User,
   body: Json<AddInteractionBody>,
-) -> Result<impl IntoResponse> {
+) -> Result<StatusCode> {
   info!(
     "Adding interaction for review {}/{}...",
     body.course_id, body.user_id
   );
 
+  let Some(author) = pseudonyms
+    .author(&db, &body.course_id, &body.user_id)
+    .await?
+  else {
+    return Ok(StatusCode::NOT_FOUND);
+  };
+
+  let referrer = user.id();
+
+  if author == referrer {
+    return Ok(StatusCode::FORBIDDEN);
+  }
+
   db.add_interaction(Interaction {
     kind: body.kind.clone(),
     course_id: body.course_id.clone(),
-    user_id: body.user_id.clone(),
-    referrer: body.referrer.clone(),
+    user_id: author,
+    referrer,
   })
   .await?;
 
-  Ok(())
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct DeleteInteractionBody {
-  course_id: String,
-  user_id: String,
-  referrer: String,
+  Ok(StatusCode::OK)
 }
 
+/// Remove an interaction
+#[utoipa::path(
+  delete,
+  path = "/api/v1/interactions",
+  request_body = DeleteInteractionBody,
+  responses(
+    (status = 200, description = "Success"),
+    (status = 404, description = "Not found"),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn delete_interaction(
   AppState(db): AppState<Arc<Db>>,
-  _user: User,
+  AppState(pseudonyms): AppState<Pseudonyms>,
+  user: User,
   body: Json<DeleteInteractionBody>,
-) -> Result<impl IntoResponse> {
+) -> Result<StatusCode> {
   info!(
     "Removing interaction for review {}/{}...",
     body.course_id, body.user_id
   );
 
-  db.delete_interaction(&body.course_id, &body.user_id, &body.referrer)
+  let Some(author) = pseudonyms
+    .author(&db, &body.course_id, &body.user_id)
+    .await?
+  else {
+    return Ok(StatusCode::NOT_FOUND);
+  };
+
+  db.delete_interaction(&body.course_id, &author, &user.id())
     .await?;
 
-  Ok(())
+  Ok(StatusCode::OK)
 }
@@ -59,6 +59,11 @@ pub(crate) struct Loader {
     help = "Scrape visual schedule builder information"
   )]
   scrape_vsb: bool,
+  #[clap(
+    long,
+    help = "Write scraping metrics here when done, in the Prometheus text format"
+  )]
+  metrics_file: Option<PathBuf>,
 }
 
 impl Loader {
@@ -131,6 +136,10 @@ impl Loader {
       }
     }
 
+    if let Some(metrics_file) = &self.metrics_file {
+      fs::write(metrics_file, telemetry::render()?)?;
+    }
+
     Ok(())
   }
 
@@ -212,6 +221,10 @@ impl Loader {
       listings?
     };
 
+    telemetry::SCRAPED_PAGES
+      .with_label_values(&["listing"])
+      .inc();
+
     thread::sleep(Duration::from_millis(self.page_delay));
 
     if let Some(listings) = listings {
@@ -256,6 +269,10 @@ impl Loader {
       course_page?
     };
 
+    telemetry::SCRAPED_PAGES
+      .with_label_values(&["course"])
+      .inc();
+
     info!(
       "Parsed course {}{}",
       &course_page.subject, &course_page.code
@@ -0,0 +1,162 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub(crate) enum LogFormat {
+  /// A JSON object per line, for log aggregators.
+  Json,
+  #[default]
+  Text,
+}
+
+/// Logs through `tracing`, filtered by `RUST_LOG`, with `log` records passed
+/// on to it. Lines logged inside a span, like the one each request is served
+/// in, carry its fields.
+pub(crate) fn init(format: LogFormat) {
+  format
+    .subscriber(std::io::stderr, std::io::stderr().is_terminal())
+    .init();
+}
+
+impl LogFormat {
+  fn subscriber<W>(
+    self,
+    writer: W,
+    ansi: bool,
+  ) -> Box<dyn Subscriber + Send + Sync>
+  where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+  {
+    let builder = tracing_subscriber::fmt()
+      .with_env_filter(
+        EnvFilter::try_from_default_env()
+          .unwrap_or_else(|_| EnvFilter::new("info")),
+      )
+      .with_writer(writer);
+
+    match self {
+      Self::Json => Box::new(
+        builder
+          .json()
+          .flatten_event(true)
+          .with_current_span(true)
+          .with_span_list(false)
+          .finish(),
+      ),
+      Self::Text => Box::new(builder.with_ansi(ansi).finish()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq, std::io};
+
+  #[derive(Clone, Default)]
+  struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+  impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+      io::Write::write(&mut *self.0.lock().unwrap(), buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+      Ok(())
+    }
+  }
+
+  impl Buffer {
+    fn lines(&self) -> Vec<String> {
+      String::from_utf8(self.0.lock().unwrap().clone())
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+    }
+  }
+
+  fn request_span() -> Span {
+    let span = info_span!(
+      "request",
+      request_id = %"a1",
+      route = %"/api/v1/reviews",
+      user_id = field::Empty,
+    );
+
+    span.record("user_id", field::display("u"));
+
+    span
+  }
+
+  #[tokio::test]
+  async fn text_lines() {
+    let buffer = Buffer::default();
+
+    let writer = buffer.clone();
+
+    let _guard = tracing::subscriber::set_default(
+      LogFormat::Text.subscriber(move || writer.clone(), false),
+    );
+
+    tokio::spawn(
+      async { tracing::info!("Adding review") }.instrument(request_span()),
+    )
+    .await
+    .unwrap();
+
+    let lines = buffer.lines();
+
+    assert_eq!(lines.len(), 1);
+
+    assert!(
+      lines[0].ends_with(
+        " INFO request{request_id=a1 route=/api/v1/reviews user_id=u}: \
+         server::logger::tests: Adding review"
+      ),
+      "{}",
+      lines[0]
+    );
+  }
+
+  #[tokio::test]
+  async fn json_lines() {
+    let buffer = Buffer::default();
+
+    let writer = buffer.clone();
+
+    let _guard = tracing::subscriber::set_default(
+      LogFormat::Json.subscriber(move || writer.clone(), false),
+    );
+
+    tokio::spawn(
+      async { tracing::warn!("a \"quoted\" message") }
+        .instrument(request_span()),
+    )
+    .await
+    .unwrap();
+
+    let lines = buffer.lines();
+
+    assert_eq!(lines.len(), 1);
+
+    let mut line = serde_json::from_str::<Value>(&lines[0]).unwrap();
+
+    assert!(line["timestamp"].is_string());
+
+    line.as_object_mut().unwrap().remove("timestamp");
+
+    assert_eq!(
+      line,
+      json!({
+        "level": "WARN",
+        "target": "server::logger::tests",
+        "message": "a \"quoted\" message",
+        "span": {
+          "name": "request",
+          "request_id": "a1",
+          "route": "/api/v1/reviews",
+          "user_id": "u",
+        },
+      })
+    );
+  }
+}
@@ -0,0 +1,122 @@
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Mail {
+  pub(crate) body: String,
+  pub(crate) from: String,
+  pub(crate) subject: String,
+  pub(crate) to: String,
+}
+
+impl Mail {
+  /// Builds the message, refusing addresses that would otherwise end up in
+  /// headers or SMTP commands as is.
+  pub(crate) fn message(&self, date: DateTime<Utc>) -> Result<Message> {
+    Ok(
+      Message::builder()
+        .date(date.into())
+        .from(Self::mailbox(&self.from)?)
+        .to(Self::mailbox(&self.to)?)
+        .subject(&self.subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(self.body.clone())?,
+    )
+  }
+
+  pub(crate) fn mailbox(value: &str) -> Result<Mailbox> {
+    if value.contains(['\r', '\n']) {
+      return Err(Error(anyhow!(
+        "Invalid mail address `{}`",
+        value.escape_debug()
+      )));
+    }
+
+    value.parse().map_err(|error| {
+      Error(anyhow!("Invalid mail address `{value}`: {error}"))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn mail() -> Mail {
+    Mail {
+      body: "Hello,\nworld\n".into(),
+      from: "mcgill.courses <noreply@mcgill.courses>".into(),
+      subject: "Your weekly digest".into(),
+      to: "a@mail.mcgill.ca".into(),
+    }
+  }
+
+  fn render(mail: &Mail) -> String {
+    String::from_utf8(
+      mail
+        .message(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap())
+        .unwrap()
+        .formatted(),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn message() {
+    assert_eq!(
+      render(&mail()),
+      [
+        "Date: Tue, 02 Jan 2024 03:04:05 +0000",
+        "From: mcgill.courses <noreply@mcgill.courses>",
+        "To: a@mail.mcgill.ca",
+        "Subject: Your weekly digest",
+        "Content-Type: text/plain; charset=utf-8",
+        "Content-Transfer-Encoding: 7bit",
+        "",
+        "Hello,",
+        "world",
+        "",
+      ]
+      .join("\r\n")
+    );
+  }
+
+  #[test]
+  fn non_ascii_subjects_are_encoded() {
+    assert!(render(&Mail {
+      subject: "Résumé".into(),
+      ..mail()
+    })
+    .contains("Subject: =?utf-8?b?UsOpc3Vtw6k=?=\r\n"));
+  }
+
+  #[test]
+  fn invalid_addresses_are_rejected() {
+    for (to, error) in [
+      (
+        "a@mail.mcgill.ca\r\nBcc: b@mail.mcgill.ca",
+        "Invalid mail address `a@mail.mcgill.ca\\r\\nBcc: b@mail.mcgill.ca`",
+      ),
+      (
+        "a@mail.mcgill.ca>\nRCPT TO:<b@mail.mcgill.ca",
+        "Invalid mail address `a@mail.mcgill.ca>\\nRCPT TO:<b@mail.mcgill.ca`",
+      ),
+      (
+        "a@mail.mcgill.ca> <b@mail.mcgill.ca",
+        "Invalid mail address `a@mail.mcgill.ca> <b@mail.mcgill.ca`: \
+         Invalid input",
+      ),
+      ("a", "Invalid mail address `a`: Invalid input"),
+    ] {
+      assert_eq!(
+        Mail {
+          to: to.into(),
+          ..mail()
+        }
+        .message(Utc::now())
+        .unwrap_err()
+        .to_string(),
+        error
+      );
+    }
+  }
+}
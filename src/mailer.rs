@@ -0,0 +1,26 @@
+use super::*;
+
+#[async_trait]
+pub(crate) trait Mailer: fmt::Debug + Send + Sync {
+  async fn send(&self, mail: &Mail) -> Result;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum MailTransport {
+  File,
+  Memory,
+  Smtp,
+}
+
+pub(crate) fn from_config(config: &Config) -> Result<Option<Arc<dyn Mailer>>> {
+  let Some(transport) = config.mail.transport else {
+    return Ok(None);
+  };
+
+  Ok(Some(match transport {
+    MailTransport::File => Arc::new(FileMailer::new(config.mail.dir.clone())),
+    MailTransport::Memory => Arc::new(MemoryMailer::default()),
+    MailTransport::Smtp => Arc::new(SmtpMailer::from_config(config)?),
+  }))
+}
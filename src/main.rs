@@ -1,19 +1,43 @@
 use {
   crate::{
+    admins::Admins,
+    allowed_origins::AllowedOrigins,
     arguments::Arguments,
     assets::Assets,
-    auth::{AuthRedirect, COOKIE_NAME},
+    auth::{AuthRedirect, AuthRejection, COOKIE_NAME},
+    catalog::CatalogVersion,
+    config::{require, Config, RateLimit, RateLimitConfig},
+    deprecation::Deprecation,
+    dev_provider::DevProvider,
     error::Error,
+    file_mailer::FileMailer,
     hash::Hash,
+    identity_provider::{IdentityProvider, IdentityProviderKind},
+    importer::Importer,
     loader::Loader,
+    logger::LogFormat,
+    mail::Mail,
+    mailer::{MailTransport, Mailer},
+    memory_mailer::MemoryMailer,
+    microsoft_provider::MicrosoftProvider,
+    notification_digest::NotificationDigest,
+    notification_hub::NotificationHub,
     object::Object,
+    oidc_provider::OidcProvider,
     options::Options,
     page::Page,
+    pseudonyms::Pseudonyms,
+    rate_limiter::RateLimiter,
+    rate_limits::{Policy, PolicyKey, RateLimited, RateLimits},
     retry::Retry,
-    server::Server,
+    security::SecurityHeaders,
+    server::{Server, API_V1},
+    sessions::SessionPolicy,
+    smtp_mailer::{SmtpMailer, SmtpSecurity},
     state::State,
     subcommand::Subcommand,
-    user::User,
+    telemetry::MetricsToken,
+    user::{MaybeUser, User},
     vec_ext::VecExt,
     vsb_client::VsbClient,
   },
@@ -23,32 +47,74 @@ use {
   axum::{
     body::Body,
     error_handling::HandleErrorLayer,
-    extract::{FromRef, FromRequestParts, Path, Query, State as AppState},
-    response::{IntoResponse, Redirect, Response},
-    routing::{get, post, Router},
+    extract::{
+      ConnectInfo, DefaultBodyLimit, FromRef, FromRequestParts, MatchedPath,
+      Path, Query, State as AppState,
+    },
+    middleware::{self, Next},
+    response::{
+      sse::{Event, KeepAlive, Sse},
+      IntoResponse, Redirect, Response,
+    },
+    routing::{delete, get, post, put, MethodRouter, Router},
     BoxError, Json, RequestPartsExt,
   },
   axum_extra::{
-    headers::Cookie, typed_header::TypedHeaderRejectionReason, TypedHeader,
+    headers::{Cookie, UserAgent},
+    typed_header::TypedHeaderRejectionReason,
+    TypedHeader,
   },
-  base64::{engine::general_purpose::STANDARD, Engine},
+  bson::oid::ObjectId,
   chrono::prelude::*,
-  clap::Parser,
+  clap::{Parser, ValueEnum},
   db::Db,
   dotenv::dotenv,
-  env_logger::Env,
-  futures::TryStreamExt,
+  futures::{future, stream, Stream, StreamExt, TryStreamExt},
+  governor::{
+    clock::{Clock, DefaultClock},
+    DefaultKeyedRateLimiter, Quota, RateLimiter as GovernorRateLimiter,
+  },
+  hmac::{Hmac, Mac},
   http::{
-    header, header::SET_COOKIE, request::Parts, HeaderMap, Request, StatusCode,
+    header, header::SET_COOKIE, request::Parts, HeaderMap, HeaderName,
+    HeaderValue, Method, Request, StatusCode,
+  },
+  lazy_static::lazy_static,
+  lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::{
+      authentication::Credentials, extension::ClientId as SmtpClientId,
+    },
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
   },
   log::{debug, error, info, trace, warn},
+  lru::LruCache,
   model::{
-    Course, CourseFilter, CourseListing, InitializeOptions, Instructor,
-    Interaction, InteractionKind, Review, ReviewFilter, Schedule, Subscription,
+    AddApiTokenBody, AddApiTokenPayload, AddInteractionBody,
+    AddOrDeleteSubscriptionBody, AddOrUpdateReviewBody, ApiToken,
+    ApiTokenPayload, Course, CourseAverages, CourseChange, CourseFilter,
+    CourseListing, CurrentUser, DeleteInteractionBody, DeleteReviewBody,
+    DeleteSessionsParams, DigestFrequency, GetCourseParams,
+    GetCourseStatsParams, GetCourseWithReviewsPayload, GetCoursesParams,
+    GetCoursesPayload, GetInstructorPayload, GetInteractionKindParams,
+    GetInteractionKindPayload, GetNotificationsParams, GetReviewsParams,
+    GetReviewsPayload, GetSessionsPayload, GetSubscriptionParams,
+    GetUserInteractionForCoursePayload, InitializationStatus,
+    InitializeOptions, Interaction, ListCoursesParams, ModerationOutcome,
+    Notification, NotificationPayload, NotificationPreferences,
+    RateLimitedPayload, ReadinessPayload, Review, ReviewFilter, ReviewStats,
+    Schedule, SearchParams, SearchResults, SessionMetadata, SessionPayload,
+    Subscription, SubscriptionTarget, TermAverage, TokenScope,
+    UpdateNotificationBody, UpdateNotificationPreferencesBody, UserExport,
+    UserResponse,
   },
   oauth2::{
     basic::BasicClient, AuthType, AuthUrl, ClientId, ClientSecret, CsrfToken,
-    RedirectUrl, Scope, TokenUrl,
+    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope, TokenUrl,
+  },
+  prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge,
+    HistogramVec, IntCounterVec, IntGauge, TextEncoder,
   },
   rayon::prelude::*,
   reqwest::blocking::RequestBuilder,
@@ -56,57 +122,91 @@ use {
   rusoto_s3::S3Client,
   rusoto_s3::{GetObjectRequest, PutObjectOutput, PutObjectRequest, S3},
   serde::{Deserialize, Serialize},
-  serde_json::json,
+  serde_json::{json, Value},
   sha2::{Digest, Sha256},
   std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashMap, HashSet},
     env,
     fmt::{self, Display, Formatter},
     fs,
     fs::File,
-    io::Read,
+    io::{IsTerminal, Read},
     marker::Sized,
     net::SocketAddr,
+    num::{NonZeroU32, NonZeroUsize},
     path::PathBuf,
     process,
-    sync::Arc,
+    sync::{Arc, Mutex},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
   },
+  tokio::sync::broadcast::{self, error::RecvError},
   tower::ServiceBuilder,
   tower_governor::{
-    errors::display_error, governor::GovernorConfigBuilder, GovernorLayer,
+    errors::{display_error, GovernorError},
+    governor::GovernorConfigBuilder,
+    GovernorLayer,
   },
   tower_http::{
+    compression::CompressionLayer,
     cors::CorsLayer,
     services::{ServeDir, ServeFile},
-    trace::TraceLayer,
   },
-  tracing::Span,
-  url::Url,
+  tracing::{field, info_span, Instrument, Span, Subscriber},
+  tracing_subscriber::{fmt::MakeWriter, util::SubscriberInitExt, EnvFilter},
+  url::{Origin, Url},
+  utoipa::{IntoParams, OpenApi, ToSchema},
   walkdir::WalkDir,
 };
 
+mod admins;
+mod allowed_origins;
+mod api_tokens;
 mod arguments;
 mod assets;
 mod auth;
+mod catalog;
+mod config;
 mod courses;
+mod deprecation;
+mod dev_provider;
 mod error;
+mod file_mailer;
 mod hash;
+mod health;
+mod identity_provider;
+mod importer;
 mod instructors;
 mod interactions;
 mod loader;
+mod logger;
+mod mail;
+mod mailer;
+mod memory_mailer;
+mod microsoft_provider;
+mod notification_digest;
+mod notification_hub;
 mod notifications;
 mod object;
+mod oidc_provider;
+mod openapi;
 mod options;
 mod page;
+mod pseudonyms;
+mod rate_limiter;
+mod rate_limits;
+mod request_context;
 mod retry;
 mod reviews;
 mod search;
+mod security;
 mod server;
+mod sessions;
+mod smtp_mailer;
 mod state;
 mod subcommand;
 mod subscriptions;
+mod telemetry;
 mod user;
 mod vec_ext;
 mod vsb_client;
@@ -115,9 +215,6 @@ type Result<T = (), E = error::Error> = std::result::Result<T, E>;
 
 #[tokio::main]
 async fn main() {
-  env_logger::Builder::from_env(Env::default().default_filter_or("info"))
-    .init();
-
   dotenv().ok();
 
   if let Err(error) = Arguments::parse().run().await {
@@ -0,0 +1,25 @@
+use super::*;
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MemoryMailer {
+  sent: Arc<Mutex<Vec<Mail>>>,
+}
+
+impl MemoryMailer {
+  #[cfg(test)]
+  pub(crate) fn sent(&self) -> Vec<Mail> {
+    self.sent.lock().unwrap().clone()
+  }
+}
+
+#[async_trait]
+impl Mailer for MemoryMailer {
+  async fn send(&self, mail: &Mail) -> Result {
+    // Refuses the same mail the other mailers would
+    mail.message(Utc::now())?;
+
+    debug!("Holding mail for {} in memory: {}", mail.to, mail.subject);
+    self.sent.lock().unwrap().push(mail.clone());
+    Ok(())
+  }
+}
@@ -0,0 +1,131 @@
+use super::*;
+
+#[derive(Debug)]
+pub(crate) struct MicrosoftProvider {
+  client_secret: String,
+  oauth_client: BasicClient,
+  redirect_url: Url,
+}
+
+impl MicrosoftProvider {
+  const AUTH_URL: &'static str =
+    "https://login.microsoftonline.com/common/oauth2/v2.0/authorize";
+
+  const TOKEN_URL: &'static str =
+    "https://login.microsoftonline.com/organizations/oauth2/v2.0/token";
+
+  const USER_URL: &'static str = "https://graph.microsoft.com/v1.0/me";
+
+  pub(crate) fn new(
+    client_id: String,
+    client_secret: String,
+    redirect_url: Url,
+  ) -> Result<Self> {
+    Ok(Self {
+      oauth_client: BasicClient::new(
+        ClientId::new(client_id),
+        Some(ClientSecret::new(client_secret.clone())),
+        AuthUrl::new(Self::AUTH_URL.into())?,
+        Some(TokenUrl::new(Self::TOKEN_URL.into())?),
+      )
+      .set_auth_type(AuthType::RequestBody)
+      .set_redirect_uri(RedirectUrl::from_url(redirect_url.clone())),
+      client_secret,
+      redirect_url,
+    })
+  }
+
+  pub(crate) fn from_config(config: &Config) -> Result<Self> {
+    Self::new(
+      require!(config.microsoft.client_id)?.into(),
+      require!(config.microsoft.client_secret)?.into(),
+      Url::parse(require!(config.microsoft.redirect_uri)?)?,
+    )
+  }
+}
+
+#[async_trait]
+impl IdentityProvider for MicrosoftProvider {
+  fn authorize_url(&self, state: &str, challenge: &PkceCodeChallenge) -> Url {
+    self
+      .oauth_client
+      .authorize_url(|| CsrfToken::new(state.into()))
+      .add_scope(Scope::new(String::from("openid")))
+      .add_scope(Scope::new(String::from("User.Read")))
+      .set_pkce_challenge(challenge.clone())
+      .url()
+      .0
+  }
+
+  fn redirect_url(&self) -> &Url {
+    &self.redirect_url
+  }
+
+  async fn user(
+    &self,
+    client: &reqwest::Client,
+    code: &str,
+    verifier: &str,
+  ) -> Result<User> {
+    debug!("Fetching token from Microsoft...");
+
+    let access_token = identity_provider::exchange_code(
+      client,
+      &self.oauth_client,
+      &self.client_secret,
+      code,
+      verifier,
+      Some("User.Read"),
+    )
+    .await?;
+
+    debug!("Fetching user data from Microsoft...");
+
+    Ok(
+      client
+        .get(Self::USER_URL)
+        .bearer_auth(access_token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn authorize_url() {
+    let provider = MicrosoftProvider::new(
+      "client".into(),
+      "secret".into(),
+      Url::parse("http://localhost:8000/api/auth/authorized").unwrap(),
+    )
+    .unwrap();
+
+    let (challenge, _) = PkceCodeChallenge::new_random_sha256();
+
+    let url = provider.authorize_url("state", &challenge);
+
+    let params = url
+      .query_pairs()
+      .into_owned()
+      .collect::<BTreeMap<String, String>>();
+
+    assert!(url.as_str().starts_with(MicrosoftProvider::AUTH_URL));
+
+    assert_eq!(params["client_id"], "client");
+    assert_eq!(params["code_challenge"], challenge.as_str());
+    assert_eq!(params["code_challenge_method"], "S256");
+    assert_eq!(
+      params["redirect_uri"],
+      "http://localhost:8000/api/auth/authorized"
+    );
+    assert_eq!(params["scope"], "openid User.Read");
+    assert_eq!(params["state"], "state");
+  }
+}
@@ -0,0 +1,293 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+pub(crate) struct NotificationDigest {
+  db: Arc<Db>,
+  from: String,
+  mailer: Arc<dyn Mailer>,
+}
+
+impl NotificationDigest {
+  const BASE_URL: &'static str = "https://mcgill.courses";
+  const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+  pub(crate) fn new(db: Arc<Db>, from: &str, mailer: Arc<dyn Mailer>) -> Self {
+    Self {
+      db,
+      from: from.into(),
+      mailer,
+    }
+  }
+
+  pub(crate) fn spawn(self) {
+    tokio::spawn(
+      async move {
+        loop {
+          match self.run(Utc::now()).await {
+            Ok(sent) if sent > 0 => {
+              info!("Sent {} notification digests", sent)
+            }
+            Ok(_) => {}
+            Err(error) => {
+              error!("Failed to send notification digests: {error}")
+            }
+          }
+
+          tokio::time::sleep(Self::INTERVAL).await;
+        }
+      }
+      .instrument(info_span!("notification_digest")),
+    );
+  }
+
+  pub(crate) async fn run(&self, now: DateTime<Utc>) -> Result<usize> {
+    let mut sent = 0;
+
+    for preferences in self.db.digest_recipients().await? {
+      if !preferences.digest_due(now.into()) {
+        continue;
+      }
+
+      let notifications = self
+        .db
+        .unseen_notifications(&preferences.user_id, preferences.last_digest_at)
+        .await?;
+
+      if let Some(mail) =
+        Self::compose(&self.from, &preferences, &notifications)
+      {
+        if let Err(error) = self.mailer.send(&mail).await {
+          warn!(
+            "Failed to send digest to user {}: {error}",
+            preferences.user_id
+          );
+          continue;
+        }
+
+        sent += 1;
+      }
+
+      self
+        .db
+        .record_digest(&preferences.user_id, now.into())
+        .await?;
+    }
+
+    Ok(sent)
+  }
+
+  fn compose(
+    from: &str,
+    preferences: &NotificationPreferences,
+    notifications: &[Notification],
+  ) -> Option<Mail> {
+    let to = preferences.mail.clone()?;
+
+    if notifications.is_empty() {
+      return None;
+    }
+
+    let period = match preferences.digest {
+      DigestFrequency::Daily => "daily",
+      _ => "weekly",
+    };
+
+    let mut body = format!(
+      "Here's what happened in the courses you follow since your last {period} digest:\n\n"
+    );
+
+    let mut courses = BTreeMap::<&str, Vec<&Notification>>::new();
+
+    for notification in notifications {
+      courses
+        .entry(&notification.course_id)
+        .or_default()
+        .push(notification);
+    }
+
+    for (course_id, notifications) in courses {
+      body.push_str(&format!(
+        "{} ({}/course/{}-{})\n",
+        Self::course_code(course_id),
+        Self::BASE_URL,
+        course_id.get(..4).unwrap_or_default().to_lowercase(),
+        course_id.get(4..).unwrap_or_default().to_lowercase(),
+      ));
+
+      for notification in notifications {
+        body.push_str(&format!("  - {}\n", Self::describe(notification)));
+      }
+
+      body.push('\n');
+    }
+
+    body.push_str(&format!(
+      "You can change how often you receive these emails from your notification preferences at {}.\n",
+      Self::BASE_URL
+    ));
+
+    Some(Mail {
+      body,
+      from: from.into(),
+      subject: format!(
+        "Your {period} mcgill.courses digest ({} new)",
+        notifications.len()
+      ),
+      to,
+    })
+  }
+
+  fn course_code(course_id: &str) -> String {
+    match (course_id.get(..4), course_id.get(4..)) {
+      (Some(subject), Some(code)) => format!("{subject} {code}"),
+      _ => course_id.into(),
+    }
+  }
+
+  fn describe(notification: &Notification) -> String {
+    match &notification.payload {
+      NotificationPayload::CourseChange { changes } => format!(
+        "The course catalog updated {}",
+        changes
+          .iter()
+          .map(|change| change.field.as_str())
+          .collect::<Vec<&str>>()
+          .join(", ")
+      ),
+      NotificationPayload::Moderation {
+        outcome: ModerationOutcome::Approved,
+        ..
+      } => "Your review was approved by a moderator".into(),
+      NotificationPayload::Moderation {
+        outcome: ModerationOutcome::Removed,
+        reason,
+        ..
+      } => match reason {
+        Some(reason) => {
+          format!("Your review was removed by a moderator: {reason}")
+        }
+        None => "Your review was removed by a moderator".into(),
+      },
+      NotificationPayload::Review(review) => {
+        format!(
+          "New review ({}/5): {}",
+          review.rating,
+          Self::excerpt(&review.content)
+        )
+      }
+      NotificationPayload::ReviewReply { content, .. } => {
+        format!("New reply to your review: {}", Self::excerpt(content))
+      }
+    }
+  }
+
+  fn excerpt(content: &str) -> String {
+    const LENGTH: usize = 140;
+
+    let content = content.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    if content.chars().count() <= LENGTH {
+      content
+    } else {
+      format!("{}...", content.chars().take(LENGTH).collect::<String>())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn notification(
+    course_id: &str,
+    payload: NotificationPayload,
+  ) -> Notification {
+    Notification::new("a", course_id, payload)
+  }
+
+  #[test]
+  fn compose_groups_notifications_by_course() {
+    let preferences = NotificationPreferences {
+      digest: DigestFrequency::Daily,
+      mail: Some("a@mail.mcgill.ca".into()),
+      user_id: "a".into(),
+      ..Default::default()
+    };
+
+    let review = |content: &str| Review {
+      content: content.into(),
+      rating: 4,
+      ..Review::default()
+    };
+
+    let mail = NotificationDigest::compose(
+      "noreply@mcgill.courses",
+      &preferences,
+      &[
+        notification(
+          "MATH240",
+          NotificationPayload::Review(review("Great\n  course")),
+        ),
+        notification(
+          "COMP202",
+          NotificationPayload::Moderation {
+            outcome: ModerationOutcome::Removed,
+            reason: Some("spam".into()),
+            review: review("foo"),
+          },
+        ),
+        notification(
+          "MATH240",
+          NotificationPayload::Review(review(&"a".repeat(200))),
+        ),
+      ],
+    )
+    .unwrap();
+
+    assert_eq!(mail.to, "a@mail.mcgill.ca");
+    assert_eq!(mail.subject, "Your daily mcgill.courses digest (3 new)");
+
+    assert_eq!(
+      mail.body,
+      format!(
+        "Here's what happened in the courses you follow since your last daily digest:\n\n\
+         COMP 202 (https://mcgill.courses/course/comp-202)\n\
+         \x20 - Your review was removed by a moderator: spam\n\n\
+         MATH 240 (https://mcgill.courses/course/math-240)\n\
+         \x20 - New review (4/5): Great course\n\
+         \x20 - New review (4/5): {}...\n\n\
+         You can change how often you receive these emails from your notification preferences at https://mcgill.courses.\n",
+        "a".repeat(140)
+      )
+    );
+  }
+
+  #[test]
+  fn compose_skips_empty_digests_and_missing_addresses() {
+    let preferences = NotificationPreferences {
+      mail: Some("a@mail.mcgill.ca".into()),
+      user_id: "a".into(),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      NotificationDigest::compose("noreply@mcgill.courses", &preferences, &[]),
+      None
+    );
+
+    assert_eq!(
+      NotificationDigest::compose(
+        "noreply@mcgill.courses",
+        &NotificationPreferences {
+          mail: None,
+          ..preferences
+        },
+        &[notification(
+          "MATH240",
+          NotificationPayload::Review(Review::default())
+        )],
+      ),
+      None
+    );
+  }
+}
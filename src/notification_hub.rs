@@ -0,0 +1,93 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+pub(crate) struct NotificationHub {
+  sender: broadcast::Sender<Notification>,
+}
+
+impl Default for NotificationHub {
+  fn default() -> Self {
+    Self {
+      sender: broadcast::channel(Self::CAPACITY).0,
+    }
+  }
+}
+
+impl NotificationHub {
+  const CAPACITY: usize = 1024;
+
+  pub(crate) fn publish(&self, notifications: Vec<Notification>) {
+    for notification in notifications {
+      // Sending only fails when nobody is listening, which is fine.
+      let _ = self.sender.send(notification);
+    }
+  }
+
+  pub(crate) fn subscribe(
+    &self,
+    user_id: String,
+  ) -> impl Stream<Item = Notification> {
+    stream::unfold(
+      (self.sender.subscribe(), user_id),
+      |(mut receiver, user_id)| async move {
+        loop {
+          match receiver.recv().await {
+            Ok(notification) if notification.user_id == user_id => {
+              return Some((notification, (receiver, user_id)));
+            }
+            Ok(_) => continue,
+            Err(RecvError::Lagged(skipped)) => {
+              warn!(
+                "Notification stream for user {} lagged by {} messages",
+                user_id, skipped
+              );
+            }
+            Err(RecvError::Closed) => return None,
+          }
+        }
+      },
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, model::NotificationPayload};
+
+  fn notification(user_id: &str) -> Notification {
+    Notification::new(
+      user_id,
+      "MATH240",
+      NotificationPayload::Review(Review::default()),
+    )
+  }
+
+  #[tokio::test]
+  async fn subscribers_only_receive_their_notifications() {
+    let hub = NotificationHub::default();
+
+    let (a, b) = (hub.subscribe("a".into()), hub.subscribe("b".into()));
+
+    let notifications =
+      vec![notification("a"), notification("b"), notification("a")];
+
+    hub.publish(notifications.clone());
+
+    drop(hub);
+
+    assert_eq!(
+      a.collect::<Vec<Notification>>().await,
+      vec![notifications[0].clone(), notifications[2].clone()]
+    );
+
+    assert_eq!(
+      b.collect::<Vec<Notification>>().await,
+      vec![notifications[1].clone()]
+    );
+  }
+
+  #[test]
+  fn publishing_without_subscribers_is_a_noop() {
+    NotificationHub::default().publish(vec![notification("a")]);
+  }
+}
@@ -1,46 +1,217 @@
 use super::*;
 
+/// List notifications
+#[utoipa::path(
+  get,
+  path = "/api/v1/notifications",
+  params(GetNotificationsParams),
+  responses(
+    (
+      status = 200,
+      description = "The notifications, newest first",
+      body = Vec<Notification>,
+    ),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn get_notifications(
   user: User,
   AppState(db): AppState<Arc<Db>>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
+  Query(params): Query<GetNotificationsParams>,
 ) -> Result<impl IntoResponse> {
-  let mut notifications = db.get_notifications(&user.id()).await?;
-  notifications.sort_by(|a, b| b.review.timestamp.cmp(&a.review.timestamp));
-  Ok(Json(notifications))
+  Ok(Json(
+    pseudonyms.notifications(
+      db.get_notifications(&user.id(), params.limit, params.offset)
+        .await?,
+    ),
+  ))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct UpdateNotificationbody {
-  course_id: String,
-  creator_id: String,
-  seen: bool,
+/// Stream new notifications
+#[utoipa::path(
+  get,
+  path = "/api/v1/notifications/stream",
+  responses(
+    (
+      status = 200,
+      description = "Server-sent events with each `Notification` as JSON data, resuming after `Last-Event-ID` when given",
+      content_type = "text/event-stream",
+      body = String,
+    ),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn stream_notifications(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+  AppState(hub): AppState<NotificationHub>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
+  headers: HeaderMap,
+) -> Result<impl IntoResponse> {
+  let user_id = user.id();
+
+  let live = hub.subscribe(user_id.clone());
+
+  let last_event_id = headers
+    .get("last-event-id")
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| ObjectId::parse_str(value).ok());
+
+  let backlog = match last_event_id {
+    Some(id) => db.notifications_since(&user_id, id).await?,
+    None => Vec::new(),
+  };
+
+  let replayed = backlog
+    .last()
+    .map(|notification| notification.id)
+    .or(last_event_id);
+
+  let live = live.filter(move |notification| {
+    future::ready(match replayed {
+      Some(id) => notification.id > id,
+      None => true,
+    })
+  });
+
+  Ok(
+    Sse::new(stream::iter(backlog).chain(live).map(move |notification| {
+      Event::default()
+        .event("notification")
+        .id(notification.id.to_hex())
+        .json_data(pseudonyms.notification(notification))
+    }))
+    .keep_alive(KeepAlive::default()),
+  )
 }
 
+/// Mark a notification as seen
+#[utoipa::path(
+  put,
+  path = "/api/v1/notifications/{id}",
+  params(("id" = String, Path)),
+  request_body = UpdateNotificationBody,
+  responses(
+    (status = 200, description = "Success"),
+    (status = 404, description = "Not found"),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn update_notification(
   user: User,
   AppState(db): AppState<Arc<Db>>,
-  body: Json<UpdateNotificationbody>,
+  Path(id): Path<ObjectId>,
+  body: Json<UpdateNotificationBody>,
 ) -> Result<impl IntoResponse> {
-  db.update_notification(
-    &user.id(),
-    &body.course_id,
-    &body.creator_id,
-    body.seen,
-  )
-  .await?;
+  let result = db.update_notification(&user.id(), id, body.seen).await?;
+
+  Ok(if result.matched_count == 0 {
+    StatusCode::NOT_FOUND
+  } else {
+    StatusCode::OK
+  })
+}
+
+/// Delete a notification
+#[utoipa::path(
+  delete,
+  path = "/api/v1/notifications/{id}",
+  params(("id" = String, Path)),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 404, description = "Not found"),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn delete_notification(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+  Path(id): Path<ObjectId>,
+) -> Result<impl IntoResponse> {
+  let result = db.delete_notification(&user.id(), id).await?;
+
+  Ok(if result.deleted_count == 0 {
+    StatusCode::NOT_FOUND
+  } else {
+    StatusCode::OK
+  })
+}
+
+/// Mark all notifications as seen
+#[utoipa::path(
+  post,
+  path = "/api/v1/notifications/seen",
+  responses((status = 200, description = "Success")),
+  security(("session" = [])),
+)]
+pub(crate) async fn mark_all_notifications_seen(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  db.mark_all_notifications_seen(&user.id()).await?;
   Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct DeleteNotificationbody {
-  course_id: String,
+/// Get notification preferences
+#[utoipa::path(
+  get,
+  path = "/api/v1/notifications/preferences",
+  responses(
+    (
+      status = 200,
+      description = "The preferences",
+      body = NotificationPreferences,
+    ),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn get_notification_preferences(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  Ok(Json(db.get_notification_preferences(&user.id()).await?))
 }
 
-pub(crate) async fn delete_notification(
+/// Update notification preferences
+#[utoipa::path(
+  put,
+  path = "/api/v1/notifications/preferences",
+  request_body = UpdateNotificationPreferencesBody,
+  responses(
+    (
+      status = 200,
+      description = "The updated preferences",
+      body = NotificationPreferences,
+    ),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn update_notification_preferences(
   user: User,
   AppState(db): AppState<Arc<Db>>,
-  body: Json<DeleteNotificationbody>,
+  body: Json<UpdateNotificationPreferencesBody>,
 ) -> Result<impl IntoResponse> {
-  db.delete_notification(&user.id(), &body.course_id).await?;
+  let UpdateNotificationPreferencesBody {
+    digest,
+    muted_courses,
+    muted_types,
+  } = body.0;
+
+  let mail = user.mail().to_string();
+
+  let user_id = user.id();
+
+  db.set_notification_preferences(NotificationPreferences {
+    digest,
+    muted_courses,
+    muted_types,
+    user_id: user_id.clone(),
+    ..Default::default()
+  })
+  .await?;
+
+  db.record_mail(&user_id, &mail).await?;
+
   Ok(())
 }
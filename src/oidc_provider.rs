@@ -0,0 +1,113 @@
+use super::*;
+
+#[derive(Debug, Deserialize)]
+struct Discovery {
+  authorization_endpoint: Url,
+  token_endpoint: Url,
+  userinfo_endpoint: Url,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+  sub: String,
+  email: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct OidcProvider {
+  client_secret: String,
+  oauth_client: BasicClient,
+  redirect_url: Url,
+  scopes: Vec<String>,
+  userinfo_url: Url,
+}
+
+impl OidcProvider {
+  pub(crate) async fn from_config(
+    config: &Config,
+    client: &reqwest::Client,
+  ) -> Result<Self> {
+    let issuer = require!(config.oidc.issuer)?;
+
+    info!("Discovering OpenID Connect endpoints for {issuer}...");
+
+    let discovery = client
+      .get(format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+      ))
+      .send()
+      .await?
+      .error_for_status()?
+      .json::<Discovery>()
+      .await?;
+
+    let redirect_url = Url::parse(require!(config.oidc.redirect_uri)?)?;
+
+    let client_secret = require!(config.oidc.client_secret)?.to_string();
+
+    Ok(Self {
+      oauth_client: BasicClient::new(
+        ClientId::new(require!(config.oidc.client_id)?.into()),
+        Some(ClientSecret::new(client_secret.clone())),
+        AuthUrl::from_url(discovery.authorization_endpoint),
+        Some(TokenUrl::from_url(discovery.token_endpoint)),
+      )
+      .set_auth_type(AuthType::RequestBody)
+      .set_redirect_uri(RedirectUrl::from_url(redirect_url.clone())),
+      client_secret,
+      redirect_url,
+      scopes: config.oidc.scopes.clone(),
+      userinfo_url: discovery.userinfo_endpoint,
+    })
+  }
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+  fn authorize_url(&self, state: &str, challenge: &PkceCodeChallenge) -> Url {
+    self
+      .oauth_client
+      .authorize_url(|| CsrfToken::new(state.into()))
+      .add_scopes(self.scopes.iter().cloned().map(Scope::new))
+      .set_pkce_challenge(challenge.clone())
+      .url()
+      .0
+  }
+
+  fn redirect_url(&self) -> &Url {
+    &self.redirect_url
+  }
+
+  async fn user(
+    &self,
+    client: &reqwest::Client,
+    code: &str,
+    verifier: &str,
+  ) -> Result<User> {
+    debug!("Fetching token from OpenID Connect provider...");
+
+    let access_token = identity_provider::exchange_code(
+      client,
+      &self.oauth_client,
+      &self.client_secret,
+      code,
+      verifier,
+      None,
+    )
+    .await?;
+
+    debug!("Fetching user info from OpenID Connect provider...");
+
+    let info = client
+      .get(self.userinfo_url.as_str())
+      .bearer_auth(access_token)
+      .send()
+      .await?
+      .error_for_status()?
+      .json::<UserInfo>()
+      .await?;
+
+    Ok(User::new(&info.sub, &info.email))
+  }
+}
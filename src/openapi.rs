@@ -0,0 +1,880 @@
+use super::*;
+
+#[derive(OpenApi)]
+#[openapi(
+  paths(
+    api_tokens::add_api_token,
+    api_tokens::delete_api_token,
+    api_tokens::get_api_tokens,
+    auth::login,
+    auth::login_authorized,
+    auth::logout,
+    courses::get_course_by_id,
+    courses::get_course_changes,
+    courses::get_course_stats,
+    courses::get_courses,
+    courses::list_courses,
+    get_openapi,
+    health::get_initialization,
+    health::healthz,
+    health::readyz,
+    instructors::get_instructor,
+    interactions::add_interaction,
+    interactions::delete_interaction,
+    interactions::get_interaction_kind,
+    interactions::get_user_interactions_for_course,
+    notifications::delete_notification,
+    notifications::get_notification_preferences,
+    notifications::get_notifications,
+    notifications::mark_all_notifications_seen,
+    notifications::stream_notifications,
+    notifications::update_notification,
+    notifications::update_notification_preferences,
+    reviews::add_review,
+    reviews::delete_review,
+    reviews::get_review,
+    reviews::get_reviews,
+    reviews::update_review,
+    search::search,
+    sessions::delete_session,
+    sessions::delete_sessions,
+    sessions::get_sessions,
+    subscriptions::add_subscription,
+    subscriptions::delete_subscription,
+    subscriptions::get_subscription,
+    telemetry::get_metrics,
+    user::delete_user,
+    user::export_user,
+    user::get_user,
+  ),
+  components(schemas(RateLimitedPayload))
+)]
+struct ApiDoc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Auth {
+  None,
+  Optional,
+  Required,
+}
+
+/// The route in axum's syntax, with `{param}` segments written as `:param`.
+fn route(path: &str) -> String {
+  path
+    .split('/')
+    .map(|segment| {
+      match segment
+        .strip_prefix('{')
+        .and_then(|segment| segment.strip_suffix('}'))
+      {
+        Some(name) => format!(":{name}"),
+        None => segment.into(),
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// Documents what every route gets from the middleware rather than its
+/// handler: caching, sign in, token scopes, rate limits and deprecation.
+fn document(method: &Method, path: &str, operation: &mut Value) {
+  let route = route(path);
+
+  operation["tags"] = json!([path
+    .trim_start_matches(API_V1)
+    .trim_start_matches("/api")
+    .split('/')
+    .nth(1)]);
+
+  let auth = match operation.get("security").and_then(Value::as_array) {
+    None => Auth::None,
+    Some(security) if security.contains(&json!({})) => Auth::Optional,
+    Some(_) => Auth::Required,
+  };
+
+  let scope = api_tokens::required_scope(method, &route);
+
+  if catalog::is_cached(method, &route) {
+    operation["parameters"]
+      .as_array_mut()
+      .expect("operations have parameters")
+      .push(json!({
+        "name": "If-None-Match",
+        "in": "header",
+        "required": false,
+        "schema": { "type": "string" },
+      }));
+
+    operation["responses"][StatusCode::NOT_MODIFIED.as_str()] = json!({
+      "description": "Unchanged since the `ETag` sent in `If-None-Match`",
+      "headers": {
+        "ETag": { "schema": { "type": "string" } },
+      },
+    });
+  }
+
+  if auth == Auth::Required {
+    operation["responses"][StatusCode::TEMPORARY_REDIRECT.as_str()] =
+      json!({ "description": "Not signed in, redirects to the login page" });
+  }
+
+  let mut limits = Vec::new();
+
+  if auth != Auth::None {
+    if let Some(scope) = scope {
+      for (status, description) in [
+        (StatusCode::UNAUTHORIZED, "The access token is invalid"),
+        (
+          StatusCode::FORBIDDEN,
+          "The access token lacks the required scope",
+        ),
+      ] {
+        operation["responses"][status.as_str()] =
+          json!({ "description": description });
+      }
+
+      operation["security"]
+        .as_array_mut()
+        .expect("security is an array")
+        .insert(1, json!({ "token": [scope] }));
+
+      limits.push("the access token's rate limit".to_string());
+    }
+  }
+
+  if let Some(policy) = Policy::for_route(method, &route) {
+    limits.push(match policy.key() {
+      PolicyKey::Ip => {
+        format!("the `{}` rate limit per address", policy.name())
+      }
+      PolicyKey::User => {
+        format!("the `{}` rate limit per user", policy.name())
+      }
+    });
+  }
+
+  if !limits.is_empty() {
+    operation["responses"][StatusCode::TOO_MANY_REQUESTS.as_str()] = json!({
+      "description": format!("Over {}", limits.join(" or ")),
+      "headers": {
+        "Retry-After": { "schema": { "type": "integer" } },
+      },
+      "content": {
+        "application/json": {
+          "schema": { "$ref": "#/components/schemas/RateLimitedPayload" },
+        },
+      },
+    });
+  }
+
+  if let Some(deprecation) = Deprecation::for_route(method, &route) {
+    if deprecation.fields.is_empty() {
+      operation["deprecated"] = json!(true);
+    } else {
+      operation["x-deprecated-fields"] = json!(deprecation.fields);
+    }
+
+    if let Some(sunset) = deprecation.sunset {
+      operation["x-sunset"] = json!(sunset.to_string());
+    }
+  }
+}
+
+pub(crate) fn spec() -> Value {
+  let mut spec =
+    serde_json::to_value(ApiDoc::openapi()).expect("the spec serializes");
+
+  for (path, operations) in
+    spec["paths"].as_object_mut().expect("paths is an object")
+  {
+    for (method, operation) in
+      operations.as_object_mut().expect("path items are objects")
+    {
+      let method = Method::from_bytes(method.to_uppercase().as_bytes())
+        .expect("operations are keyed by method");
+
+      if operation.get("parameters").is_none() {
+        operation["parameters"] = json!([]);
+      }
+
+      document(&method, path, operation);
+    }
+  }
+
+  spec["info"] = json!({
+    "title": "mcgill.courses",
+    "version": "1",
+    "description": format!(
+      "Routes under `{API_V1}` only change compatibly. The unversioned `/api` \
+       routes are a deprecated alias of them."
+    ),
+  });
+
+  spec["components"]["securitySchemes"] = json!({
+    "session": { "type": "apiKey", "in": "cookie", "name": COOKIE_NAME },
+    "token": {
+      "type": "http",
+      "scheme": "bearer",
+      "description": format!(
+        "A personal access token, prefixed with `{}`",
+        api_tokens::TOKEN_PREFIX
+      ),
+    },
+  });
+
+  spec
+}
+
+/// Get this document
+#[utoipa::path(
+  get,
+  path = "/api/v1/openapi.json",
+  responses(
+    (status = 200, description = "The OpenAPI document", body = Value),
+  ),
+)]
+pub(crate) async fn get_openapi() -> impl IntoResponse {
+  Json(spec())
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    model::{
+      ActiveSession, Block, CourseSort, CourseSortType, InitializationState,
+      Instructor, InteractionKind, NotificationType, SeedKind, SeedProgress,
+      SeedState, TimeBlock, YearlyAverage,
+    },
+    pretty_assertions::assert_eq,
+    serde::de::DeserializeOwned,
+  };
+
+  fn references(value: &Value, found: &mut Vec<String>) {
+    match value {
+      Value::Array(values) => {
+        values.iter().for_each(|value| references(value, found))
+      }
+      Value::Object(object) => {
+        if let Some(Value::String(reference)) = object.get("$ref") {
+          found.push(reference.clone());
+        }
+
+        object.values().for_each(|value| references(value, found));
+      }
+      _ => {}
+    }
+  }
+
+  /// Whether `schema` only allows `null`.
+  fn null(schema: &Value) -> bool {
+    schema.get("type") == Some(&json!("null"))
+  }
+
+  /// Builds a value matching `schema`, picking the first alternative other
+  /// than `null` wherever there is a choice.
+  fn example(schema: &Value, components: &Value) -> Value {
+    if let Some(example) =
+      schema.get("examples").and_then(|examples| examples.get(0))
+    {
+      return example.clone();
+    }
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+      return example(
+        &components[reference.trim_start_matches("#/components/schemas/")],
+        components,
+      );
+    }
+
+    if let Some(values) = schema.get("enum") {
+      return values[0].clone();
+    }
+
+    for key in ["anyOf", "oneOf"] {
+      if let Some(schemas) = schema.get(key).and_then(Value::as_array) {
+        return example(
+          schemas.iter().find(|schema| !null(schema)).unwrap(),
+          components,
+        );
+      }
+    }
+
+    if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+      let mut merged = serde_json::Map::new();
+
+      for schema in schemas {
+        if let Value::Object(object) = example(schema, components) {
+          merged.extend(object);
+        }
+      }
+
+      return Value::Object(merged);
+    }
+
+    let kind = match schema.get("type") {
+      Some(Value::Array(kinds)) => kinds.iter().find(|kind| *kind != "null"),
+      kind => kind,
+    };
+
+    match kind.and_then(Value::as_str) {
+      Some("array") => json!([example(&schema["items"], components)]),
+      Some("boolean") => json!(false),
+      Some("integer") => json!(0),
+      Some("number") => json!(0.0),
+      Some("object") => Value::Object(
+        schema["properties"]
+          .as_object()
+          .into_iter()
+          .flatten()
+          .map(|(key, schema)| (key.clone(), example(schema, components)))
+          .collect(),
+      ),
+      Some("string") => json!("string"),
+      _ => Value::Null,
+    }
+  }
+
+  /// Checks that an example built from `T`'s schema deserializes and
+  /// serializes back to the same fields, so schemas can't drift from the
+  /// types they describe.
+  fn assert_round_trips<T: ToSchema + Serialize + DeserializeOwned>() {
+    fn keys(value: &Value) -> Vec<String> {
+      let mut keys = value
+        .as_object()
+        .map(|object| object.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+      keys.sort();
+
+      keys
+    }
+
+    let spec = spec();
+
+    let components = &spec["components"]["schemas"];
+
+    let example = example(&components[T::name().as_ref()], components);
+
+    let value =
+      serde_json::from_value::<T>(example.clone()).unwrap_or_else(|error| {
+        panic!("{} does not match its schema: {error}", T::name())
+      });
+
+    assert_eq!(
+      keys(&serde_json::to_value(value).unwrap()),
+      keys(&example),
+      "{}",
+      T::name()
+    );
+  }
+
+  /// Checks that a query string built from `T`'s parameters deserializes.
+  fn assert_parses<T: IntoParams + DeserializeOwned>() {
+    let query = T::into_params(|| None)
+      .into_iter()
+      .map(|parameter| {
+        let schema = serde_json::to_value(parameter.schema).unwrap();
+
+        let value = match example(&schema, &Value::Null) {
+          Value::String(value) => value,
+          value => value.to_string(),
+        };
+
+        format!("{}={value}", parameter.name)
+      })
+      .collect::<Vec<_>>()
+      .join("&");
+
+    let uri = format!("/?{query}").parse::<http::Uri>().unwrap();
+
+    if let Err(error) = Query::<T>::try_from_uri(&uri) {
+      panic!("{query} does not parse: {error}");
+    }
+  }
+
+  fn assert_variants<T: ToSchema + Serialize + DeserializeOwned>() {
+    let spec = spec();
+
+    for variant in spec["components"]["schemas"][T::name().as_ref()]["enum"]
+      .as_array()
+      .unwrap()
+    {
+      assert_eq!(
+        &serde_json::to_value(
+          serde_json::from_value::<T>(variant.clone()).unwrap()
+        )
+        .unwrap(),
+        variant
+      );
+    }
+  }
+
+  #[test]
+  fn schemas_match_types() {
+    assert_round_trips::<ActiveSession>();
+    assert_round_trips::<AddApiTokenBody>();
+    assert_round_trips::<AddApiTokenPayload>();
+    assert_round_trips::<AddInteractionBody>();
+    assert_round_trips::<AddOrDeleteSubscriptionBody>();
+    assert_round_trips::<AddOrUpdateReviewBody>();
+    assert_round_trips::<ApiTokenPayload>();
+    assert_round_trips::<Block>();
+    assert_round_trips::<Course>();
+    assert_round_trips::<CourseChange>();
+    assert_round_trips::<CourseFilter>();
+    assert_round_trips::<CourseSort>();
+    assert_round_trips::<CurrentUser>();
+    assert_round_trips::<DeleteInteractionBody>();
+    assert_round_trips::<DeleteReviewBody>();
+    assert_round_trips::<GetCourseWithReviewsPayload>();
+    assert_round_trips::<GetCoursesPayload>();
+    assert_round_trips::<GetInstructorPayload>();
+    assert_round_trips::<GetInteractionKindPayload>();
+    assert_round_trips::<GetReviewsPayload>();
+    assert_round_trips::<GetSessionsPayload>();
+    assert_round_trips::<GetUserInteractionForCoursePayload>();
+    assert_round_trips::<InitializationStatus>();
+    assert_round_trips::<Instructor>();
+    assert_round_trips::<Interaction>();
+    assert_round_trips::<Notification>();
+    assert_round_trips::<NotificationPreferences>();
+    assert_round_trips::<RateLimitedPayload>();
+    assert_round_trips::<ReadinessPayload>();
+    assert_round_trips::<Review>();
+    assert_round_trips::<ReviewStats>();
+    assert_round_trips::<Schedule>();
+    assert_round_trips::<SearchResults>();
+    assert_round_trips::<SeedProgress>();
+    assert_round_trips::<SessionPayload>();
+    assert_round_trips::<Subscription>();
+    assert_round_trips::<TermAverage>();
+    assert_round_trips::<TimeBlock>();
+    assert_round_trips::<UpdateNotificationBody>();
+    assert_round_trips::<UpdateNotificationPreferencesBody>();
+    assert_round_trips::<UserExport>();
+    assert_round_trips::<UserResponse>();
+    assert_round_trips::<YearlyAverage>();
+
+    assert_variants::<CourseSortType>();
+    assert_variants::<DigestFrequency>();
+    assert_variants::<InitializationState>();
+    assert_variants::<InteractionKind>();
+    assert_variants::<ModerationOutcome>();
+    assert_variants::<NotificationType>();
+    assert_variants::<SeedKind>();
+    assert_variants::<SeedState>();
+    assert_variants::<TokenScope>();
+  }
+
+  #[test]
+  fn parameters_match_types() {
+    use auth::{AuthRequest, LoginRequest, LogoutRequest};
+
+    assert_parses::<AuthRequest>();
+    assert_parses::<DeleteSessionsParams>();
+    assert_parses::<GetCourseParams>();
+    assert_parses::<GetCourseStatsParams>();
+    assert_parses::<GetCoursesParams>();
+    assert_parses::<GetInteractionKindParams>();
+    assert_parses::<GetNotificationsParams>();
+    assert_parses::<GetReviewsParams>();
+    assert_parses::<GetSubscriptionParams>();
+    assert_parses::<ListCoursesParams>();
+    assert_parses::<LoginRequest>();
+    assert_parses::<LogoutRequest>();
+    assert_parses::<SearchParams>();
+  }
+
+  #[test]
+  fn references_resolve() {
+    let spec = spec();
+
+    let mut found = Vec::new();
+
+    references(&spec, &mut found);
+
+    assert!(!found.is_empty());
+
+    for reference in found {
+      let name = reference.trim_start_matches("#/components/schemas/");
+
+      assert!(
+        spec["components"]["schemas"][name].is_object(),
+        "{reference} does not resolve"
+      );
+    }
+  }
+
+  #[test]
+  fn path_parameters_are_declared() {
+    let spec = spec();
+
+    for (path, operations) in spec["paths"].as_object().unwrap() {
+      for operation in operations.as_object().unwrap().values() {
+        let mut declared = operation["parameters"]
+          .as_array()
+          .unwrap()
+          .iter()
+          .filter(|parameter| parameter["in"] == "path")
+          .map(|parameter| {
+            format!("{{{}}}", parameter["name"].as_str().unwrap())
+          })
+          .collect::<Vec<_>>();
+
+        let mut expected = path
+          .split('/')
+          .filter(|segment| segment.starts_with('{'))
+          .map(String::from)
+          .collect::<Vec<_>>();
+
+        declared.sort();
+        expected.sort();
+
+        assert_eq!(declared, expected, "{path}");
+      }
+    }
+  }
+
+  /// Keys that only document the api, so can change freely.
+  const DOCUMENTATION: &[&str] = &["description", "example", "summary", "tags"];
+
+  /// Schemas referenced from request bodies, which can't gain required
+  /// properties.
+  fn request_schemas(spec: &Value) -> HashSet<String> {
+    let mut found = Vec::new();
+
+    for operations in spec["paths"].as_object().unwrap().values() {
+      for operation in operations.as_object().unwrap().values() {
+        references(&operation["requestBody"], &mut found);
+      }
+    }
+
+    let mut schemas = HashSet::new();
+
+    while let Some(reference) = found.pop() {
+      let name = reference.trim_start_matches("#/components/schemas/");
+
+      if schemas.insert(name.to_string()) {
+        references(&spec["components"]["schemas"][name], &mut found);
+      }
+    }
+
+    schemas
+  }
+
+  fn strings(value: Option<&Value>) -> Vec<&str> {
+    value
+      .and_then(Value::as_array)
+      .map(|values| values.iter().filter_map(Value::as_str).collect())
+      .unwrap_or_default()
+  }
+
+  fn compare(
+    location: &str,
+    old: &Value,
+    new: &Value,
+    request: bool,
+    changes: &mut Vec<String>,
+  ) {
+    match (old, new) {
+      (Value::Object(old), Value::Object(new)) => {
+        for (key, value) in old {
+          if DOCUMENTATION.contains(&key.as_str()) {
+            continue;
+          }
+
+          let location = format!("{location}.{key}");
+
+          match (key.as_str(), new.get(key)) {
+            ("required", Some(Value::Array(_))) => {}
+            ("parameters", Some(current)) => {
+              for parameter in value.as_array().unwrap() {
+                match current
+                  .as_array()
+                  .unwrap()
+                  .iter()
+                  .find(|current| current["name"] == parameter["name"])
+                {
+                  Some(current) => compare(
+                    &format!("{location}.{}", parameter["name"]),
+                    parameter,
+                    current,
+                    request,
+                    changes,
+                  ),
+                  None => changes.push(format!(
+                    "{location}.{} was removed",
+                    parameter["name"]
+                  )),
+                }
+              }
+
+              for parameter in current.as_array().unwrap() {
+                if parameter["required"] == true
+                  && !value
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .any(|old| old["name"] == parameter["name"])
+                {
+                  changes.push(format!(
+                    "{location}.{} is a new required parameter",
+                    parameter["name"]
+                  ));
+                }
+              }
+            }
+            (_, Some(current)) => {
+              compare(&location, value, current, request, changes)
+            }
+            (_, None) => changes.push(format!("{location} was removed")),
+          }
+        }
+
+        let (required, current) =
+          (strings(old.get("required")), strings(new.get("required")));
+
+        for property in &required {
+          if !current.contains(property) {
+            changes
+              .push(format!("{location}.{property} is no longer required"));
+          }
+        }
+
+        for property in current {
+          if !required.contains(&property)
+            && (request
+              || old
+                .get("properties")
+                .is_some_and(|properties| properties.get(property).is_some()))
+          {
+            changes.push(format!("{location}.{property} is now required"));
+          }
+        }
+      }
+      (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+        for (i, (old, new)) in old.iter().zip(new).enumerate() {
+          compare(&format!("{location}[{i}]"), old, new, request, changes);
+        }
+      }
+      _ => {
+        if old != new {
+          changes.push(format!("{location} changed from {old} to {new}"));
+        }
+      }
+    }
+  }
+
+  /// Ways clients written against `old` would break when talking to `new`.
+  fn breaking_changes(old: &Value, new: &Value) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    for (path, operations) in old["paths"].as_object().unwrap() {
+      for (method, operation) in operations.as_object().unwrap() {
+        let location = format!("{} {path}", method.to_uppercase());
+
+        match new["paths"][path].get(method) {
+          Some(current) => {
+            compare(&location, operation, current, false, &mut changes)
+          }
+          None => changes.push(format!("{location} was removed")),
+        }
+      }
+    }
+
+    let requests = request_schemas(old);
+
+    for (name, schema) in old["components"]["schemas"].as_object().unwrap() {
+      match new["components"]["schemas"].get(name) {
+        Some(current) => {
+          compare(name, schema, current, requests.contains(name), &mut changes)
+        }
+        None => changes.push(format!("{name} was removed")),
+      }
+    }
+
+    changes
+  }
+
+  #[test]
+  fn compatible_changes() {
+    let old = json!({
+      "paths": {
+        "/a": {
+          "get": {
+            "summary": "Get a",
+            "parameters": [{ "name": "x", "in": "query", "required": false }],
+            "requestBody": { "$ref": "#/components/schemas/Body" },
+          },
+        },
+      },
+      "components": {
+        "schemas": {
+          "A": {
+            "type": "object",
+            "properties": { "a": { "type": "string" } },
+            "required": ["a"],
+          },
+          "Body": {
+            "type": "object",
+            "properties": { "b": { "type": "string" } },
+            "required": [],
+          },
+        },
+      },
+    });
+
+    assert_eq!(breaking_changes(&old, &old), Vec::<String>::new());
+
+    let mut new = old.clone();
+
+    new["paths"]["/a"]["get"]["summary"] = json!("Fetch a");
+    new["paths"]["/a"]["get"]["parameters"]
+      .as_array_mut()
+      .unwrap()
+      .push(json!({ "name": "y", "in": "query", "required": false }));
+    new["paths"]["/b"] = json!({ "get": {} });
+    new["components"]["schemas"]["A"]["properties"]["c"] =
+      json!({ "type": "string" });
+    new["components"]["schemas"]["A"]["required"] = json!(["a", "c"]);
+    new["components"]["schemas"]["C"] = json!({ "type": "string" });
+
+    assert_eq!(breaking_changes(&old, &new), Vec::<String>::new());
+  }
+
+  #[test]
+  fn breaking_changes_are_found() {
+    let old = json!({
+      "paths": {
+        "/a": {
+          "get": {
+            "parameters": [{ "name": "x", "in": "query", "required": false }],
+            "requestBody": { "$ref": "#/components/schemas/Body" },
+          },
+          "put": {},
+        },
+      },
+      "components": {
+        "schemas": {
+          "A": {
+            "type": "object",
+            "properties": {
+              "a": { "type": "string" },
+              "b": { "type": "string" },
+              "c": { "type": "string" },
+              "d": { "type": "string", "enum": ["x", "y"] },
+            },
+            "required": ["a", "b"],
+          },
+          "Body": {
+            "type": "object",
+            "properties": { "b": { "type": "string" } },
+            "required": [],
+          },
+          "Gone": { "type": "string" },
+        },
+      },
+    });
+
+    let mut new = old.clone();
+
+    new["paths"]["/a"]
+      .as_object_mut()
+      .unwrap()
+      .remove("put")
+      .unwrap();
+    new["paths"]["/a"]["get"]["parameters"] = json!([
+      { "name": "x", "in": "query", "required": true },
+      { "name": "y", "in": "query", "required": true },
+    ]);
+    new["components"]["schemas"]
+      .as_object_mut()
+      .unwrap()
+      .remove("Gone")
+      .unwrap();
+    new["components"]["schemas"]["A"]["properties"]
+      .as_object_mut()
+      .unwrap()
+      .remove("a")
+      .unwrap();
+    new["components"]["schemas"]["A"]["properties"]["b"]["type"] =
+      json!("integer");
+    new["components"]["schemas"]["A"]["properties"]["d"]["enum"] =
+      json!(["x", "y", "z"]);
+    new["components"]["schemas"]["A"]["required"] = json!(["b", "c"]);
+    new["components"]["schemas"]["Body"]["properties"]["e"] =
+      json!({ "type": "string" });
+    new["components"]["schemas"]["Body"]["required"] = json!(["e"]);
+
+    let mut changes = breaking_changes(&old, &new);
+
+    changes.sort();
+
+    assert_eq!(
+      changes,
+      vec![
+        "A.a is no longer required",
+        "A.c is now required",
+        "A.properties.a was removed",
+        "A.properties.b.type changed from \"string\" to \"integer\"",
+        "A.properties.d.enum changed from [\"x\",\"y\"] to [\"x\",\"y\",\"z\"]",
+        "Body.e is now required",
+        "GET /a.parameters.\"x\".required changed from false to true",
+        "GET /a.parameters.\"y\" is a new required parameter",
+        "Gone was removed",
+        "PUT /a was removed",
+      ]
+    );
+  }
+
+  #[test]
+  fn v1_is_compatible_with_snapshot() {
+    let path =
+      PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("openapi/v1.json");
+
+    let snapshot =
+      serde_json::from_str::<Value>(&fs::read_to_string(&path).unwrap())
+        .unwrap();
+
+    let spec = spec();
+
+    assert_eq!(breaking_changes(&snapshot, &spec), Vec::<String>::new());
+
+    if env::var_os("UPDATE_API_SNAPSHOT").is_some() {
+      fs::write(&path, serde_json::to_string_pretty(&spec).unwrap() + "\n")
+        .unwrap();
+    } else {
+      assert!(
+        snapshot == spec,
+        "{API_V1} changed compatibly, rerun with UPDATE_API_SNAPSHOT=1 to \
+         update {}",
+        path.display()
+      );
+    }
+  }
+
+  #[test]
+  fn token_scopes_are_documented() {
+    let spec = spec();
+
+    assert_eq!(
+      spec["paths"]["/api/v1/reviews"]["post"]["security"],
+      json!([{ "session": [] }, { "token": ["reviews:write"] }])
+    );
+
+    assert_eq!(
+      spec["paths"]["/api/v1/reviews"]["get"]["security"],
+      json!([{ "session": [] }, { "token": ["reviews:read"] }, {}])
+    );
+
+    assert_eq!(
+      spec["paths"]["/api/v1/tokens"]["get"]["security"],
+      json!([{ "session": [] }])
+    );
+
+    assert!(spec["paths"]["/api/v1/search"]["get"]
+      .get("security")
+      .is_none());
+  }
+}
@@ -2,6 +2,13 @@ use super::*;
 
 #[derive(Parser)]
 pub(crate) struct Options {
+  #[clap(
+    long,
+    value_enum,
+    default_value_t = LogFormat::Text,
+    help = "Format to log in"
+  )]
+  pub(crate) log_format: LogFormat,
   #[clap(long, default_value = "courses.json")]
   pub(crate) source: PathBuf,
 }
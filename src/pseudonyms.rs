@@ -0,0 +1,269 @@
+use super::*;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone)]
+pub(crate) struct Pseudonyms {
+  /// The account behind recently handed out pseudonyms, to resolve them
+  /// without hashing every author of a course.
+  authors: Arc<Mutex<LruCache<String, String>>>,
+  key: Arc<[u8]>,
+}
+
+impl fmt::Debug for Pseudonyms {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Pseudonyms").finish_non_exhaustive()
+  }
+}
+
+impl Pseudonyms {
+  const AUTHORS: NonZeroUsize = match NonZeroUsize::new(10_000) {
+    Some(authors) => authors,
+    None => unreachable!(),
+  };
+
+  const LENGTH: usize = 16;
+
+  pub(crate) fn new(key: &[u8]) -> Self {
+    Self {
+      authors: Arc::new(Mutex::new(LruCache::new(Self::AUTHORS))),
+      key: key.into(),
+    }
+  }
+
+  pub(crate) fn from_config(config: &Config) -> Result<Self> {
+    match config.auth.pseudonym_key.as_deref() {
+      Some(key) => Ok(Self::new(key.as_bytes())),
+      // Every restart and replica would publish different pseudonyms
+      None if config.production() => Err(Error(anyhow!(
+        "`auth.pseudonym_key` must be set in production, set it in the config file or with PSEUDONYM_KEY"
+      ))),
+      None => {
+        warn!(
+          "PSEUDONYM_KEY is not set, pseudonyms will change on every restart"
+        );
+        Ok(Self::new(&rand::random::<[u8; 32]>()))
+      }
+    }
+  }
+
+  pub(crate) fn pseudonym(&self, user_id: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(&self.key)
+      .expect("HMAC can take a key of any size");
+
+    mac.update(user_id.as_bytes());
+
+    let pseudonym = hex::encode(&mac.finalize().into_bytes()[..Self::LENGTH]);
+
+    self
+      .authors
+      .lock()
+      .unwrap()
+      .put(pseudonym.clone(), user_id.into());
+
+    pseudonym
+  }
+
+  pub(crate) fn review(&self, review: Review) -> Review {
+    Review {
+      user_id: self.pseudonym(&review.user_id),
+      ..review
+    }
+  }
+
+  pub(crate) fn reviews(&self, reviews: Vec<Review>) -> Vec<Review> {
+    reviews
+      .into_iter()
+      .map(|review| self.review(review))
+      .collect()
+  }
+
+  pub(crate) fn interactions(
+    &self,
+    interactions: Vec<Interaction>,
+  ) -> Vec<Interaction> {
+    interactions
+      .into_iter()
+      .map(|interaction| Interaction {
+        user_id: self.pseudonym(&interaction.user_id),
+        ..interaction
+      })
+      .collect()
+  }
+
+  pub(crate) fn notification(
+    &self,
+    notification: Notification,
+  ) -> Notification {
+    Notification {
+      payload: match notification.payload {
+        NotificationPayload::Moderation {
+          outcome,
+          reason,
+          review,
+        } => NotificationPayload::Moderation {
+          outcome,
+          reason,
+          review: self.review(review),
+        },
+        NotificationPayload::Review(review) => {
+          NotificationPayload::Review(self.review(review))
+        }
+        NotificationPayload::ReviewReply {
+          content,
+          review,
+          user_id,
+        } => NotificationPayload::ReviewReply {
+          content,
+          review: self.review(review),
+          user_id: self.pseudonym(&user_id),
+        },
+        payload => payload,
+      },
+      ..notification
+    }
+  }
+
+  pub(crate) fn notifications(
+    &self,
+    notifications: Vec<Notification>,
+  ) -> Vec<Notification> {
+    notifications
+      .into_iter()
+      .map(|notification| self.notification(notification))
+      .collect()
+  }
+
+  /// The author of the review of `course_id` published under `pseudonym`.
+  pub(crate) async fn author(
+    &self,
+    db: &Db,
+    course_id: &str,
+    pseudonym: &str,
+  ) -> Result<Option<String>> {
+    let author = self.authors.lock().unwrap().get(pseudonym).cloned();
+
+    if let Some(author) = author {
+      return Ok(
+        db.find_review(course_id, &author)
+          .await?
+          .map(|review| review.user_id),
+      );
+    }
+
+    // Handed out before a restart, remember every author of the course
+    Ok(
+      db.find_reviews_by_course_id(course_id)
+        .await?
+        .into_iter()
+        .map(|review| review.user_id)
+        .find(|user_id| self.pseudonym(user_id) == pseudonym),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn pseudonyms_are_stable_per_key() {
+    let (a, b) = (Pseudonyms::new(b"a"), Pseudonyms::new(b"b"));
+
+    assert_eq!(a.pseudonym("user").len(), 32);
+    assert_eq!(a.pseudonym("user"), a.pseudonym("user"));
+
+    assert_ne!(a.pseudonym("user"), a.pseudonym("other"));
+    assert_ne!(a.pseudonym("user"), b.pseudonym("user"));
+  }
+
+  #[test]
+  fn key_is_required_in_production() {
+    let config = |vars: &[(&str, &str)]| {
+      Config::from_sources(
+        None,
+        &vars
+          .iter()
+          .map(|(key, value)| (key.to_string(), value.to_string()))
+          .collect(),
+      )
+      .unwrap()
+    };
+
+    assert!(Pseudonyms::from_config(&config(&[])).is_ok());
+
+    assert_eq!(
+      Pseudonyms::from_config(&config(&[("ENV", "production")]))
+        .unwrap_err()
+        .to_string(),
+      "`auth.pseudonym_key` must be set in production, set it in the config file or with PSEUDONYM_KEY"
+    );
+
+    let (a, b) = (
+      Pseudonyms::from_config(&config(&[
+        ("ENV", "production"),
+        ("PSEUDONYM_KEY", "secret"),
+      ]))
+      .unwrap(),
+      Pseudonyms::new(b"secret"),
+    );
+
+    assert_eq!(a.pseudonym("user"), b.pseudonym("user"));
+  }
+
+  #[test]
+  fn recent_pseudonyms_are_remembered() {
+    let pseudonyms = Pseudonyms::new(b"key");
+
+    let pseudonym = pseudonyms.pseudonym("user");
+
+    assert_eq!(
+      pseudonyms.authors.lock().unwrap().get(&pseudonym).unwrap(),
+      "user"
+    );
+
+    for user in 0..Pseudonyms::AUTHORS.get() {
+      pseudonyms.pseudonym(&user.to_string());
+    }
+
+    let authors = pseudonyms.authors.lock().unwrap();
+
+    assert_eq!(authors.len(), Pseudonyms::AUTHORS.get());
+    assert!(!authors.contains(&pseudonym));
+  }
+
+  #[test]
+  fn review_authors_are_replaced() {
+    let pseudonyms = Pseudonyms::new(b"key");
+
+    let review = Review {
+      course_id: "MATH240".into(),
+      user_id: "user".into(),
+      ..Default::default()
+    };
+
+    let notification = pseudonyms.notification(Notification::new(
+      "recipient",
+      "MATH240",
+      NotificationPayload::ReviewReply {
+        content: "reply".into(),
+        review: review.clone(),
+        user_id: "replier".into(),
+      },
+    ));
+
+    assert_eq!(notification.user_id, "recipient");
+
+    assert_eq!(
+      notification.payload,
+      NotificationPayload::ReviewReply {
+        content: "reply".into(),
+        review: Review {
+          user_id: pseudonyms.pseudonym("user"),
+          ..review
+        },
+        user_id: pseudonyms.pseudonym("replier"),
+      }
+    );
+  }
+}
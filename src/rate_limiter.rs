@@ -0,0 +1,124 @@
+use super::*;
+
+type KeyedLimiter = DefaultKeyedRateLimiter<String>;
+
+/// A `governor` limiter for each limit in use, and when keys that stopped
+/// making requests were last dropped from them.
+#[derive(Debug)]
+struct Limiters {
+  by_limit: HashMap<u32, Arc<KeyedLimiter>>,
+  pruned_at: Instant,
+}
+
+/// Requests per period counted by an arbitrary string, where every key can
+/// have its own limit. Limits are enforced with GCRA, so up to `limit`
+/// requests can be made at once, and after that one every `period / limit`.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+  limiters: Arc<Mutex<Limiters>>,
+  period: Duration,
+}
+
+impl Default for RateLimiter {
+  fn default() -> Self {
+    Self::new(Duration::from_secs(60))
+  }
+}
+
+impl RateLimiter {
+  pub(crate) fn new(period: Duration) -> Self {
+    Self {
+      limiters: Arc::new(Mutex::new(Limiters {
+        by_limit: HashMap::new(),
+        pruned_at: Instant::now(),
+      })),
+      period,
+    }
+  }
+
+  /// Counts a request against `key`, returning how long to wait before
+  /// retrying if it is over `limit`.
+  pub(crate) fn check(&self, key: &str, limit: u32) -> Result<(), Duration> {
+    let limiter = self.limiter(limit);
+
+    limiter.check_key(&key.to_owned()).map_err(|not_until| {
+      not_until.wait_time_from(DefaultClock::default().now())
+    })
+  }
+
+  fn limiter(&self, limit: u32) -> Arc<KeyedLimiter> {
+    let mut limiters = self.limiters.lock().unwrap();
+
+    // Keys that stopped making requests are dropped at most once a period,
+    // rather than scanning every key on every request
+    if limiters.pruned_at.elapsed() >= self.period {
+      for limiter in limiters.by_limit.values() {
+        limiter.retain_recent();
+        limiter.shrink_to_fit();
+      }
+
+      limiters.pruned_at = Instant::now();
+    }
+
+    let period = self.period;
+
+    limiters
+      .by_limit
+      .entry(limit)
+      .or_insert_with(|| {
+        let burst = NonZeroU32::new(limit).unwrap_or(NonZeroU32::MIN);
+
+        Arc::new(GovernorRateLimiter::keyed(
+          Quota::with_period(period / burst.get())
+            .unwrap_or_else(|| Quota::per_second(NonZeroU32::MAX))
+            .allow_burst(burst),
+        ))
+      })
+      .clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn assert_retry(result: Result<(), Duration>, after: Duration) {
+    let retry = result.unwrap_err();
+
+    assert!(
+      retry <= after && retry > after - Duration::from_secs(1),
+      "{retry:?}"
+    );
+  }
+
+  #[test]
+  fn limits_each_key() {
+    let limiter = RateLimiter::new(Duration::from_secs(60));
+
+    assert_eq!(limiter.check("a", 2), Ok(()));
+    assert_eq!(limiter.check("a", 2), Ok(()));
+    assert_eq!(limiter.check("b", 1), Ok(()));
+    assert_eq!(limiter.check("c", 2), Ok(()));
+
+    // Replenished one at a time, so a client can't send twice the limit
+    // across the edge of a window
+    assert_retry(limiter.check("a", 2), Duration::from_secs(30));
+    assert_retry(limiter.check("b", 1), Duration::from_secs(60));
+  }
+
+  #[test]
+  fn prunes_idle_keys_once_a_period() {
+    let limiter = RateLimiter::new(Duration::from_millis(50));
+
+    let keys = || limiter.limiter(1).len();
+
+    assert_eq!(limiter.check("a", 1), Ok(()));
+    assert_eq!(limiter.check("b", 1), Ok(()));
+    assert_eq!(keys(), 2);
+
+    thread::sleep(Duration::from_millis(150));
+
+    assert_eq!(limiter.check("a", 1), Ok(()));
+    assert_eq!(keys(), 1);
+  }
+}
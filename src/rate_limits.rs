@@ -0,0 +1,256 @@
+use super::*;
+
+/// What a policy counts requests by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PolicyKey {
+  Ip,
+  User,
+}
+
+/// A named budget of requests per minute, shared by some routes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Policy {
+  Interactions,
+  Reviews,
+  Search,
+}
+
+impl Policy {
+  pub(crate) fn key(self) -> PolicyKey {
+    match self {
+      Self::Interactions | Self::Reviews => PolicyKey::User,
+      Self::Search => PolicyKey::Ip,
+    }
+  }
+
+  pub(crate) fn name(self) -> &'static str {
+    match self {
+      Self::Interactions => "interactions",
+      Self::Reviews => "reviews",
+      Self::Search => "search",
+    }
+  }
+
+  /// The policy for `method` on `route`, a route under either api root.
+  pub(crate) fn for_route(method: &Method, route: &str) -> Option<Self> {
+    let route = route
+      .strip_prefix(API_V1)
+      .or_else(|| route.strip_prefix("/api"))?;
+
+    match (route, method == Method::GET) {
+      ("/interactions", false) => Some(Self::Interactions),
+      ("/reviews", false) => Some(Self::Reviews),
+      ("/search", true) => Some(Self::Search),
+      _ => None,
+    }
+  }
+}
+
+/// Requests per minute allowed under each policy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RateLimits {
+  pub(crate) interactions: u32,
+  pub(crate) reviews: u32,
+  pub(crate) search: u32,
+}
+
+impl RateLimits {
+  pub(crate) fn unlimited() -> Self {
+    Self {
+      interactions: u32::MAX,
+      reviews: u32::MAX,
+      search: u32::MAX,
+    }
+  }
+
+  pub(crate) fn from_config(config: &Config) -> Result<Self> {
+    let RateLimitConfig {
+      enabled,
+      interactions,
+      reviews,
+      search,
+      ..
+    } = config.rate_limit;
+
+    if !enabled {
+      return Ok(Self::unlimited());
+    }
+
+    let limit = |key: &str, limit: u32| -> Result<u32> {
+      match limit {
+        0 => Err(Error(anyhow!("`{key}` must be a positive number"))),
+        limit => Ok(limit),
+      }
+    };
+
+    Ok(Self {
+      interactions: limit("rate_limit.interactions", interactions)?,
+      reviews: limit("rate_limit.reviews", reviews)?,
+      search: limit("rate_limit.search", search)?,
+    })
+  }
+
+  fn limit(&self, policy: Policy) -> u32 {
+    match policy {
+      Policy::Interactions => self.interactions,
+      Policy::Reviews => self.reviews,
+      Policy::Search => self.search,
+    }
+  }
+
+  /// Counts a request by `key`, a user id or ip address, against `policy`.
+  pub(crate) fn check(
+    &self,
+    limiter: &RateLimiter,
+    policy: Policy,
+    key: &str,
+  ) -> Result<(), RateLimited> {
+    limiter
+      .check(&format!("{}:{key}", policy.name()), self.limit(policy))
+      .map_err(|retry_after| RateLimited::new(policy.name(), retry_after))
+  }
+}
+
+impl FromRef<State> for RateLimits {
+  fn from_ref(state: &State) -> Self {
+    state.rate_limits
+  }
+}
+
+/// A request turned away by `limiter`, answered with a `Retry-After` header
+/// and a `RateLimitedPayload`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RateLimited {
+  limiter: &'static str,
+  retry_after: Duration,
+}
+
+impl RateLimited {
+  pub(crate) fn new(limiter: &'static str, retry_after: Duration) -> Self {
+    Self {
+      limiter,
+      retry_after,
+    }
+  }
+}
+
+impl IntoResponse for RateLimited {
+  fn into_response(self) -> Response {
+    telemetry::RATE_LIMITED
+      .with_label_values(&[self.limiter])
+      .inc();
+
+    let retry_after = self.retry_after.as_secs().max(1);
+
+    (
+      StatusCode::TOO_MANY_REQUESTS,
+      [(header::RETRY_AFTER, retry_after.to_string())],
+      Json(RateLimitedPayload {
+        limiter: self.limiter.into(),
+        message: format!(
+          "over the {} rate limit, retry in {retry_after}s",
+          self.limiter
+        ),
+        retry_after,
+      }),
+    )
+      .into_response()
+  }
+}
+
+/// Applies the policies counted per ip address. Those counted per user are
+/// applied by the `User` extractor, once it knows who's asking.
+pub(crate) async fn enforce(
+  AppState(limiter): AppState<RateLimiter>,
+  AppState(limits): AppState<RateLimits>,
+  matched_path: Option<MatchedPath>,
+  request: Request<Body>,
+  next: Next,
+) -> Response {
+  let policy = matched_path
+    .and_then(|path| Policy::for_route(request.method(), path.as_str()))
+    .filter(|policy| policy.key() == PolicyKey::Ip);
+
+  if let Some(policy) = policy {
+    let ip = request
+      .extensions()
+      .get::<ConnectInfo<SocketAddr>>()
+      .map(|ConnectInfo(addr)| addr.ip().to_string())
+      .unwrap_or_default();
+
+    if let Err(rejection) = limits.check(&limiter, policy, &ip) {
+      return rejection.into_response();
+    }
+  }
+
+  next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn policies_for_routes() {
+    for (method, route, policy) in [
+      (Method::POST, "/api/v1/reviews", Some(Policy::Reviews)),
+      (Method::PUT, "/api/reviews", Some(Policy::Reviews)),
+      (Method::DELETE, "/api/v1/reviews", Some(Policy::Reviews)),
+      (Method::GET, "/api/v1/reviews", None),
+      (
+        Method::POST,
+        "/api/v1/interactions",
+        Some(Policy::Interactions),
+      ),
+      (Method::GET, "/api/v1/interactions", None),
+      (Method::GET, "/api/v1/search", Some(Policy::Search)),
+      (Method::GET, "/api/v1/courses/:id", None),
+      (Method::POST, "/reviews", None),
+    ] {
+      assert_eq!(
+        Policy::for_route(&method, route),
+        policy,
+        "{method} {route}"
+      );
+    }
+  }
+
+  #[test]
+  fn policies_are_counted_separately() {
+    let limiter = RateLimiter::default();
+
+    let limits = RateLimits {
+      interactions: 2,
+      reviews: 1,
+      search: 1,
+    };
+
+    assert_eq!(limits.check(&limiter, Policy::Reviews, "a"), Ok(()));
+    assert_eq!(limits.check(&limiter, Policy::Reviews, "b"), Ok(()));
+    assert_eq!(limits.check(&limiter, Policy::Interactions, "a"), Ok(()));
+    assert_eq!(limits.check(&limiter, Policy::Interactions, "a"), Ok(()));
+
+    assert_eq!(
+      limits
+        .check(&limiter, Policy::Reviews, "a")
+        .unwrap_err()
+        .limiter,
+      "reviews"
+    );
+
+    assert!(limits.check(&limiter, Policy::Interactions, "a").is_err());
+    assert_eq!(limits.check(&limiter, Policy::Search, "a"), Ok(()));
+  }
+
+  #[test]
+  fn limits_from_config() {
+    assert_eq!(
+      RateLimits::from_config(&Config::default()).unwrap(),
+      RateLimits {
+        interactions: 30,
+        reviews: 10,
+        search: 120,
+      }
+    );
+  }
+}
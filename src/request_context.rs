@@ -0,0 +1,101 @@
+use super::*;
+
+pub(crate) const REQUEST_ID: HeaderName =
+  HeaderName::from_static("x-request-id");
+
+/// Records the signed in user on the span of the request being served.
+pub(crate) fn record_user(user_id: &str) {
+  Span::current().record("user_id", field::display(user_id));
+}
+
+/// The caller's request id if it's reasonable, otherwise a new one.
+fn request_id(headers: &HeaderMap) -> String {
+  headers
+    .get(REQUEST_ID)
+    .and_then(|value| value.to_str().ok())
+    .filter(|id| {
+      (1..=128).contains(&id.len())
+        && id.chars().all(|c| {
+          c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')
+        })
+    })
+    .map(String::from)
+    .unwrap_or_else(|| hex::encode(rand::random::<[u8; 16]>()))
+}
+
+/// Serves each request in a `request` span, passing its id on in the
+/// `X-Request-Id` header both ways, and logs one line once it's done. Tasks
+/// spawned while serving it keep its fields by being instrumented with
+/// `Span::current()`.
+pub(crate) async fn layer(
+  matched_path: Option<MatchedPath>,
+  mut request: Request<Body>,
+  next: Next,
+) -> Response {
+  let id = request_id(request.headers());
+
+  let header = HeaderValue::from_str(&id).ok();
+
+  if let Some(header) = header.clone() {
+    request.headers_mut().insert(REQUEST_ID, header);
+  }
+
+  let method = request.method().clone();
+
+  let path = request.uri().path().to_string();
+
+  let span = info_span!(
+    "request",
+    request_id = %id,
+    method = %method,
+    route = field::Empty,
+    user_id = field::Empty,
+    status = field::Empty,
+    latency_ms = field::Empty,
+  );
+
+  if let Some(matched_path) = matched_path {
+    span.record("route", matched_path.as_str());
+  }
+
+  let start = Instant::now();
+
+  let mut response = next.run(request).instrument(span.clone()).await;
+
+  span.record("status", response.status().as_u16());
+
+  span.record(
+    "latency_ms",
+    (start.elapsed().as_secs_f64() * 1e6).round() / 1e3,
+  );
+
+  span.in_scope(|| info!("Served {} {}", method, path));
+
+  if let Some(header) = header {
+    response.headers_mut().insert(REQUEST_ID, header);
+  }
+
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn request_id(value: &str) -> String {
+    let mut headers = HeaderMap::new();
+    headers.insert(REQUEST_ID, value.parse().unwrap());
+    super::request_id(&headers)
+  }
+
+  #[test]
+  fn keeps_reasonable_request_ids() {
+    assert_eq!(request_id("abc-123_4.5:6"), "abc-123_4.5:6");
+
+    for unreasonable in ["", "a b", "<script>", &"a".repeat(129)] {
+      let id = request_id(unreasonable);
+      assert_eq!(id.len(), 32, "{unreasonable}");
+      assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+  }
+}
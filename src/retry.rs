@@ -14,8 +14,16 @@ impl Retry for RequestBuilder {
         .ok_or_else(|| anyhow!("Failed to clone request builder"))?
         .send()
       {
-        Ok(response) => return Ok(response),
+        Ok(response) => {
+          telemetry::SCRAPE_REQUESTS
+            .with_label_values(&["success"])
+            .inc();
+          return Ok(response);
+        }
         Err(error) => {
+          telemetry::SCRAPE_REQUESTS
+            .with_label_values(&["failure"])
+            .inc();
           error!("Request failed: {error}");
           attempts += 1;
           thread::sleep(Duration::from_secs(1));
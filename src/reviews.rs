@@ -1,40 +1,48 @@
 use super::*;
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct GetReviewsParams {
-  pub(crate) course_id: Option<String>,
-  pub(crate) instructor_name: Option<String>,
-  pub(crate) limit: Option<i64>,
-  pub(crate) offset: Option<u64>,
-  pub(crate) sorted: Option<bool>,
-  pub(crate) user_id: Option<String>,
-  pub(crate) with_user_count: Option<bool>,
-}
-
-#[derive(Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct GetReviewsPayload {
-  pub reviews: Vec<Review>,
-  pub unique_user_count: Option<u64>,
-}
-
+/// List reviews
+#[utoipa::path(
+  get,
+  path = "/api/v1/reviews",
+  params(GetReviewsParams),
+  responses(
+    (
+      status = 200,
+      description = "The matching reviews",
+      body = GetReviewsPayload,
+    ),
+  ),
+  security(("session" = []), ()),
+)]
 pub(crate) async fn get_reviews(
+  MaybeUser(user): MaybeUser,
   params: Query<GetReviewsParams>,
   AppState(db): AppState<Arc<Db>>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
 ) -> Result<impl IntoResponse> {
+  let user_id = params.user_id.as_ref().map(|pseudonym| {
+    user
+      .map(User::id)
+      .filter(|user_id| pseudonyms.pseudonym(user_id) == *pseudonym)
+  });
+
   Ok(Json(GetReviewsPayload {
-    reviews: db
-      .reviews(
-        params.limit,
-        params.offset,
-        Some(ReviewFilter {
-          course_id: params.course_id.clone(),
-          instructor_name: params.instructor_name.clone(),
-          sorted: params.sorted,
-          user_id: params.user_id.clone(),
-        }),
-      )
-      .await?,
+    reviews: match user_id {
+      Some(None) => Vec::new(),
+      user_id => pseudonyms.reviews(
+        db.reviews(
+          params.limit,
+          params.offset,
+          Some(ReviewFilter {
+            course_id: params.course_id.clone(),
+            instructor_name: params.instructor_name.clone(),
+            sorted: params.sorted,
+            user_id: user_id.flatten(),
+          }),
+        )
+        .await?,
+      ),
+    },
     unique_user_count: if params.with_user_count.unwrap_or(false) {
       Some(db.unique_user_count().await?)
     } else {
@@ -43,25 +51,40 @@ pub(crate) async fn get_reviews(
   }))
 }
 
+/// Get the user's review of a course
+#[utoipa::path(
+  get,
+  path = "/api/v1/reviews/{id}",
+  params(("id" = String, Path)),
+  responses(
+    (status = 200, description = "The review, if any", body = Option<Review>),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn get_review(
   user: User,
   Path(id): Path<String>,
   AppState(db): AppState<Arc<Db>>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
 ) -> Result<impl IntoResponse> {
-  Ok(Json(db.find_review(&id, &user.id()).await?))
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct AddOrUpdateReviewBody {
-  pub(crate) content: String,
-  pub(crate) course_id: String,
-  pub(crate) instructors: Vec<String>,
-  pub(crate) rating: u32,
-  pub(crate) difficulty: u32,
+  Ok(Json(
+    db.find_review(&id, &user.id())
+      .await?
+      .map(|review| pseudonyms.review(review)),
+  ))
 }
 
+/// Review a course
+#[utoipa::path(
+  post,
+  path = "/api/v1/reviews",
+  request_body = AddOrUpdateReviewBody,
+  responses((status = 200, description = "Success")),
+  security(("session" = [])),
+)]
 pub(crate) async fn add_review(
   AppState(db): AppState<Arc<Db>>,
+  AppState(hub): AppState<NotificationHub>,
   user: User,
   body: Json<AddOrUpdateReviewBody>,
 ) -> Result<impl IntoResponse> {
@@ -92,11 +115,19 @@ pub(crate) async fn add_review(
 
   info!("Adding notifications for course {}...", &course_id);
 
-  db.add_notifications(review).await?;
+  hub.publish(db.add_notifications(review).await?);
 
   Ok(())
 }
 
+/// Update the user's review of a course
+#[utoipa::path(
+  put,
+  path = "/api/v1/reviews",
+  request_body = AddOrUpdateReviewBody,
+  responses((status = 200, description = "Success")),
+  security(("session" = [])),
+)]
 pub(crate) async fn update_review(
   AppState(db): AppState<Arc<Db>>,
   user: User,
@@ -135,11 +166,14 @@ pub(crate) async fn update_review(
   Ok(())
 }
 
-#[derive(Debug, Deserialize)]
-pub(crate) struct DeleteReviewBody {
-  course_id: String,
-}
-
+/// Delete the user's review of a course
+#[utoipa::path(
+  delete,
+  path = "/api/v1/reviews",
+  request_body = DeleteReviewBody,
+  responses((status = 200, description = "Success")),
+  security(("session" = [])),
+)]
 pub(crate) async fn delete_review(
   AppState(db): AppState<Arc<Db>>,
   user: User,
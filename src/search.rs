@@ -1,10 +1,14 @@
 use super::*;
 
-#[derive(Deserialize)]
-pub(crate) struct SearchParams {
-  pub(crate) query: String,
-}
-
+/// Search courses and instructors
+#[utoipa::path(
+  get,
+  path = "/api/v1/search",
+  params(SearchParams),
+  responses(
+    (status = 200, description = "The matches", body = SearchResults),
+  ),
+)]
 pub(crate) async fn search(
   Query(params): Query<SearchParams>,
   AppState(state): AppState<State>,
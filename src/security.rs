@@ -0,0 +1,153 @@
+use super::*;
+
+/// Requests carrying JSON bodies can't be larger than this, unless their route
+/// is listed in `body_limit`.
+const DEFAULT_BODY_LIMIT: usize = 4 * 1024;
+
+/// The largest request body accepted by `route`, relative to the api root.
+pub(crate) fn body_limit(route: &str) -> usize {
+  match route {
+    // Review text, and course filters listing many subjects or terms
+    "/courses" | "/reviews" => 32 * 1024,
+    _ => DEFAULT_BODY_LIMIT,
+  }
+}
+
+/// Only the configured origins can make requests from the browser with the
+/// user's session cookie.
+pub(crate) fn cors(allowed_origins: &AllowedOrigins) -> Result<CorsLayer> {
+  Ok(
+    CorsLayer::new()
+      .allow_origin(
+        allowed_origins
+          .iter()
+          .map(|origin| HeaderValue::from_str(&origin.ascii_serialization()))
+          .collect::<Result<Vec<HeaderValue>, _>>()?,
+      )
+      .allow_credentials(true)
+      .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+      .allow_headers([
+        header::AUTHORIZATION,
+        header::CONTENT_TYPE,
+        HeaderName::from_static("last-event-id"),
+        request_context::REQUEST_ID,
+      ])
+      .expose_headers([
+        catalog::CATALOG_VERSION,
+        header::ETAG,
+        header::LINK,
+        header::RETRY_AFTER,
+        request_context::REQUEST_ID,
+      ])
+      .max_age(Duration::from_secs(60 * 60)),
+  )
+}
+
+/// Headers added to every response, including the frontend's assets.
+#[derive(Debug, Clone)]
+pub(crate) struct SecurityHeaders {
+  content_security_policy: HeaderValue,
+  /// Tell browsers to only ever use https, which only makes sense once the
+  /// site is served over it.
+  hsts: bool,
+}
+
+impl SecurityHeaders {
+  const HSTS: &'static str = "max-age=31536000; includeSubDomains";
+
+  pub(crate) fn from_config(config: &Config) -> Result<Self> {
+    let policy = &config.security.content_security_policy;
+
+    Ok(Self {
+      content_security_policy: HeaderValue::from_str(policy).map_err(|_| {
+        anyhow!("Invalid `security.content_security_policy` `{policy}`")
+      })?,
+      hsts: config.security.hsts.unwrap_or_else(|| config.production()),
+    })
+  }
+
+  fn apply(&self, headers: &mut HeaderMap) {
+    headers
+      .entry(header::CONTENT_SECURITY_POLICY)
+      .or_insert_with(|| self.content_security_policy.clone());
+
+    headers.insert(
+      header::X_CONTENT_TYPE_OPTIONS,
+      HeaderValue::from_static("nosniff"),
+    );
+
+    headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+
+    headers.insert(
+      header::REFERRER_POLICY,
+      HeaderValue::from_static("strict-origin-when-cross-origin"),
+    );
+
+    if self.hsts {
+      headers.insert(
+        header::STRICT_TRANSPORT_SECURITY,
+        HeaderValue::from_static(Self::HSTS),
+      );
+    }
+  }
+}
+
+pub(crate) async fn headers(
+  AppState(security_headers): AppState<SecurityHeaders>,
+  request: Request<Body>,
+  next: Next,
+) -> Response {
+  let mut response = next.run(request).await;
+
+  security_headers.apply(response.headers_mut());
+
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn hsts_follows_the_environment() {
+    let mut headers = HeaderMap::new();
+
+    SecurityHeaders::from_config(&Config::default())
+      .unwrap()
+      .apply(&mut headers);
+
+    assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+    assert!(headers[header::CONTENT_SECURITY_POLICY]
+      .to_str()
+      .unwrap()
+      .starts_with("default-src 'self'"));
+    assert!(!headers.contains_key(header::STRICT_TRANSPORT_SECURITY));
+
+    let security_headers = SecurityHeaders {
+      content_security_policy: HeaderValue::from_static("default-src 'none'"),
+      hsts: true,
+    };
+
+    let mut headers = HeaderMap::new();
+
+    headers.insert(
+      header::CONTENT_SECURITY_POLICY,
+      HeaderValue::from_static("sandbox"),
+    );
+
+    security_headers.apply(&mut headers);
+
+    assert_eq!(headers[header::CONTENT_SECURITY_POLICY], "sandbox");
+    assert_eq!(
+      headers[header::STRICT_TRANSPORT_SECURITY],
+      SecurityHeaders::HSTS
+    );
+  }
+
+  #[test]
+  fn body_limits() {
+    assert_eq!(body_limit("/reviews"), 32 * 1024);
+    assert_eq!(body_limit("/interactions"), DEFAULT_BODY_LIMIT);
+  }
+}
@@ -1,13 +1,18 @@
 use super::*;
 
+/// Root of the current api version, see `openapi::spec`.
+pub(crate) const API_V1: &str = "/api/v1";
+
 #[derive(Parser)]
 pub(crate) struct Server {
   #[clap(long, help = "Directory to serve assets from")]
   asset_dir: Option<PathBuf>,
-  #[clap(long, default_value = "8000", help = "Port to listen on")]
-  port: u16,
-  #[clap(long, default_value = "admin", help = "Database name")]
-  db_name: String,
+  #[clap(long, help = "Configuration file to read settings from")]
+  config: Option<PathBuf>,
+  #[clap(long, help = "Port to listen on [default: 8000]")]
+  port: Option<u16>,
+  #[clap(long, help = "Database name [default: admin]")]
+  db_name: Option<String>,
   #[clap(long, default_value = "false", help = "Seed latest courses only")]
   latest_courses: bool,
   #[clap(long, default_value = "false", help = "Enable multithreaded seeding")]
@@ -22,81 +27,162 @@ pub(crate) struct Server {
 
 #[derive(Debug)]
 struct AppConfig<'a> {
+  admins: Admins,
+  allowed_origins: AllowedOrigins,
+  catalog_version: CatalogVersion,
   db: Arc<Db>,
   assets: Option<Assets<'a>>,
+  identity_provider: Arc<dyn IdentityProvider>,
+  metrics_token: MetricsToken,
+  notification_hub: NotificationHub,
+  pseudonyms: Pseudonyms,
+  rate_limit: Option<RateLimit>,
+  rate_limits: RateLimits,
+  security_headers: SecurityHeaders,
+  session_policy: SessionPolicy,
   session_store: MongodbSessionStore,
-  rate_limit: bool,
 }
 
 impl Server {
+  /// Settings from `--config`, the environment and the flags passed.
+  fn config(&self) -> Result<Config> {
+    let mut config = Config::load(self.config.as_deref())?;
+
+    if let Some(asset_dir) = &self.asset_dir {
+      config.server.asset_dir = Some(asset_dir.clone());
+    }
+
+    if let Some(db_name) = &self.db_name {
+      config.server.db_name.clone_from(db_name);
+    }
+
+    if let Some(port) = self.port {
+      config.server.port = port;
+    }
+
+    Ok(config)
+  }
+
   pub(crate) async fn run(self, source: PathBuf) -> Result {
-    let addr = SocketAddr::from(([0, 0, 0, 0], self.port));
+    let config = self.config()?;
+
+    let rate_limit = config.rate_limit()?;
+
+    let db_name = &config.server.db_name;
+
+    let mongodb_url = &config.mongodb.url;
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], config.server.port));
 
     info!("Listening on port: {}", addr.port());
 
-    let db = Arc::new(Db::connect(&self.db_name).await?);
+    let db = Arc::new(Db::connect_to(mongodb_url, db_name).await?);
 
-    if self.initialize {
-      let source_hash = source.hash()?;
+    // Hashed on every start, since the database was seeded from `source` by
+    // this server or an earlier one
+    let source_hash = if self.initialize || source.exists() {
+      Some(source.hash()?)
+    } else {
+      None
+    };
 
-      let client = match env::var("ENV") {
-        Ok(env) if env == "production" => Some(S3Client::new(Region::UsEast1)),
-        _ => None,
-      };
+    let catalog_version = source_hash
+      .as_deref()
+      .map(CatalogVersion::from_hash)
+      .unwrap_or_default();
+
+    let notification_hub = NotificationHub::default();
+
+    if let Some(source_hash) = source_hash.filter(|_| self.initialize) {
+      let client = config.production().then(|| S3Client::new(Region::UsEast1));
+
+      let bucket = &config.s3.bucket;
 
       let prev_hash = match client {
-        Some(ref client) => client.get("mcgill.courses", "source-hash").await?,
+        Some(ref client) => client.get(bucket, "source-hash").await?,
         None => None,
       };
 
       if Some(&source_hash) != prev_hash.as_ref() {
-        let clone = db.clone();
-
         if let Some(client) = client {
-          client
-            .put("mcgill.courses", "source-hash", source_hash)
-            .await?;
+          client.put(bucket, "source-hash", source_hash).await?;
         }
 
-        tokio::spawn(async move {
-          if let Err(error) = clone
-            .initialize(InitializeOptions {
-              latest_courses: self.latest_courses,
-              multithreaded: self.multithreaded,
-              skip_courses: self.skip_courses,
-              skip_reviews: self.skip_reviews,
-              source,
-            })
-            .await
-          {
-            error!("error: {error}");
+        let initialize = db.initialize_publishing(
+          InitializeOptions {
+            latest_courses: self.latest_courses,
+            multithreaded: self.multithreaded,
+            skip_courses: self.skip_courses,
+            skip_reviews: self.skip_reviews,
+            source,
+          },
+          Arc::new({
+            let notification_hub = notification_hub.clone();
+            move |notifications| notification_hub.publish(notifications)
+          }),
+        );
+
+        tokio::spawn(
+          async move {
+            if let Err(error) = initialize.await {
+              error!("error: {error}");
+            }
           }
-        });
+          .instrument(info_span!("initialize")),
+        );
+      }
+    }
+
+    match mailer::from_config(&config)? {
+      Some(mailer) => {
+        let from = &config.mail.from;
+
+        // Fail on startup rather than on every digest
+        Mail::mailbox(from)?;
+
+        info!("Sending notification digests with {:?}", mailer);
+        NotificationDigest::new(db.clone(), from, mailer).spawn();
+      }
+      None => {
+        info!("`mail.transport` is not set, skipping notification digests")
       }
     }
 
-    let assets = self.asset_dir.as_ref().map(|asset_dir| Assets {
-      dir: ServeDir::new(asset_dir.clone()),
+    let identity_provider =
+      identity_provider::from_config(&config, &reqwest::Client::new()).await?;
+
+    info!("Signing users in with {:?}", identity_provider);
+
+    let allowed_origins =
+      AllowedOrigins::from_config(&config, identity_provider.redirect_url())?;
+
+    let assets = config.server.asset_dir.as_ref().map(|asset_dir| Assets {
+      dir: ServeDir::new(asset_dir),
       index: ServeFile::new(asset_dir.join("index.html")),
       route: "/assets",
     });
 
-    let session_store = MongodbSessionStore::new(
-      &env::var("MONGODB_URL").unwrap_or_else(|_| {
-        "mongodb://localhost:27017/?directConnection=true&replicaSet=rs0".into()
-      }),
-      &db.name(),
-      "store",
-    )
-    .await?;
+    let session_store =
+      MongodbSessionStore::new(mongodb_url, &db.name(), Db::SESSION_COLLECTION)
+        .await?;
 
     axum_server::Server::bind(addr)
       .serve(
         Self::app(AppConfig {
+          admins: Admins::from_config(&config),
+          allowed_origins,
+          catalog_version,
           db,
           assets,
+          identity_provider,
+          metrics_token: MetricsToken::from_config(&config),
+          notification_hub,
+          pseudonyms: Pseudonyms::from_config(&config)?,
+          rate_limit,
+          rate_limits: RateLimits::from_config(&config)?,
+          security_headers: SecurityHeaders::from_config(&config)?,
+          session_policy: SessionPolicy::from_config(&config),
           session_store,
-          rate_limit: true,
         })
         .await?
         .into_make_service_with_connect_info::<SocketAddr>(),
@@ -106,46 +192,126 @@ impl Server {
     Ok(())
   }
 
-  async fn app(config: AppConfig<'_>) -> Result<Router> {
-    let mut router = Router::new()
-      .route("/api/auth/authorized", get(auth::login_authorized))
-      .route("/api/auth/login", get(auth::microsoft_auth))
-      .route("/api/auth/logout", get(auth::logout))
-      .route("/api/courses", post(courses::get_courses))
-      .route("/api/courses/:id", get(courses::get_course_by_id))
-      .route("/api/instructors/:name", get(instructors::get_instructor))
-      .route(
-        "/api/interactions/:course_id/referrer/:referrer",
+  /// Routes outside of the versioned api: the browser sign in flow, which
+  /// identity providers redirect back to, and probes for orchestrators.
+  fn unversioned_routes() -> Vec<(&'static str, MethodRouter<State>)> {
+    vec![
+      ("/api/auth/authorized", get(auth::login_authorized)),
+      ("/api/auth/login", get(auth::login)),
+      ("/api/auth/logout", get(auth::logout)),
+      ("/healthz", get(health::healthz)),
+      ("/metrics", get(telemetry::get_metrics)),
+      ("/readyz", get(health::readyz)),
+    ]
+  }
+
+  /// Routes of the versioned api, relative to its root, each limited to the
+  /// request body size it needs.
+  fn api_routes() -> Vec<(&'static str, MethodRouter<State>)> {
+    [
+      ("/admin/initialization", get(health::get_initialization)),
+      (
+        "/courses",
+        get(courses::list_courses).post(courses::get_courses),
+      ),
+      ("/courses/:id", get(courses::get_course_by_id)),
+      ("/courses/:id/changes", get(courses::get_course_changes)),
+      ("/courses/:id/stats", get(courses::get_course_stats)),
+      ("/instructors/:name", get(instructors::get_instructor)),
+      (
+        "/interactions/:course_id",
         get(interactions::get_user_interactions_for_course),
-      )
-      .route(
-        "/api/interactions",
+      ),
+      (
+        "/interactions",
         get(interactions::get_interaction_kind)
           .post(interactions::add_interaction)
           .delete(interactions::delete_interaction),
-      )
-      .route(
-        "/api/notifications",
-        get(notifications::get_notifications)
-          .put(notifications::update_notification)
+      ),
+      ("/notifications", get(notifications::get_notifications)),
+      (
+        "/notifications/stream",
+        get(notifications::stream_notifications),
+      ),
+      (
+        "/notifications/:id",
+        put(notifications::update_notification)
           .delete(notifications::delete_notification),
-      )
-      .route(
-        "/api/reviews",
+      ),
+      (
+        "/notifications/preferences",
+        get(notifications::get_notification_preferences)
+          .put(notifications::update_notification_preferences),
+      ),
+      (
+        "/notifications/seen",
+        post(notifications::mark_all_notifications_seen),
+      ),
+      ("/openapi.json", get(openapi::get_openapi)),
+      (
+        "/reviews",
         get(reviews::get_reviews)
           .delete(reviews::delete_review)
           .post(reviews::add_review)
           .put(reviews::update_review),
-      )
-      .route("/api/reviews/:id", get(reviews::get_review))
-      .route("/api/search", get(search::search))
-      .route(
-        "/api/subscriptions",
+      ),
+      ("/reviews/:id", get(reviews::get_review)),
+      ("/search", get(search::search)),
+      (
+        "/sessions",
+        get(sessions::get_sessions).delete(sessions::delete_sessions),
+      ),
+      ("/sessions/:id", delete(sessions::delete_session)),
+      (
+        "/subscriptions",
         get(subscriptions::get_subscription)
           .post(subscriptions::add_subscription)
           .delete(subscriptions::delete_subscription),
+      ),
+      (
+        "/tokens",
+        get(api_tokens::get_api_tokens).post(api_tokens::add_api_token),
+      ),
+      ("/tokens/:id", delete(api_tokens::delete_api_token)),
+      ("/user", get(user::get_user).delete(user::delete_user)),
+      ("/user/export", get(user::export_user)),
+    ]
+    .into_iter()
+    .map(|(path, route)| {
+      (
+        path,
+        route.layer(DefaultBodyLimit::max(security::body_limit(path))),
       )
-      .route("/api/user", get(user::get_user));
+    })
+    .collect()
+  }
+
+  /// Every route, each of which must be listed in `openapi::ApiDoc`.
+  fn routes() -> Vec<(String, MethodRouter<State>)> {
+    Self::unversioned_routes()
+      .into_iter()
+      .map(|(path, route)| (path.to_string(), route))
+      .chain(
+        Self::api_routes()
+          .into_iter()
+          .map(|(path, route)| (format!("{API_V1}{path}"), route)),
+      )
+      .collect()
+  }
+
+  async fn app(config: AppConfig<'_>) -> Result<Router> {
+    let mut router = Self::routes()
+      .into_iter()
+      .fold(Router::new(), |router, (path, route)| {
+        router.route(&path, route)
+      });
+
+    // Keep serving the unversioned routes while clients migrate
+    router = Self::api_routes()
+      .into_iter()
+      .fold(router, |router, (path, route)| {
+        router.route(&format!("/api{path}"), route)
+      });
 
     // Serve microsoft identity association file
     router = router.route(
@@ -168,40 +334,76 @@ impl Server {
         .fallback_service(assets.index)
     }
 
-    let router = router
-      .with_state(State::new(config.db, config.session_store).await?)
-      .layer(
-        TraceLayer::new_for_http()
-          .on_request(|request: &Request<Body>, _span: &Span| {
-            info!("Received {} {}", request.method(), request.uri().path(),)
-          })
-          .on_response(
-            |response: &Response, latency: Duration, _span: &Span| {
-              info!("Response {} in {:?}", response.status(), latency)
-            },
-          ),
-      );
+    let cors = security::cors(&config.allowed_origins)?;
+
+    let state = State {
+      admins: config.admins,
+      allowed_origins: config.allowed_origins,
+      catalog_version: config.catalog_version,
+      db: config.db,
+      identity_provider: config.identity_provider,
+      metrics_token: config.metrics_token,
+      notification_hub: config.notification_hub,
+      pseudonyms: config.pseudonyms,
+      rate_limiter: RateLimiter::default(),
+      rate_limits: config.rate_limits,
+      request_client: reqwest::Client::new(),
+      session_policy: config.session_policy,
+      session_store: config.session_store,
+    };
 
-    Ok(if config.rate_limit {
+    let router = router
+      .layer(middleware::from_fn_with_state(
+        state.clone(),
+        catalog::cache,
+      ))
+      .layer(middleware::from_fn_with_state(
+        state.clone(),
+        rate_limits::enforce,
+      ))
+      .with_state(state)
+      .layer(middleware::from_fn(deprecation::headers))
+      .layer(CompressionLayer::new());
+
+    let router = if let Some(rate_limit) = config.rate_limit {
       router.layer(
         ServiceBuilder::new()
           .layer(HandleErrorLayer::new(|err: BoxError| async move {
-            display_error(err)
+            match err.downcast_ref::<GovernorError>() {
+              Some(GovernorError::TooManyRequests { wait_time, .. }) => {
+                RateLimited::new("ip", Duration::from_secs(*wait_time))
+                  .into_response()
+              }
+              _ => display_error(err).into_response(),
+            }
           }))
           .layer(GovernorLayer {
             config: Box::leak(Box::new(
               GovernorConfigBuilder::default()
-                .per_millisecond(10)
-                .burst_size(100)
+                .period(rate_limit.period)
+                .burst_size(rate_limit.burst)
                 .finish()
                 .ok_or(anyhow!("Failed to create governor configuration"))?,
             )),
-          })
-          .layer(CorsLayer::very_permissive()),
+          }),
       )
     } else {
-      router.layer(CorsLayer::very_permissive())
-    })
+      router
+    };
+
+    // Outside the rate limiter, so browsers can read its rejections
+    let router = router.layer(cors).layer(middleware::from_fn_with_state(
+      config.security_headers,
+      security::headers,
+    ));
+
+    // Outermost, so requests turned away by the rate limiter are counted and
+    // logged too
+    Ok(
+      router
+        .layer(middleware::from_fn(telemetry::track))
+        .layer(middleware::from_fn(request_context::layer)),
+    )
   }
 }
 
@@ -209,30 +411,46 @@ impl Server {
 mod tests {
   use {
     super::*,
-    crate::instructors::GetInstructorPayload,
     axum::body::Body,
-    courses::GetCoursesPayload,
     http::{Method, Request},
-    interactions::{
-      GetCourseReviewsInteractionPayload, GetInteractionKindPayload,
+    interactions::GetCourseReviewsInteractionPayload,
+    model::{
+      InitializationState, Instructor, InteractionKind, NotificationType,
+      SeedKind, SeedState,
     },
-    model::Notification,
     pretty_assertions::assert_eq,
-    reviews::GetReviewsPayload,
     serde::de::DeserializeOwned,
     serde_json::json,
     std::sync::atomic::{AtomicUsize, Ordering},
     tower::{Service, ServiceExt},
   };
 
+  const REDIRECT_URI: &str = "http://localhost:8000/api/auth/authorized";
+
+  const ADMIN_MAIL: &str = "admin@mail.mcgill.ca";
+
+  const METRICS_TOKEN: &str = "metrics";
+
   struct TestContext {
     app: Router,
     db: Arc<Db>,
-    session_store: MongodbSessionStore,
+    session_store: TestSessions,
+  }
+
+  /// Where `mock_login` keeps sessions, along with the metadata that
+  /// `auth::login_authorized` records for them.
+  #[derive(Clone)]
+  struct TestSessions {
+    db: Arc<Db>,
+    store: MongodbSessionStore,
   }
 
   impl TestContext {
     async fn new() -> Self {
+      Self::with_rate_limits(RateLimits::unlimited()).await
+    }
+
+    async fn with_rate_limits(rate_limits: RateLimits) -> Self {
       dotenv().ok();
 
       static TEST_DATABASE_NUMBER: AtomicUsize = AtomicUsize::new(0);
@@ -254,24 +472,43 @@ mod tests {
       let session_store = MongodbSessionStore::new(
         "mongodb://localhost:27017/?directConnection=true&replicaSet=rs0",
         &db.name(),
-        "store",
+        Db::SESSION_COLLECTION,
       )
       .await
       .unwrap();
 
       let app = Server::app(AppConfig {
+        admins: Admins::new([ADMIN_MAIL]),
+        allowed_origins: AllowedOrigins::new(vec![Url::parse(REDIRECT_URI)
+          .unwrap()
+          .origin()]),
+        catalog_version: CatalogVersion::from_hash(b"test"),
         db: db.clone(),
         assets: None,
+        identity_provider: Arc::new(DevProvider::new(
+          User::new("test", "test@mail.mcgill.ca"),
+          Url::parse(REDIRECT_URI).unwrap(),
+        )),
+        metrics_token: MetricsToken::new(METRICS_TOKEN),
+        notification_hub: NotificationHub::default(),
+        pseudonyms: Pseudonyms::new(b"test"),
+        rate_limit: None,
+        rate_limits,
+        security_headers: SecurityHeaders::from_config(&Config::default())
+          .unwrap(),
+        session_policy: SessionPolicy::default(),
         session_store: session_store.clone(),
-        rate_limit: false,
       })
       .await
       .unwrap();
 
       TestContext {
         app,
-        db,
-        session_store,
+        db: db.clone(),
+        session_store: TestSessions {
+          db,
+          store: session_store,
+        },
       }
     }
   }
@@ -281,21 +518,55 @@ mod tests {
   }
 
   async fn mock_login(
-    session_store: MongodbSessionStore,
+    session_store: TestSessions,
     id: &str,
     mail: &str,
   ) -> String {
     let mut session = Session::new();
 
+    session_store
+      .db
+      .add_session(SessionMetadata {
+        last_seen_at: bson::DateTime::now(),
+        session_id: session.id().into(),
+        user_agent: None,
+        user_id: id.into(),
+      })
+      .await
+      .unwrap();
+
     session.insert("user", User::new(id, mail)).unwrap();
 
     format!(
       "{}={}",
       COOKIE_NAME,
-      session_store.store_session(session).await.unwrap().unwrap()
+      session_store
+        .store
+        .store_session(session)
+        .await
+        .unwrap()
+        .unwrap()
     )
   }
 
+  async fn pseudonym(app: &mut Router, cookie: &str) -> String {
+    app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie)
+          .uri("/api/user")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap()
+      .convert::<serde_json::Value>()
+      .await["user"]["pseudonym"]
+      .as_str()
+      .unwrap()
+      .to_string()
+  }
+
   #[async_trait]
   trait ResponseExt {
     async fn convert<T: DeserializeOwned>(self) -> T;
@@ -313,12 +584,6 @@ mod tests {
     }
   }
 
-  #[derive(Debug, Deserialize, Serialize)]
-  pub(crate) struct GetCourseWithReviewsPayload {
-    pub(crate) course: Course,
-    pub(crate) reviews: Vec<Review>,
-  }
-
   #[tokio::test]
   async fn courses_route_works() {
     let TestContext { db, app, .. } = TestContext::new().await;
@@ -515,6 +780,156 @@ mod tests {
     assert_eq!(payload.reviews.len(), 1);
   }
 
+  #[tokio::test]
+  async fn can_get_course_stats() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+
+    let review = json!({
+      "content": "test",
+      "course_id": "MATH240",
+      "instructors": ["Adrian Roshan Vetta"],
+      "rating": 4,
+      "difficulty": 2
+    })
+    .to_string();
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", cookie)
+          .header("Content-Type", "application/json")
+          .uri("/api/reviews")
+          .body(Body::from(review))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/courses/MATH240/stats")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let stats = response.convert::<ReviewStats>().await;
+
+    assert_eq!(stats.review_count, 1);
+    assert_eq!(stats.rating_distribution, vec![0, 0, 0, 1, 0]);
+    assert_eq!(stats.difficulty_distribution, vec![0, 1, 0, 0, 0]);
+    assert_eq!(stats.yearly_averages.len(), 1);
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/courses/MATH240/stats?instructor_name=Jeremy%20Macdonald")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.convert::<ReviewStats>().await.review_count, 0);
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/courses/COMP1337/stats")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+
+  #[tokio::test]
+  async fn can_get_course_changes() {
+    let TestContext { db, mut app, .. } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: PathBuf::from("crates/db/test-seeds/before_update.json"),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    db.initialize(InitializeOptions {
+      source: PathBuf::from("crates/db/test-seeds/update.json"),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/courses/COMP251/changes")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let changes = response.convert::<Vec<CourseChange>>().await;
+
+    assert_eq!(changes, db.course_changes("COMP251").await.unwrap());
+
+    assert_eq!(
+      changes
+        .iter()
+        .map(|change| (change.field.as_str(), change.source.as_str()))
+        .collect::<Vec<(&str, &str)>>(),
+      [
+        "corequisites",
+        "instructors",
+        "prerequisites",
+        "restrictions",
+        "schedule",
+        "terms"
+      ]
+      .map(|field| (field, "update.json"))
+    );
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/courses/COMP1337/changes")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+
   #[tokio::test]
   async fn course_by_id_invalid_course_code() {
     let TestContext { db, app, .. } = TestContext::new().await;
@@ -865,23 +1280,34 @@ mod tests {
       .await
       .unwrap();
 
-    let response = app
-      .call(
-        Request::builder()
-          .method(http::Method::GET)
-          .uri("/api/reviews?user_id=test")
-          .body(Body::empty())
-          .unwrap(),
-      )
-      .await
-      .unwrap();
+    let pseudonym = pseudonym(&mut app, &cookie).await;
 
-    assert_eq!(response.status(), StatusCode::OK);
+    for (cookie, user_id, count) in [
+      (Some(&cookie), pseudonym.as_str(), 3),
+      (Some(&cookie), "test", 0),
+      (None, pseudonym.as_str(), 0),
+    ] {
+      let mut request = Request::builder()
+        .method(http::Method::GET)
+        .uri(format!("/api/reviews?user_id={user_id}"));
 
-    assert_eq!(
-      response.convert::<GetReviewsPayload>().await.reviews.len(),
-      3
-    );
+      if let Some(cookie) = cookie {
+        request = request.header("Cookie", cookie);
+      }
+
+      let response = app
+        .call(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+
+      let reviews = response.convert::<GetReviewsPayload>().await.reviews;
+
+      assert_eq!(reviews.len(), count);
+
+      assert!(reviews.iter().all(|review| review.user_id == pseudonym));
+    }
   }
 
   #[tokio::test]
@@ -972,7 +1398,13 @@ mod tests {
     .await
     .unwrap();
 
-    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+    let cookie =
+      mock_login(session_store.clone(), "test", "test@mail.mcgill.ca").await;
+
+    let voter =
+      mock_login(session_store, "test2", "test2@mail.mcgill.ca").await;
+
+    let pseudonym = pseudonym(&mut app, &cookie).await;
 
     let review = json!({
       "content": "test",
@@ -1002,9 +1434,11 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::GET)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
-          .uri("/api/interactions?course_id=MATH240&user_id=test&referrer=test")
+          .uri(format!(
+            "/api/interactions?course_id=MATH240&user_id={pseudonym}"
+          ))
           .body(Body::empty())
           .unwrap(),
       )
@@ -1019,8 +1453,7 @@ mod tests {
     let interaction = json! ({
       "kind": "like",
       "course_id": "MATH240",
-      "user_id": "test",
-      "referrer": "test"
+      "user_id": pseudonym,
     })
     .to_string();
 
@@ -1028,7 +1461,7 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::POST)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
           .uri("/api/interactions")
           .body(Body::from(interaction))
@@ -1051,9 +1484,11 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::GET)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
-          .uri("/api/interactions?course_id=MATH240&user_id=test&referrer=test")
+          .uri(format!(
+            "/api/interactions?course_id=MATH240&user_id={pseudonym}"
+          ))
           .body(Body::empty())
           .unwrap(),
       )
@@ -1071,8 +1506,7 @@ mod tests {
 
     let interaction = json! ({
       "course_id": "MATH240",
-      "user_id": "test",
-      "referrer": "test"
+      "user_id": pseudonym,
     })
     .to_string();
 
@@ -1080,7 +1514,7 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::DELETE)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
           .uri("/api/interactions")
           .body(Body::from(interaction))
@@ -1103,9 +1537,11 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::GET)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
-          .uri("/api/interactions?course_id=MATH240&user_id=test&referrer=test")
+          .uri(format!(
+            "/api/interactions?course_id=MATH240&user_id={pseudonym}"
+          ))
           .body(Body::empty())
           .unwrap(),
       )
@@ -1121,8 +1557,13 @@ mod tests {
   }
 
   #[tokio::test]
-  async fn get_invalid_instructor() {
-    let TestContext { db, mut app, .. } = TestContext::new().await;
+  async fn interactions_are_bound_to_the_session_user() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
 
     db.initialize(InitializeOptions {
       source: seed(),
@@ -1131,14 +1572,149 @@ mod tests {
     .await
     .unwrap();
 
+    let (a, b, c) = (
+      mock_login(session_store.clone(), "a", "a@mail.mcgill.ca").await,
+      mock_login(session_store.clone(), "b", "b@mail.mcgill.ca").await,
+      mock_login(session_store, "c", "c@mail.mcgill.ca").await,
+    );
+
     let response = app
       .call(
         Request::builder()
-          .method(http::Method::GET)
+          .method(http::Method::POST)
+          .header("Cookie", a.clone())
           .header("Content-Type", "application/json")
-          .uri("/api/instructors/foobar")
-          .body(Body::empty())
-          .unwrap(),
+          .uri("/api/reviews")
+          .body(Body::from(
+            json!({
+              "content": "test",
+              "course_id": "MATH240",
+              "instructors": ["Adrian Roshan Vetta"],
+              "rating": 5,
+              "difficulty": 5
+            })
+            .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let pseudonym = pseudonym(&mut app, &a).await;
+
+    let vote = |cookie: Option<&str>, method: Method, referrer: &str| {
+      let mut request = Request::builder()
+        .method(method)
+        .header("Content-Type", "application/json")
+        .uri("/api/interactions");
+
+      if let Some(cookie) = cookie {
+        request = request.header("Cookie", cookie);
+      }
+
+      request
+        .body(Body::from(
+          json!({
+            "kind": "like",
+            "course_id": "MATH240",
+            "user_id": pseudonym,
+            "referrer": referrer,
+          })
+          .to_string(),
+        ))
+        .unwrap()
+    };
+
+    let response = app.call(vote(Some(&a), Method::POST, "b")).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app.call(vote(None, Method::POST, "b")).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    assert!(db
+      .interactions_for_review("MATH240", "a")
+      .await
+      .unwrap()
+      .is_empty());
+
+    let response = app.call(vote(Some(&b), Method::POST, "c")).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app.call(vote(Some(&c), Method::DELETE, "b")).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+      db.interactions_for_review("MATH240", "a").await.unwrap(),
+      vec![Interaction {
+        kind: InteractionKind::Like,
+        user_id: "a".into(),
+        course_id: "MATH240".into(),
+        referrer: "b".into(),
+      }]
+    );
+
+    for (cookie, kind) in [(&b, Some(InteractionKind::Like)), (&c, None)] {
+      let response = app
+        .call(
+          Request::builder()
+            .header("Cookie", cookie)
+            .uri(format!(
+              "/api/interactions?course_id=MATH240&user_id={pseudonym}&referrer=b"
+            ))
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+
+      assert_eq!(
+        response.convert::<GetInteractionKindPayload>().await,
+        GetInteractionKindPayload { kind }
+      );
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri(format!(
+            "/api/interactions?course_id=MATH240&user_id={pseudonym}&referrer=b"
+          ))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+  }
+
+  #[tokio::test]
+  async fn get_invalid_instructor() {
+    let TestContext { db, mut app, .. } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::GET)
+          .header("Content-Type", "application/json")
+          .uri("/api/instructors/foobar")
+          .body(Body::empty())
+          .unwrap(),
       )
       .await
       .unwrap();
@@ -1223,7 +1799,12 @@ mod tests {
 
   #[tokio::test]
   async fn get_empty_user_interactions_for_course() {
-    let TestContext { db, mut app, .. } = TestContext::new().await;
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
 
     db.initialize(InitializeOptions {
       source: seed(),
@@ -1232,12 +1813,15 @@ mod tests {
     .await
     .unwrap();
 
+    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+
     let response = app
       .call(
         Request::builder()
           .method(http::Method::GET)
+          .header("Cookie", cookie)
           .header("Content-Type", "application/json")
-          .uri("/api/interactions/COMP202/referrer/test")
+          .uri("/api/interactions/COMP202")
           .body(Body::empty())
           .unwrap(),
       )
@@ -1270,7 +1854,13 @@ mod tests {
     .await
     .unwrap();
 
-    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+    let cookie =
+      mock_login(session_store.clone(), "test", "test@mail.mcgill.ca").await;
+
+    let voter =
+      mock_login(session_store, "test2", "test2@mail.mcgill.ca").await;
+
+    let pseudonym = pseudonym(&mut app, &cookie).await;
 
     let review = json!({
       "content": "test",
@@ -1300,8 +1890,7 @@ mod tests {
     let interaction = json! ({
       "kind": "like",
       "course_id": "MATH240",
-      "user_id": "test",
-      "referrer": "test"
+      "user_id": pseudonym,
     })
     .to_string();
 
@@ -1309,7 +1898,7 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::POST)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
           .uri("/api/interactions")
           .body(Body::from(interaction))
@@ -1332,8 +1921,9 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::GET)
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
-          .uri("/api/interactions/MATH240/referrer/test")
+          .uri("/api/interactions/MATH240")
           .body(Body::empty())
           .unwrap(),
       )
@@ -1349,7 +1939,8 @@ mod tests {
     assert_eq!(payload.course_id, "MATH240");
     assert_eq!(payload.interactions.len(), 1);
     assert_eq!(payload.interactions[0].kind, InteractionKind::Like);
-    assert_eq!(payload.interactions[0].referrer, "test");
+    assert_eq!(payload.interactions[0].referrer, "test2");
+    assert_eq!(payload.interactions[0].user_id, pseudonym);
   }
 
   #[tokio::test]
@@ -1368,7 +1959,13 @@ mod tests {
     .await
     .unwrap();
 
-    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+    let cookie =
+      mock_login(session_store.clone(), "test", "test@mail.mcgill.ca").await;
+
+    let voter =
+      mock_login(session_store, "test2", "test2@mail.mcgill.ca").await;
+
+    let pseudonym = pseudonym(&mut app, &cookie).await;
 
     let review = json!({
       "content": "test",
@@ -1398,8 +1995,7 @@ mod tests {
     let interaction = json! ({
       "kind": "like",
       "course_id": "MATH240",
-      "user_id": "test",
-      "referrer": "test"
+      "user_id": pseudonym,
     })
     .to_string();
 
@@ -1407,7 +2003,7 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::POST)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
           .uri("/api/interactions")
           .body(Body::from(interaction))
@@ -1446,9 +2042,11 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::GET)
-          .header("Cookie", cookie.clone())
+          .header("Cookie", voter.clone())
           .header("Content-Type", "application/json")
-          .uri("/api/interactions?course_id=MATH240&user_id=test&referrer=test")
+          .uri(format!(
+            "/api/interactions?course_id=MATH240&user_id={pseudonym}"
+          ))
           .body(Body::empty())
           .unwrap(),
       )
@@ -1507,6 +2105,8 @@ mod tests {
     })
     .to_string();
 
+    let pseudonym = pseudonym(&mut app, &b).await;
+
     let response = app
       .call(
         Request::builder()
@@ -1537,7 +2137,100 @@ mod tests {
       .unwrap();
 
     assert_eq!(response.status(), StatusCode::OK);
-    assert_eq!(response.convert::<Vec<Notification>>().await.len(), 1);
+
+    let notifications = response.convert::<Vec<Notification>>().await;
+
+    assert_eq!(notifications.len(), 1);
+
+    let NotificationPayload::Review(review) = &notifications[0].payload else {
+      panic!("expected a review notification");
+    };
+
+    assert_eq!(review.user_id, pseudonym);
+  }
+
+  #[tokio::test]
+  async fn notify_subscriber_of_course_changes() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    let tempdir = tempfile::tempdir().unwrap();
+
+    let source = tempdir.path().join("courses.json");
+
+    fs::copy("crates/db/test-seeds/before_update.json", &source).unwrap();
+
+    db.initialize(InitializeOptions {
+      source: source.clone(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let cookie = mock_login(session_store, "a", "a@mail.mcgill.ca").await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", cookie.clone())
+          .header("Content-Type", "application/json")
+          .uri("/api/subscriptions")
+          .body(Body::from(json!({"course_id": "COMP251"}).to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    fs::copy("crates/db/test-seeds/update.json", &source).unwrap();
+
+    db.initialize(InitializeOptions {
+      source,
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie.clone())
+          .uri("/api/notifications")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let notifications = response.convert::<Vec<Notification>>().await;
+
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].course_id, "COMP251");
+    assert!(!notifications[0].seen);
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::PUT)
+          .header("Cookie", cookie.clone())
+          .header("Content-Type", "application/json")
+          .uri(format!("/api/notifications/{}", notifications[0].id))
+          .body(Body::from(json!({"seen": true}).to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(db.get_notifications("a", None, None).await.unwrap()[0].seen);
   }
 
   #[tokio::test]
@@ -1700,7 +2393,7 @@ mod tests {
       .call(
         Request::builder()
           .method(http::Method::POST)
-          .header("Cookie", b)
+          .header("Cookie", b.clone())
           .header("Content-Type", "application/json")
           .uri("/api/reviews")
           .body(Body::from(review))
@@ -1726,16 +2419,32 @@ mod tests {
       .unwrap();
 
     assert_eq!(response.status(), StatusCode::OK);
-    assert_eq!(response.convert::<Vec<Notification>>().await.len(), 1);
+
+    let notifications = response.convert::<Vec<Notification>>().await;
+
+    assert_eq!(notifications.len(), 1);
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::DELETE)
+          .header("Cookie", b)
+          .uri(format!("/api/notifications/{}", notifications[0].id))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
     let response = app
       .call(
         Request::builder()
           .method(http::Method::DELETE)
           .header("Cookie", a.clone())
-          .header("Content-Type", "application/json")
-          .uri("/api/notifications")
-          .body(Body::from(json!({"course_id": "MATH240"}).to_string()))
+          .uri(format!("/api/notifications/{}", notifications[0].id))
+          .body(Body::empty())
           .unwrap(),
       )
       .await
@@ -1759,4 +2468,2044 @@ mod tests {
     assert_eq!(response.status(), StatusCode::OK);
     assert_eq!(response.convert::<Vec<Notification>>().await.len(), 0);
   }
+
+  #[tokio::test]
+  async fn notification_preferences_and_mark_all_seen() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    let cookie = mock_login(session_store, "a", "a@mail.mcgill.ca").await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::PUT)
+          .header("Cookie", cookie.clone())
+          .header("Content-Type", "application/json")
+          .uri("/api/notifications/preferences")
+          .body(Body::from(
+            json!({"mutedCourses": ["COMP202"], "mutedTypes": ["courseChange"]})
+              .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie.clone())
+          .uri("/api/notifications/preferences")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+      response.convert::<NotificationPreferences>().await,
+      NotificationPreferences {
+        digest: DigestFrequency::Never,
+        last_digest_at: None,
+        mail: Some("a@mail.mcgill.ca".into()),
+        muted_courses: vec!["COMP202".into()],
+        muted_types: vec![NotificationType::CourseChange],
+        user_id: "a".into(),
+      }
+    );
+
+    for course_id in ["COMP202", "MATH240", "COMP251"] {
+      db.notify_user(
+        "a",
+        course_id,
+        NotificationPayload::Review(Review {
+          course_id: course_id.into(),
+          user_id: "b".into(),
+          ..Review::default()
+        }),
+      )
+      .await
+      .unwrap();
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie.clone())
+          .uri("/api/notifications?limit=1&offset=1")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let notifications = response.convert::<Vec<Notification>>().await;
+
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].course_id, "MATH240");
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", cookie.clone())
+          .uri("/api/notifications/seen")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(db
+      .get_notifications("a", None, None)
+      .await
+      .unwrap()
+      .iter()
+      .all(|notification| notification.seen));
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::PUT)
+          .header("Cookie", cookie)
+          .header("Content-Type", "application/json")
+          .uri("/api/notifications/foo")
+          .body(Body::from(json!({"seen": false}).to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  async fn next_event_ids(response: Response, count: usize) -> Vec<String> {
+    let mut body = response.into_body().into_data_stream();
+
+    let (mut buffer, mut ids) = (String::new(), Vec::new());
+
+    while ids.len() < count {
+      buffer.push_str(
+        std::str::from_utf8(&body.next().await.unwrap().unwrap()).unwrap(),
+      );
+
+      while let Some(index) = buffer.find("\n\n") {
+        let event = buffer[..index].to_string();
+
+        buffer.drain(..index + 2);
+
+        if let Some(id) =
+          event.lines().find_map(|line| line.strip_prefix("id: "))
+        {
+          ids.push(id.to_string());
+        }
+      }
+    }
+
+    ids
+  }
+
+  #[tokio::test]
+  async fn stream_notifications() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let (a, b) = (
+      mock_login(session_store.clone(), "a", "a@mail.mcgill.ca").await,
+      mock_login(session_store, "b", "b@mail.mcgill.ca").await,
+    );
+
+    db.add_subscription(Subscription::course("MATH240", "a"))
+      .await
+      .unwrap();
+
+    let stream = app
+      .call(
+        Request::builder()
+          .header("Cookie", a)
+          .uri("/api/notifications/stream")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(stream.status(), StatusCode::OK);
+
+    assert_eq!(
+      stream.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/event-stream"
+    );
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", b)
+          .header("Content-Type", "application/json")
+          .uri("/api/reviews")
+          .body(Body::from(
+            json!({
+              "content": "test",
+              "course_id": "MATH240",
+              "instructors": ["Adrian Roshan Vetta"],
+              "rating": 5,
+              "difficulty": 5
+            })
+            .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+      next_event_ids(stream, 1).await,
+      vec![db.get_notifications("a", None, None).await.unwrap()[0]
+        .id
+        .to_hex()]
+    );
+  }
+
+  #[tokio::test]
+  async fn stream_notifications_replays_missed_events() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    let cookie = mock_login(session_store, "a", "a@mail.mcgill.ca").await;
+
+    let mut ids = Vec::new();
+
+    for course_id in ["COMP202", "COMP250", "MATH240"] {
+      ids.extend(
+        db.notify_user(
+          "a",
+          course_id,
+          NotificationPayload::Review(Review {
+            course_id: course_id.into(),
+            user_id: "b".into(),
+            ..Review::default()
+          }),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|notification| notification.id.to_hex()),
+      );
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie)
+          .header("Last-Event-ID", &ids[0])
+          .uri("/api/notifications/stream")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(next_event_ids(response, 2).await, ids[1..]);
+  }
+
+  #[tokio::test]
+  async fn send_notification_digests() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let (a, b) = (
+      mock_login(session_store.clone(), "a", "a@mail.mcgill.ca").await,
+      mock_login(session_store, "b", "b@mail.mcgill.ca").await,
+    );
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", a.clone())
+          .header("Content-Type", "application/json")
+          .uri("/api/subscriptions")
+          .body(Body::from(json!({ "course_id": "MATH240" }).to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", b)
+          .header("Content-Type", "application/json")
+          .uri("/api/reviews")
+          .body(Body::from(
+            json!({
+              "content": "test",
+              "course_id": "MATH240",
+              "instructors": ["Adrian Roshan Vetta"],
+              "rating": 5,
+              "difficulty": 5
+            })
+            .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mailer = MemoryMailer::default();
+
+    let digest = NotificationDigest::new(
+      db.clone(),
+      "mcgill.courses <noreply@mcgill.courses>",
+      Arc::new(mailer.clone()),
+    );
+
+    let now = Utc::now();
+
+    // Subscribing records the mail, but digests are opt in
+    assert_eq!(digest.run(now).await.unwrap(), 0);
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::PUT)
+          .header("Cookie", a)
+          .header("Content-Type", "application/json")
+          .uri("/api/notifications/preferences")
+          .body(Body::from(json!({ "digest": "weekly" }).to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(digest.run(now).await.unwrap(), 1);
+
+    let sent = mailer.sent();
+
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to, "a@mail.mcgill.ca");
+    assert!(sent[0].body.contains("MATH 240"));
+
+    assert_eq!(
+      digest.run(now + chrono::Duration::days(1)).await.unwrap(),
+      0
+    );
+
+    db.notify_user(
+      "a",
+      "MATH240",
+      NotificationPayload::Review(Review {
+        course_id: "MATH240".into(),
+        user_id: "c".into(),
+        ..Review::default()
+      }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+      digest.run(now + chrono::Duration::weeks(1)).await.unwrap(),
+      1
+    );
+
+    assert_eq!(mailer.sent().len(), 2);
+  }
+
+  #[tokio::test]
+  async fn subscribe_to_subjects_instructors_and_filters() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    let cookie = mock_login(session_store, "a", "a@mail.mcgill.ca").await;
+
+    let bodies = [
+      json!({ "subject": "comp" }),
+      json!({ "instructor": "Adrian Roshan Vetta" }),
+      json!({ "filter": { "subjects": ["MATH"], "levels": ["2"] } }),
+    ];
+
+    for body in bodies {
+      let response = app
+        .call(
+          Request::builder()
+            .method(http::Method::POST)
+            .header("Cookie", cookie.clone())
+            .header("Content-Type", "application/json")
+            .uri("/api/subscriptions")
+            .body(Body::from(body.to_string()))
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    assert_eq!(
+      db.get_subscriptions("a")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|subscription| subscription.target)
+        .collect::<Vec<SubscriptionTarget>>(),
+      vec![
+        SubscriptionTarget::Subject("COMP".into()),
+        SubscriptionTarget::Instructor("Adrian Roshan Vetta".into()),
+        SubscriptionTarget::Filter(CourseFilter {
+          levels: Some(vec!["2".into()]),
+          subjects: Some(vec!["MATH".into()]),
+          ..Default::default()
+        }),
+      ]
+    );
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::DELETE)
+          .header("Cookie", cookie.clone())
+          .header("Content-Type", "application/json")
+          .uri("/api/subscriptions")
+          .body(Body::from(json!({ "subject": "COMP" }).to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(db.get_subscriptions("a").await.unwrap().len(), 2);
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", cookie)
+          .header("Content-Type", "application/json")
+          .uri("/api/subscriptions")
+          .body(Body::from(
+            json!({ "course_id": "MATH240", "subject": "MATH" }).to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn export_and_delete_user() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let cookie = mock_login(session_store, "a", "a@mail.mcgill.ca").await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", cookie.clone())
+          .header("Content-Type", "application/json")
+          .uri("/api/reviews")
+          .body(Body::from(
+            json!({
+              "content": "test",
+              "course_id": "MATH240",
+              "instructors": ["Adrian Roshan Vetta"],
+              "rating": 5,
+              "difficulty": 5
+            })
+            .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::POST)
+          .header("Cookie", cookie.clone())
+          .header("Content-Type", "application/json")
+          .uri("/api/subscriptions")
+          .body(Body::from(json!({ "subject": "MATH" }).to_string()))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie.clone())
+          .uri("/api/user/export")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+      response.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+      "attachment; filename=\"mcgill-courses-export.json\""
+    );
+
+    let export = response.convert::<UserExport>().await;
+
+    assert_eq!(export.user_id, "a");
+    assert_eq!(export.reviews.len(), 1);
+    assert_eq!(export.subscriptions.len(), 1);
+    assert_eq!(export.sessions.len(), 1);
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(http::Method::DELETE)
+          .header("Cookie", cookie.clone())
+          .uri("/api/user")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(response
+      .headers()
+      .get(SET_COOKIE)
+      .unwrap()
+      .to_str()
+      .unwrap()
+      .contains("Max-Age=0"));
+
+    assert!(db.find_reviews_by_user_id("a").await.unwrap().is_empty());
+    assert!(db.get_subscriptions("a").await.unwrap().is_empty());
+
+    let course = db.find_course_by_id("MATH240").await.unwrap().unwrap();
+
+    assert_eq!(course.review_count, 0);
+    assert_eq!(course.avg_rating, 0.0);
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie)
+          .uri("/api/user/export")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+  }
+
+  #[tokio::test]
+  async fn login_redirects_are_restricted_to_allowed_origins() {
+    let TestContext { mut app, .. } = TestContext::new().await;
+
+    let origin = Url::parse(REDIRECT_URI)
+      .unwrap()
+      .origin()
+      .ascii_serialization();
+
+    for uri in [
+      "/api/auth/login?redirect=https://evil.com".to_string(),
+      format!("/api/auth/login?redirect={origin}.evil.com"),
+      "/api/auth/logout?redirect=https://evil.com".to_string(),
+    ] {
+      let response = app
+        .call(
+          Request::builder()
+            .header("Cookie", "session=foo")
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri(format!("/api/auth/login?redirect={origin}/profile"))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    let location = Url::parse(
+      response
+        .headers()
+        .get(header::LOCATION)
+        .unwrap()
+        .to_str()
+        .unwrap(),
+    )
+    .unwrap();
+
+    let params = location
+      .query_pairs()
+      .into_owned()
+      .collect::<BTreeMap<String, String>>();
+
+    assert!(location.as_str().starts_with(REDIRECT_URI));
+    assert!(params.contains_key("code"));
+    assert!(params.contains_key("state"));
+
+    assert!(response
+      .headers()
+      .get(SET_COOKIE)
+      .unwrap()
+      .to_str()
+      .unwrap()
+      .starts_with(&format!("{}=", auth::LOGIN_COOKIE_NAME)));
+  }
+
+  #[tokio::test]
+  async fn login_callback_requires_matching_state() {
+    let TestContext { mut app, .. } = TestContext::new().await;
+
+    let origin = Url::parse(REDIRECT_URI)
+      .unwrap()
+      .origin()
+      .ascii_serialization();
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri(format!("/api/auth/login?redirect={origin}"))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    let login = response
+      .headers()
+      .get(SET_COOKIE)
+      .unwrap()
+      .to_str()
+      .unwrap()
+      .split(';')
+      .next()
+      .unwrap()
+      .to_string();
+
+    let state = Url::parse(
+      response
+        .headers()
+        .get(header::LOCATION)
+        .unwrap()
+        .to_str()
+        .unwrap(),
+    )
+    .unwrap()
+    .query_pairs()
+    .find(|(key, _)| key == "state")
+    .unwrap()
+    .1
+    .into_owned();
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri(format!("/api/auth/authorized?code=foo&state={state}"))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", login.clone())
+          .uri("/api/auth/authorized?code=foo&state=forged")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", login)
+          .uri(format!("/api/auth/authorized?code=foo&state={state}"))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn dev_login_signs_in_as_configured_user() {
+    let TestContext { mut app, .. } = TestContext::new().await;
+
+    let origin = Url::parse(REDIRECT_URI)
+      .unwrap()
+      .origin()
+      .ascii_serialization();
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri(format!("/api/auth/login?redirect={origin}/profile"))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    let login = response
+      .headers()
+      .get(SET_COOKIE)
+      .unwrap()
+      .to_str()
+      .unwrap()
+      .split(';')
+      .next()
+      .unwrap()
+      .to_string();
+
+    let callback = Url::parse(
+      response
+        .headers()
+        .get(header::LOCATION)
+        .unwrap()
+        .to_str()
+        .unwrap(),
+    )
+    .unwrap();
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", login)
+          .uri(format!("{}?{}", callback.path(), callback.query().unwrap()))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+    assert_eq!(
+      response.headers().get(header::LOCATION).unwrap(),
+      &format!("{origin}/profile")
+    );
+
+    let cookie = response
+      .headers()
+      .get_all(SET_COOKIE)
+      .iter()
+      .map(|value| value.to_str().unwrap().split(';').next().unwrap())
+      .find(|cookie| cookie.starts_with(&format!("{COOKIE_NAME}=")))
+      .unwrap()
+      .to_string();
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", cookie)
+          .uri("/api/user")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    let body = response.convert::<serde_json::Value>().await;
+
+    assert_eq!(body["user"]["id"], "test");
+    assert_eq!(body["user"]["mail"], "test@mail.mcgill.ca");
+  }
+
+  #[tokio::test]
+  async fn list_and_revoke_sessions() {
+    let TestContext {
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    let first =
+      mock_login(session_store.clone(), "test", "test@mail.mcgill.ca").await;
+    let second =
+      mock_login(session_store.clone(), "test", "test@mail.mcgill.ca").await;
+    let third =
+      mock_login(session_store.clone(), "test", "test@mail.mcgill.ca").await;
+    let other =
+      mock_login(session_store.clone(), "test2", "test2@mail.mcgill.ca").await;
+
+    let sessions = app
+      .call(
+        Request::builder()
+          .header("Cookie", &first)
+          .uri("/api/sessions")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap()
+      .convert::<GetSessionsPayload>()
+      .await
+      .sessions;
+
+    assert_eq!(sessions.len(), 3);
+
+    assert_eq!(sessions.iter().filter(|session| session.current).count(), 1);
+
+    let other_id = app
+      .call(
+        Request::builder()
+          .header("Cookie", &other)
+          .uri("/api/sessions")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap()
+      .convert::<GetSessionsPayload>()
+      .await
+      .sessions
+      .remove(0)
+      .session
+      .id;
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::DELETE)
+          .header("Cookie", &first)
+          .uri(format!("/api/sessions/{other_id}"))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let second_id = sessions
+      .iter()
+      .find(|session| {
+        Some(session.session.id.as_str())
+          == Session::id_from_cookie_value(second.split_once('=').unwrap().1)
+            .ok()
+            .as_deref()
+      })
+      .unwrap()
+      .session
+      .id
+      .clone();
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::DELETE)
+          .header("Cookie", &first)
+          .uri(format!("/api/sessions/{second_id}"))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(SET_COOKIE).is_none());
+
+    for (cookie, status) in [
+      (&second, StatusCode::TEMPORARY_REDIRECT),
+      (&third, StatusCode::OK),
+    ] {
+      let response = app
+        .call(
+          Request::builder()
+            .header("Cookie", cookie)
+            .uri("/api/sessions")
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), status);
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::DELETE)
+          .header("Cookie", &first)
+          .uri("/api/sessions?others=true")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get(SET_COOKIE).is_none());
+
+    for (cookie, status) in [
+      (&first, StatusCode::OK),
+      (&third, StatusCode::TEMPORARY_REDIRECT),
+      (&other, StatusCode::OK),
+    ] {
+      let response = app
+        .call(
+          Request::builder()
+            .header("Cookie", cookie)
+            .uri("/api/sessions")
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), status);
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::DELETE)
+          .header("Cookie", &first)
+          .uri("/api/sessions")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert!(response
+      .headers()
+      .get(SET_COOKIE)
+      .unwrap()
+      .to_str()
+      .unwrap()
+      .contains("Max-Age=0"));
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", &first)
+          .uri("/api/sessions")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+  }
+
+  #[tokio::test]
+  async fn security_headers_cors_and_body_limits() {
+    let TestContext {
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/healthz")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(
+      response.headers()[header::X_CONTENT_TYPE_OPTIONS],
+      "nosniff"
+    );
+    assert!(response
+      .headers()
+      .contains_key(header::CONTENT_SECURITY_POLICY));
+
+    for (origin, allowed) in [
+      ("http://localhost:8000", true),
+      ("https://example.com", false),
+    ] {
+      let response = app
+        .call(
+          Request::builder()
+            .method(Method::OPTIONS)
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .uri("/api/v1/reviews")
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+      let headers = response.headers();
+
+      assert_eq!(
+        headers
+          .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+          .map(|value| value.to_str().unwrap()),
+        allowed.then_some(origin),
+        "{origin}"
+      );
+
+      if allowed {
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+      }
+    }
+
+    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::POST)
+          .header("Cookie", &cookie)
+          .header("Content-Type", "application/json")
+          .uri("/api/v1/interactions")
+          .body(Body::from(
+            json!({
+              "kind": "like",
+              "course_id": "MATH240",
+              "user_id": "a".repeat(8 * 1024),
+              "referrer": "test",
+            })
+            .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+  }
+
+  #[tokio::test]
+  async fn catalog_responses_are_revalidated_and_compressed() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+    } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let get = |if_none_match: Option<&str>| {
+      let mut request = Request::builder()
+        .uri("/api/v1/courses/MATH240")
+        .header(header::ACCEPT_ENCODING, "gzip");
+
+      if let Some(etag) = if_none_match {
+        request = request.header(header::IF_NONE_MATCH, etag);
+      }
+
+      request.body(Body::empty()).unwrap()
+    };
+
+    let response = app.call(get(None)).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    assert_eq!(
+      response.headers()[header::CACHE_CONTROL],
+      "public, no-cache"
+    );
+    assert_eq!(
+      response.headers()[catalog::CATALOG_VERSION],
+      hex::encode(b"test")
+    );
+
+    let etag = response.headers()[header::ETAG]
+      .to_str()
+      .unwrap()
+      .to_owned();
+
+    let response = app.call(get(Some(&etag))).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::POST)
+          .header("Cookie", &cookie)
+          .header("Content-Type", "application/json")
+          .uri("/api/v1/reviews")
+          .body(Body::from(
+            json!({
+              "content": "test",
+              "course_id": "MATH240",
+              "instructors": ["Adrian Roshan Vetta"],
+              "rating": 5,
+              "difficulty": 5
+            })
+            .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The course's ratings changed, so the old tag no longer matches
+    let response = app.call(get(Some(&etag))).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG], etag.as_str());
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/v1/reviews?course_id=MATH240")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert!(!response.headers().contains_key(header::ETAG));
+  }
+
+  #[tokio::test]
+  async fn course_listing_is_revalidated() {
+    let TestContext { db, mut app, .. } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let filter = CourseFilter {
+      subjects: Some(vec!["MATH".into()]),
+      ..Default::default()
+    };
+
+    let get = |query: &[(&str, &str)], if_none_match: Option<&str>| {
+      let mut url = Url::parse("http://localhost/api/v1/courses").unwrap();
+
+      url.query_pairs_mut().extend_pairs(query);
+
+      let mut request = Request::builder().uri(format!(
+        "{}?{}",
+        url.path(),
+        url.query().unwrap_or_default()
+      ));
+
+      if let Some(etag) = if_none_match {
+        request = request.header(header::IF_NONE_MATCH, etag);
+      }
+
+      request.body(Body::empty()).unwrap()
+    };
+
+    let encoded = serde_json::to_string(&filter).unwrap();
+
+    let query = [("filter", encoded.as_str()), ("limit", "10")];
+
+    let response = app.call(get(&query, None)).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let etag = response.headers()[header::ETAG]
+      .to_str()
+      .unwrap()
+      .to_owned();
+
+    assert_eq!(
+      response.convert::<GetCoursesPayload>().await.courses,
+      db.courses(Some(10), None, Some(filter)).await.unwrap()
+    );
+
+    let response = app.call(get(&query, Some(&etag))).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+    // Another page is another representation
+    let response = app
+      .call(get(
+        &[
+          ("filter", encoded.as_str()),
+          ("limit", "10"),
+          ("offset", "10"),
+        ],
+        Some(&etag),
+      ))
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG], etag.as_str());
+
+    let response = app
+      .call(get(&[("filter", "{\"subjects\": \"MATH\"}")], None))
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn rate_limit_policies() {
+    let TestContext {
+      mut app,
+      session_store,
+      ..
+    } = TestContext::with_rate_limits(RateLimits {
+      interactions: 2,
+      reviews: 1,
+      search: 1,
+    })
+    .await;
+
+    let a = mock_login(session_store.clone(), "a", "a@mail.mcgill.ca").await;
+    let b = mock_login(session_store, "b", "b@mail.mcgill.ca").await;
+
+    let review = json!({
+      "content": "test",
+      "course_id": "MATH240",
+      "instructors": [],
+      "rating": 5,
+      "difficulty": 5
+    })
+    .to_string();
+
+    let mut statuses = Vec::new();
+
+    for (cookie, uri) in [
+      (&a, "/api/reviews"),
+      (&b, "/api/v1/reviews"),
+      (&a, "/api/v1/reviews"),
+    ] {
+      statuses.push(
+        app
+          .call(
+            Request::builder()
+              .method(Method::POST)
+              .header("Cookie", cookie)
+              .header("Content-Type", "application/json")
+              .uri(uri)
+              .body(Body::from(review.clone()))
+              .unwrap(),
+          )
+          .await
+          .unwrap(),
+      );
+    }
+
+    let rejected = statuses.pop().unwrap();
+
+    for response in statuses {
+      assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let retry_after = rejected.headers()[header::RETRY_AFTER]
+      .to_str()
+      .unwrap()
+      .parse::<u64>()
+      .unwrap();
+
+    let payload = rejected.convert::<RateLimitedPayload>().await;
+
+    assert_eq!(payload.limiter, "reviews");
+    assert_eq!(payload.retry_after, retry_after);
+    assert!((1..=60).contains(&retry_after));
+
+    // Reading reviews isn't limited
+    let response = app
+      .call(
+        Request::builder()
+          .header("Cookie", &a)
+          .uri("/api/v1/reviews?course_id=MATH240")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let search = |app: &mut Router| {
+      app.call(
+        Request::builder()
+          .uri("/api/v1/search?query=comp")
+          .body(Body::empty())
+          .unwrap(),
+      )
+    };
+
+    assert_eq!(search(&mut app).await.unwrap().status(), StatusCode::OK);
+
+    let rejected = search(&mut app).await.unwrap();
+
+    assert_eq!(rejected.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+      rejected.convert::<RateLimitedPayload>().await.limiter,
+      "search"
+    );
+  }
+
+  #[tokio::test]
+  async fn api_tokens_authenticate_with_scopes_and_rate_limits() {
+    let TestContext {
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::POST)
+          .header("Cookie", &cookie)
+          .header("Content-Type", "application/json")
+          .uri("/api/tokens")
+          .body(Body::from(
+            json!({
+              "name": "discord bot",
+              "scopes": ["subscriptions:read", "user:read"],
+              "rateLimit": 2,
+            })
+            .to_string(),
+          ))
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let created = response.convert::<AddApiTokenPayload>().await;
+
+    assert!(created.token.starts_with(api_tokens::TOKEN_PREFIX));
+
+    let bearer = format!("Bearer {}", created.token);
+
+    let tokens = app
+      .call(
+        Request::builder()
+          .header("Cookie", &cookie)
+          .uri("/api/tokens")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap()
+      .convert::<serde_json::Value>()
+      .await;
+
+    assert_eq!(tokens[0]["id"], created.api_token.id.as_str());
+    assert_eq!(tokens[0].get("hash"), None);
+
+    let response = app
+      .call(
+        Request::builder()
+          .header("Authorization", &bearer)
+          .uri("/api/user")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap()
+      .convert::<serde_json::Value>()
+      .await;
+
+    assert_eq!(response["user"]["id"], "test");
+
+    for (method, uri, status) in [
+      (Method::GET, "/api/subscriptions", StatusCode::OK),
+      (Method::POST, "/api/subscriptions", StatusCode::FORBIDDEN),
+      (Method::GET, "/api/tokens", StatusCode::FORBIDDEN),
+      (Method::GET, "/api/v1/user/export", StatusCode::FORBIDDEN),
+      (Method::GET, "/api/reviews", StatusCode::FORBIDDEN),
+      (
+        Method::GET,
+        "/api/subscriptions",
+        StatusCode::TOO_MANY_REQUESTS,
+      ),
+      (Method::GET, "/api/user", StatusCode::TOO_MANY_REQUESTS),
+    ] {
+      let response = app
+        .call(
+          Request::builder()
+            .method(method)
+            .header("Authorization", &bearer)
+            .header("Content-Type", "application/json")
+            .uri(uri)
+            .body(Body::from(json!({ "course_id": "MATH240" }).to_string()))
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), status, "{uri}");
+
+      if status == StatusCode::TOO_MANY_REQUESTS {
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+      }
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .method(Method::DELETE)
+          .header("Cookie", &cookie)
+          .uri(format!("/api/tokens/{}", created.api_token.id))
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for token in [created.token.as_str(), "mcc_forged", "not-a-token"] {
+      for uri in ["/api/subscriptions", "/api/user"] {
+        let response = app
+          .call(
+            Request::builder()
+              .header("Authorization", format!("Bearer {token}"))
+              .uri(uri)
+              .body(Body::empty())
+              .unwrap(),
+          )
+          .await
+          .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{uri}");
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn every_route_is_documented() {
+    let TestContext { mut app, .. } = TestContext::new().await;
+
+    let spec = openapi::spec();
+
+    let mut documented = spec["paths"]
+      .as_object()
+      .unwrap()
+      .iter()
+      .flat_map(|(path, operations)| {
+        operations
+          .as_object()
+          .unwrap()
+          .keys()
+          .map(move |method| format!("{} {path}", method.to_uppercase()))
+      })
+      .collect::<Vec<String>>();
+
+    let mut routed = Vec::new();
+
+    let mut aliased = Vec::new();
+
+    for (path, _) in Server::routes() {
+      let segments = path.split('/');
+
+      let uri = segments
+        .clone()
+        .map(|segment| {
+          if segment.starts_with(':') {
+            "x"
+          } else {
+            segment
+          }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+      let path = segments
+        .map(|segment| match segment.strip_prefix(':') {
+          Some(name) => format!("{{{name}}}"),
+          None => segment.into(),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+      for method in [
+        Method::DELETE,
+        Method::GET,
+        Method::PATCH,
+        Method::POST,
+        Method::PUT,
+      ] {
+        let response = app
+          .call(
+            Request::builder()
+              .method(method.clone())
+              .uri(&uri)
+              .body(Body::empty())
+              .unwrap(),
+          )
+          .await
+          .unwrap();
+
+        assert!(!response.headers().contains_key("deprecation"), "{uri}");
+
+        if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+          routed.push(format!("{method} {path}"));
+        }
+
+        let Some(route) = uri.strip_prefix(API_V1) else {
+          continue;
+        };
+
+        let alias = app
+          .call(
+            Request::builder()
+              .method(method.clone())
+              .uri(format!("/api{route}"))
+              .body(Body::empty())
+              .unwrap(),
+          )
+          .await
+          .unwrap();
+
+        if alias.status() != StatusCode::METHOD_NOT_ALLOWED {
+          aliased.push(format!("{method} {path}"));
+
+          assert!(alias.headers().contains_key("deprecation"), "/api{route}");
+
+          assert!(
+            alias.headers().get_all(header::LINK).iter().any(
+              |link| *link == format!("<{uri}>; rel=\"successor-version\"")
+            )
+          );
+        }
+      }
+    }
+
+    documented.sort();
+    routed.sort();
+    aliased.sort();
+
+    assert_eq!(routed, documented);
+
+    assert_eq!(
+      aliased,
+      routed
+        .iter()
+        .filter(|route| route.contains(API_V1))
+        .cloned()
+        .collect::<Vec<String>>()
+    );
+  }
+
+  #[tokio::test]
+  async fn health_and_readiness() {
+    let TestContext { db, mut app, .. } = TestContext::new().await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/healthz")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/readyz")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    assert_eq!(
+      response.convert::<ReadinessPayload>().await,
+      ReadinessPayload {
+        database: true,
+        indexes: false,
+        initialization: InitializationState::Idle,
+        ready: false,
+      }
+    );
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/readyz")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+      response.convert::<ReadinessPayload>().await,
+      ReadinessPayload {
+        database: true,
+        indexes: true,
+        initialization: InitializationState::Complete,
+        ready: true,
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn responses_carry_request_ids() {
+    let TestContext { mut app, .. } = TestContext::new().await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/v1/courses/COMP1337")
+          .header("X-Request-Id", "upstream-1")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.headers()["x-request-id"], "upstream-1");
+
+    let first = app
+      .call(
+        Request::builder()
+          .uri("/healthz")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    let second = app
+      .call(
+        Request::builder()
+          .uri("/healthz")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(first.headers()["x-request-id"].len(), 32);
+    assert_ne!(
+      first.headers()["x-request-id"],
+      second.headers()["x-request-id"]
+    );
+  }
+
+  #[tokio::test]
+  async fn serves_metrics() {
+    let TestContext {
+      mut app,
+      session_store,
+      ..
+    } = TestContext::new().await;
+
+    mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+
+    let labels = ["GET", "/api/v1/courses/:id", "404"];
+
+    let before = telemetry::HTTP_REQUESTS.with_label_values(&labels).get();
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/v1/courses/COMP1337")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    assert_eq!(
+      telemetry::HTTP_REQUESTS.with_label_values(&labels).get(),
+      before + 1
+    );
+
+    for authorization in [None, Some("Bearer wrong"), Some(METRICS_TOKEN)] {
+      let mut request = Request::builder().uri("/metrics");
+
+      if let Some(authorization) = authorization {
+        request = request.header(header::AUTHORIZATION, authorization);
+      }
+
+      let response = app
+        .call(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+      assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = app
+      .call(
+        Request::builder()
+          .header(header::AUTHORIZATION, format!("Bearer {METRICS_TOKEN}"))
+          .uri("/metrics")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+      response.headers()[header::CONTENT_TYPE],
+      "text/plain; version=0.0.4; charset=utf-8"
+    );
+
+    let body = String::from_utf8(
+      axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap()
+        .to_vec(),
+    )
+    .unwrap();
+
+    for expected in [
+      "# TYPE http_requests_total counter",
+      "http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/v1/courses/:id\",status=\"404\",le=\"+Inf\"}",
+      "mongodb_command_duration_seconds_count{command=\"find\",outcome=\"success\"}",
+      "# TYPE sessions gauge",
+    ] {
+      assert!(body.contains(expected), "{expected}");
+    }
+
+    assert!(telemetry::SESSIONS.get() >= 1);
+  }
+
+  #[tokio::test]
+  async fn initialization_progress_is_for_admins() {
+    let TestContext {
+      db,
+      mut app,
+      session_store,
+    } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/v1/admin/initialization")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    let cookie =
+      mock_login(session_store.clone(), "test", "test@mail.mcgill.ca").await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/v1/admin/initialization")
+          .header("Cookie", cookie)
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let cookie = mock_login(session_store, "admin", ADMIN_MAIL).await;
+
+    let response = app
+      .call(
+        Request::builder()
+          .uri("/api/v1/admin/initialization")
+          .header("Cookie", cookie)
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let status = response.convert::<InitializationStatus>().await;
+
+    assert_eq!(status.state, InitializationState::Complete);
+
+    assert_eq!(
+      status
+        .seeds
+        .iter()
+        .map(|seed| (seed.file.as_str(), seed.kind, seed.state))
+        .collect::<Vec<_>>(),
+      vec![("mini.json", SeedKind::Courses, SeedState::Complete)]
+    );
+
+    assert_eq!(status.seeds[0].completed, status.seeds[0].total);
+  }
+
+  #[tokio::test]
+  async fn serves_openapi_document() {
+    let TestContext { app, .. } = TestContext::new().await;
+
+    let response = app
+      .oneshot(
+        Request::builder()
+          .uri("/api/v1/openapi.json")
+          .body(Body::empty())
+          .unwrap(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert_eq!(
+      response.convert::<serde_json::Value>().await,
+      openapi::spec()
+    );
+  }
+
+  #[tokio::test]
+  async fn api_client_works_against_server() {
+    let TestContext {
+      db,
+      app,
+      session_store,
+    } = TestContext::new().await;
+
+    db.initialize(InitializeOptions {
+      source: seed(),
+      ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    assert_eq!(api_client::SESSION_COOKIE, COOKIE_NAME);
+
+    let cookie = mock_login(session_store, "test", "test@mail.mcgill.ca").await;
+
+    let client = api_client::Client::new(&base_url)
+      .unwrap()
+      .with_session(&cookie[COOKIE_NAME.len() + 1..]);
+
+    let courses = client
+      .courses(
+        &GetCoursesParams {
+          with_course_count: Some(true),
+          ..Default::default()
+        },
+        &CourseFilter::default(),
+      )
+      .await
+      .unwrap();
+
+    assert_eq!(courses.courses, db.courses(None, None, None).await.unwrap());
+
+    assert_eq!(courses.course_count, Some(db.course_count().await.unwrap()));
+
+    assert_eq!(
+      client.course("MATH240").await.unwrap().unwrap().id,
+      "MATH240"
+    );
+
+    assert_eq!(client.course("MATH999").await.unwrap(), None);
+
+    client
+      .add_review(&AddOrUpdateReviewBody {
+        content: "test".into(),
+        course_id: "MATH240".into(),
+        instructors: vec!["Adrian Roshan Vetta".into()],
+        rating: 5,
+        difficulty: 5,
+      })
+      .await
+      .unwrap();
+
+    assert_eq!(
+      client.review("MATH240").await.unwrap().unwrap().content,
+      "test"
+    );
+
+    client
+      .add_subscription(SubscriptionTarget::Course("MATH240".into()))
+      .await
+      .unwrap();
+
+    assert_eq!(
+      client.subscriptions().await.unwrap(),
+      vec![Subscription::course("MATH240", "test")]
+    );
+
+    assert_eq!(client.user().await.unwrap().unwrap().id, "test");
+
+    let token = client
+      .add_api_token(&AddApiTokenBody {
+        name: "script".into(),
+        rate_limit: None,
+        scopes: vec![TokenScope::ReviewsRead],
+      })
+      .await
+      .unwrap();
+
+    let token_client = api_client::Client::new(&base_url)
+      .unwrap()
+      .with_token(&token.token);
+
+    assert_eq!(
+      token_client
+        .reviews(&GetReviewsParams {
+          course_id: Some("MATH240".into()),
+          ..Default::default()
+        })
+        .await
+        .unwrap()
+        .reviews
+        .len(),
+      1
+    );
+
+    assert!(token_client.subscriptions().await.is_err());
+
+    assert_eq!(
+      api_client::Client::new(&base_url)
+        .unwrap()
+        .user()
+        .await
+        .unwrap(),
+      None
+    );
+  }
 }
@@ -0,0 +1,139 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SessionPolicy {
+  pub(crate) expiry: Duration,
+  /// Push a session's expiry out on every request instead of expiring it a
+  /// fixed time after login.
+  pub(crate) sliding: bool,
+}
+
+impl Default for SessionPolicy {
+  fn default() -> Self {
+    Self {
+      expiry: Duration::from_secs(60 * 60 * 24 * 7),
+      sliding: false,
+    }
+  }
+}
+
+impl SessionPolicy {
+  pub(crate) fn from_config(config: &Config) -> Self {
+    Self {
+      expiry: Duration::from_secs(config.sessions.expiry_days * 60 * 60 * 24),
+      sliding: config.sessions.sliding,
+    }
+  }
+
+  pub(crate) fn expires_at(&self) -> bson::DateTime {
+    bson::DateTime::from_millis(
+      bson::DateTime::now().timestamp_millis() + self.expiry.as_millis() as i64,
+    )
+  }
+}
+
+impl FromRef<State> for SessionPolicy {
+  fn from_ref(state: &State) -> Self {
+    state.session_policy
+  }
+}
+
+pub(crate) fn current_session_id(cookies: &Cookie) -> Option<String> {
+  Session::id_from_cookie_value(cookies.get(COOKIE_NAME)?).ok()
+}
+
+/// List active sessions
+#[utoipa::path(
+  get,
+  path = "/api/v1/sessions",
+  responses(
+    (status = 200, description = "The sessions", body = GetSessionsPayload),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn get_sessions(
+  user: User,
+  TypedHeader(cookies): TypedHeader<Cookie>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  let current = current_session_id(&cookies);
+
+  Ok(Json(GetSessionsPayload {
+    sessions: db
+      .sessions(&user.id())
+      .await?
+      .into_iter()
+      .map(|session| SessionPayload {
+        current: current.as_deref() == Some(session.id.as_str()),
+        session,
+      })
+      .collect(),
+  }))
+}
+
+/// Sign out of every session
+#[utoipa::path(
+  delete,
+  path = "/api/v1/sessions",
+  params(DeleteSessionsParams),
+  responses((status = 200, description = "Success")),
+  security(("session" = [])),
+)]
+pub(crate) async fn delete_sessions(
+  user: User,
+  Query(params): Query<DeleteSessionsParams>,
+  TypedHeader(cookies): TypedHeader<Cookie>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  let current = current_session_id(&cookies);
+
+  let user_id = user.id();
+
+  let except = params.others.then_some(current.as_deref()).flatten();
+
+  let revoked = db.revoke_sessions(&user_id, except).await?;
+
+  info!("Revoked {} sessions for user {}", revoked, user_id);
+
+  let mut headers = HeaderMap::new();
+
+  if except.is_none() {
+    headers.insert(SET_COOKIE, clear_session_cookie()?);
+  }
+
+  Ok(headers)
+}
+
+/// Sign out of a session
+#[utoipa::path(
+  delete,
+  path = "/api/v1/sessions/{id}",
+  params(("id" = String, Path)),
+  responses(
+    (status = 200, description = "Success"),
+    (status = 404, description = "Not found"),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn delete_session(
+  user: User,
+  Path(id): Path<String>,
+  TypedHeader(cookies): TypedHeader<Cookie>,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<Response> {
+  if !db.revoke_session(&user.id(), &id).await? {
+    return Ok(StatusCode::NOT_FOUND.into_response());
+  }
+
+  let mut headers = HeaderMap::new();
+
+  if current_session_id(&cookies).as_deref() == Some(id.as_str()) {
+    headers.insert(SET_COOKIE, clear_session_cookie()?);
+  }
+
+  Ok(headers.into_response())
+}
+
+pub(crate) fn clear_session_cookie() -> Result<HeaderValue> {
+  Ok(format!("{}=; Max-Age=0; SameSite=Lax; Path=/", COOKIE_NAME).parse()?)
+}
@@ -0,0 +1,261 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SmtpSecurity {
+  None,
+  #[default]
+  StartTls,
+  Tls,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SmtpMailer {
+  transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+  const CLIENT_NAME: &'static str = "mcgill.courses";
+
+  pub(crate) fn from_config(config: &Config) -> Result<Self> {
+    let security = config.smtp.security;
+
+    let port = config.smtp.port.unwrap_or(match security {
+      SmtpSecurity::None => 25,
+      SmtpSecurity::StartTls => 587,
+      SmtpSecurity::Tls => 465,
+    });
+
+    let credentials = match (
+      config.smtp.username.as_deref(),
+      config.smtp.password.as_deref(),
+    ) {
+      (Some(username), Some(password)) => {
+        Some(Credentials::new(username.into(), password.into()))
+      }
+      _ => None,
+    };
+
+    // `AUTH PLAIN` sends the password as is
+    if credentials.is_some() && security == SmtpSecurity::None {
+      return Err(Error(anyhow!(
+        "SMTP credentials can't be sent without TLS, set `smtp.security` to `starttls` or `tls`"
+      )));
+    }
+
+    Self::new(require!(config.smtp.host)?, port, security, credentials)
+  }
+
+  fn new(
+    host: &str,
+    port: u16,
+    security: SmtpSecurity,
+    credentials: Option<Credentials>,
+  ) -> Result<Self> {
+    let builder = match security {
+      SmtpSecurity::None => {
+        AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+      }
+      SmtpSecurity::StartTls => {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+      }
+      SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+    }
+    .hello_name(SmtpClientId::Domain(Self::CLIENT_NAME.into()))
+    .port(port);
+
+    Ok(Self {
+      transport: match credentials {
+        Some(credentials) => builder.credentials(credentials).build(),
+        None => builder.build(),
+      },
+    })
+  }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+  async fn send(&self, mail: &Mail) -> Result {
+    self.transport.send(mail.message(Utc::now())?).await?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    pretty_assertions::assert_eq,
+    tokio::{
+      io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+      net::TcpListener,
+    },
+  };
+
+  /// Replies to each command in turn, returning the commands received and
+  /// the data sent after `DATA`.
+  async fn converse(
+    mail: Mail,
+    replies: &'static [&'static str],
+  ) -> (Result, Vec<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+    let mailer = SmtpMailer::new(
+      "127.0.0.1",
+      listener.local_addr().unwrap().port(),
+      SmtpSecurity::None,
+      None,
+    )
+    .unwrap();
+
+    let transcript = tokio::spawn(async move {
+      let mut transcript = Vec::new();
+
+      let mut server = BufReader::new(listener.accept().await.unwrap().0);
+
+      for (index, reply) in replies.iter().enumerate() {
+        if index > 0 {
+          let mut line = String::new();
+
+          server.read_line(&mut line).await.unwrap();
+
+          if transcript.last().map(String::as_str) == Some("DATA") {
+            while !line.ends_with("\r\n.\r\n") {
+              server.read_line(&mut line).await.unwrap();
+            }
+          }
+
+          transcript.push(line.trim_end().to_string());
+        }
+
+        server.get_mut().write_all(reply.as_bytes()).await.unwrap();
+      }
+
+      transcript
+    });
+
+    let result = mailer.send(&mail).await;
+
+    (result, transcript.await.unwrap())
+  }
+
+  fn mail() -> Mail {
+    Mail {
+      body: "hello\n.leading dot".into(),
+      from: "mcgill.courses <noreply@mcgill.courses>".into(),
+      subject: "digest".into(),
+      to: "a@mail.mcgill.ca".into(),
+    }
+  }
+
+  #[tokio::test]
+  async fn send_mail() {
+    let (result, transcript) = converse(
+      mail(),
+      &[
+        "220 localhost ready\r\n",
+        "250 localhost\r\n",
+        "250 ok\r\n",
+        "250 ok\r\n",
+        "354 go ahead\r\n",
+        "250 queued\r\n",
+        "221 bye\r\n",
+      ],
+    )
+    .await;
+
+    result.unwrap();
+
+    assert_eq!(transcript[0], "EHLO mcgill.courses");
+    assert_eq!(transcript[1], "MAIL FROM:<noreply@mcgill.courses>");
+    assert_eq!(transcript[2], "RCPT TO:<a@mail.mcgill.ca>");
+    assert_eq!(transcript[3], "DATA");
+    assert!(
+      transcript[4].ends_with("\r\nhello\r\n..leading dot\r\n."),
+      "{}",
+      transcript[4]
+    );
+    assert_eq!(transcript[5], "QUIT");
+  }
+
+  #[tokio::test]
+  async fn invalid_recipients_are_refused_before_connecting() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+    let mailer = SmtpMailer::new(
+      "127.0.0.1",
+      listener.local_addr().unwrap().port(),
+      SmtpSecurity::None,
+      None,
+    )
+    .unwrap();
+
+    assert_eq!(
+      mailer
+        .send(&Mail {
+          to: "a@mail.mcgill.ca>\r\nRCPT TO:<b@mail.mcgill.ca".into(),
+          ..mail()
+        })
+        .await
+        .unwrap_err()
+        .to_string(),
+      "Invalid mail address `a@mail.mcgill.ca>\\r\\nRCPT TO:<b@mail.mcgill.ca`"
+    );
+
+    assert!(tokio::time::timeout(
+      Duration::from_millis(100),
+      listener.accept()
+    )
+    .await
+    .is_err());
+  }
+
+  #[test]
+  fn credentials_require_tls() {
+    let config = |security: &str| {
+      Config::from_sources(
+        None,
+        &[
+          ("SMTP_HOST", "localhost"),
+          ("SMTP_PASSWORD", "pass"),
+          ("SMTP_SECURITY", security),
+          ("SMTP_USERNAME", "user"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into()))
+        .collect(),
+      )
+      .unwrap()
+    };
+
+    assert_eq!(
+      SmtpMailer::from_config(&config("none"))
+        .unwrap_err()
+        .to_string(),
+      "SMTP credentials can't be sent without TLS, set `smtp.security` to `starttls` or `tls`"
+    );
+
+    for security in ["starttls", "tls"] {
+      assert!(SmtpMailer::from_config(&config(security)).is_ok());
+    }
+  }
+
+  #[tokio::test]
+  async fn rejected_recipient() {
+    let (result, _) = converse(
+      mail(),
+      &[
+        "220 localhost ready\r\n",
+        "250 localhost\r\n",
+        "250 ok\r\n",
+        "550 no such user\r\n",
+      ],
+    )
+    .await;
+
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "permanent error (550): no such user"
+    );
+  }
+}
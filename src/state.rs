@@ -2,77 +2,65 @@ use super::*;
 
 #[derive(Debug, Clone)]
 pub(crate) struct State {
-  pub(crate) client_secret: String,
+  pub(crate) admins: Admins,
+  pub(crate) allowed_origins: AllowedOrigins,
+  pub(crate) catalog_version: CatalogVersion,
   pub(crate) db: Arc<Db>,
-  pub(crate) oauth_client: BasicClient,
+  pub(crate) identity_provider: Arc<dyn IdentityProvider>,
+  pub(crate) metrics_token: MetricsToken,
+  pub(crate) notification_hub: NotificationHub,
+  pub(crate) pseudonyms: Pseudonyms,
+  pub(crate) rate_limiter: RateLimiter,
+  pub(crate) rate_limits: RateLimits,
   pub(crate) request_client: reqwest::Client,
+  pub(crate) session_policy: SessionPolicy,
   pub(crate) session_store: MongodbSessionStore,
 }
 
+impl FromRef<State> for Admins {
+  fn from_ref(state: &State) -> Self {
+    state.admins.clone()
+  }
+}
+
+impl FromRef<State> for AllowedOrigins {
+  fn from_ref(state: &State) -> Self {
+    state.allowed_origins.clone()
+  }
+}
+
 impl FromRef<State> for Arc<Db> {
   fn from_ref(state: &State) -> Self {
     state.db.clone()
   }
 }
 
-impl FromRef<State> for BasicClient {
+impl FromRef<State> for NotificationHub {
   fn from_ref(state: &State) -> Self {
-    state.oauth_client.clone()
+    state.notification_hub.clone()
   }
 }
 
-impl FromRef<State> for reqwest::Client {
+impl FromRef<State> for Pseudonyms {
   fn from_ref(state: &State) -> Self {
-    state.request_client.clone()
+    state.pseudonyms.clone()
   }
 }
 
-impl FromRef<State> for MongodbSessionStore {
+impl FromRef<State> for RateLimiter {
   fn from_ref(state: &State) -> Self {
-    state.session_store.clone()
+    state.rate_limiter.clone()
   }
 }
 
-impl State {
-  pub(crate) async fn new(
-    db: Arc<Db>,
-    session_store: MongodbSessionStore,
-  ) -> Result<Self> {
-    let client_secret = env::var("MS_CLIENT_SECRET")
-      .expect("Missing the MS_CLIENT_SECRET environment variable.");
+impl FromRef<State> for reqwest::Client {
+  fn from_ref(state: &State) -> Self {
+    state.request_client.clone()
+  }
+}
 
-    Ok(Self {
-      client_secret: client_secret.clone(),
-      db: db.clone(),
-      oauth_client: BasicClient::new(
-        ClientId::new(
-          env::var("MS_CLIENT_ID")
-            .expect("Missing the MS_CLIENT_ID environment variable"),
-        ),
-        Some(ClientSecret::new(client_secret)),
-        AuthUrl::new(
-          "https://login.microsoftonline.com/common/oauth2/v2.0/authorize"
-            .to_string(),
-        )
-        .expect("Invalid authorization URL"),
-        Some(
-          TokenUrl::new(
-            "https://login.microsoftonline.com/common/oauth2/v2.0/token"
-              .to_string(),
-          )
-          .expect("Invalid token endpoint URL"),
-        ),
-      )
-      .set_auth_type(AuthType::RequestBody)
-      .set_redirect_uri(
-        RedirectUrl::new(
-          env::var("MS_REDIRECT_URI")
-            .expect("Missing the MS_REDIRECT_URI environment variable."),
-        )
-        .expect("Invalid redirect URL"),
-      ),
-      request_client: reqwest::Client::new(),
-      session_store,
-    })
+impl FromRef<State> for MongodbSessionStore {
+  fn from_ref(state: &State) -> Self {
+    state.session_store.clone()
   }
 }
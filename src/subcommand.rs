@@ -2,6 +2,7 @@ use super::*;
 
 #[derive(Parser)]
 pub(crate) enum Subcommand {
+  Import(Importer),
   Load(Loader),
   Serve(Server),
 }
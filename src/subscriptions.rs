@@ -1,68 +1,139 @@
 use super::*;
 
-#[derive(Deserialize)]
-pub(crate) struct GetSubscriptionParams {
-  course_id: Option<String>,
+/// Every subscription, or only the one to a course when asked for.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub(crate) enum GetSubscriptionPayload {
+  All(Vec<Subscription>),
+  One(Option<Subscription>),
 }
 
+/// List subscriptions
+#[utoipa::path(
+  get,
+  path = "/api/v1/subscriptions",
+  params(GetSubscriptionParams),
+  responses(
+    (
+      status = 200,
+      description = "Every subscription, or only the one to `course_id` when it is set",
+      body = GetSubscriptionPayload,
+    ),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn get_subscription(
   user: User,
   AppState(db): AppState<Arc<Db>>,
   params: Query<GetSubscriptionParams>,
 ) -> Result<impl IntoResponse> {
   Ok(Json(match &params.course_id {
-    Some(course_id) => {
-      json!(db.get_subscription(&user.id(), course_id).await?)
+    Some(course_id) => GetSubscriptionPayload::One(
+      db.get_subscription(&user.id(), course_id).await?,
+    ),
+    None => {
+      GetSubscriptionPayload::All(db.get_subscriptions(&user.id()).await?)
     }
-    None => json!(db.get_subscriptions(&user.id()).await?),
   }))
 }
 
-#[derive(Deserialize)]
-pub(crate) struct AddOrDeleteSubscriptionBody {
-  course_id: String,
+fn target(body: AddOrDeleteSubscriptionBody) -> Option<SubscriptionTarget> {
+  match (body.course_id, body.filter, body.instructor, body.subject) {
+    (Some(course_id), None, None, None) => {
+      Some(SubscriptionTarget::Course(course_id))
+    }
+    (None, Some(filter), None, None) => {
+      Some(SubscriptionTarget::Filter(CourseFilter {
+        sort_by: None,
+        ..filter
+      }))
+    }
+    (None, None, Some(instructor), None) => {
+      Some(SubscriptionTarget::Instructor(instructor))
+    }
+    (None, None, None, Some(subject)) => {
+      Some(SubscriptionTarget::Subject(subject.to_uppercase()))
+    }
+    _ => None,
+  }
 }
 
+const INVALID_TARGET: &str =
+  "expected exactly one of `course_id`, `filter`, `instructor` or `subject`";
+
+/// Subscribe to updates
+#[utoipa::path(
+  post,
+  path = "/api/v1/subscriptions",
+  request_body = AddOrDeleteSubscriptionBody,
+  responses(
+    (status = 200, description = "The database update result", body = Value),
+    (status = 400, description = "Not exactly one target"),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn add_subscription(
   user: User,
   AppState(db): AppState<Arc<Db>>,
   body: Json<AddOrDeleteSubscriptionBody>,
-) -> Result<impl IntoResponse> {
+) -> Result<Response> {
+  let Some(target) = target(body.0) else {
+    return Ok((StatusCode::BAD_REQUEST, INVALID_TARGET).into_response());
+  };
+
+  let mail = user.mail().to_string();
+
   let user_id = user.id();
 
-  info!(
-    "Adding subscription for user {} to course {}",
-    &user_id, body.course_id
-  );
+  info!("Adding subscription for user {} to {:?}", &user_id, target);
 
-  Ok(Json(
-    db.add_subscription(Subscription {
-      user_id,
-      course_id: body.course_id.clone(),
-    })
-    .await?,
-  ))
+  db.record_mail(&user_id, &mail).await?;
+
+  Ok(
+    Json(
+      db.add_subscription(Subscription { target, user_id })
+        .await?,
+    )
+    .into_response(),
+  )
 }
 
+/// Unsubscribe from updates
+#[utoipa::path(
+  delete,
+  path = "/api/v1/subscriptions",
+  request_body = AddOrDeleteSubscriptionBody,
+  responses(
+    (status = 200, description = "The database delete result", body = Value),
+    (status = 400, description = "Not exactly one target"),
+  ),
+  security(("session" = [])),
+)]
 pub(crate) async fn delete_subscription(
   user: User,
   AppState(db): AppState<Arc<Db>>,
   body: Json<AddOrDeleteSubscriptionBody>,
-) -> Result<impl IntoResponse> {
+) -> Result<Response> {
+  let Some(target) = target(body.0) else {
+    return Ok((StatusCode::BAD_REQUEST, INVALID_TARGET).into_response());
+  };
+
   let user_id = user.id();
 
   info!(
-    "Removing subscription for user {} to course {}",
-    &user_id, body.course_id
+    "Removing subscription for user {} to {:?}",
+    &user_id, target
   );
 
-  db.purge_notifications(&user_id, &body.course_id).await?;
+  if let SubscriptionTarget::Course(course_id) = &target {
+    db.purge_notifications(&user_id, course_id).await?;
+  }
 
-  Ok(Json(
-    db.delete_subscription(Subscription {
-      user_id,
-      course_id: body.course_id.clone(),
-    })
-    .await?,
-  ))
+  Ok(
+    Json(
+      db.delete_subscription(Subscription { target, user_id })
+        .await?,
+    )
+    .into_response(),
+  )
 }
@@ -0,0 +1,194 @@
+use super::*;
+
+lazy_static! {
+  pub(crate) static ref HTTP_REQUESTS: IntCounterVec =
+    register_int_counter_vec!(
+      "http_requests_total",
+      "Requests served, by route and status",
+      &["method", "route", "status"]
+    )
+    .unwrap();
+  pub(crate) static ref HTTP_REQUEST_DURATION: HistogramVec =
+    register_histogram_vec!(
+      "http_request_duration_seconds",
+      "Time taken to serve requests, by route and status",
+      &["method", "route", "status"]
+    )
+    .unwrap();
+  pub(crate) static ref RATE_LIMITED: IntCounterVec =
+    register_int_counter_vec!(
+      "rate_limited_requests_total",
+      "Requests rejected for going over a rate limit, by limiter",
+      &["limiter"]
+    )
+    .unwrap();
+  pub(crate) static ref SESSIONS: IntGauge =
+    register_int_gauge!("sessions", "Sessions that haven't expired").unwrap();
+  pub(crate) static ref SCRAPE_REQUESTS: IntCounterVec =
+    register_int_counter_vec!(
+      "scrape_requests_total",
+      "Requests made while scraping, including retries",
+      &["outcome"]
+    )
+    .unwrap();
+  pub(crate) static ref SCRAPED_PAGES: IntCounterVec =
+    register_int_counter_vec!(
+      "scraped_pages_total",
+      "Pages scraped and extracted, by kind",
+      &["kind"]
+    )
+    .unwrap();
+}
+
+/// Everything recorded by the server and the database, which register with
+/// the default registry. Metrics nothing was recorded for are left out.
+pub(crate) fn render() -> Result<String> {
+  Ok(TextEncoder::new().encode_to_string(&prometheus::gather())?)
+}
+
+/// Counts and times every request, labelled with the route it matched rather
+/// than its path, so the number of series stays bounded.
+pub(crate) async fn track(
+  matched_path: Option<MatchedPath>,
+  request: Request<Body>,
+  next: Next,
+) -> Response {
+  let method = request.method().clone();
+
+  let route = matched_path
+    .map(|matched_path| matched_path.as_str().to_string())
+    .unwrap_or_else(|| "unmatched".into());
+
+  let start = Instant::now();
+
+  let response = next.run(request).await;
+
+  let status = response.status();
+
+  let labels = [method.as_str(), &route, status.as_str()];
+
+  HTTP_REQUESTS.with_label_values(&labels).inc();
+  HTTP_REQUEST_DURATION
+    .with_label_values(&labels)
+    .observe(start.elapsed().as_secs_f64());
+
+  response
+}
+
+/// The bearer token Prometheus scrapes `/metrics` with. Metrics aren't
+/// served at all without one.
+#[derive(Clone, Default)]
+pub(crate) struct MetricsToken(Option<Arc<str>>);
+
+impl fmt::Debug for MetricsToken {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("MetricsToken")
+      .field(&self.0.as_ref().map(|_| "..."))
+      .finish()
+  }
+}
+
+impl MetricsToken {
+  pub(crate) fn new(token: &str) -> Self {
+    Self(Some(token.into()))
+  }
+
+  pub(crate) fn from_config(config: &Config) -> Self {
+    config
+      .metrics
+      .token
+      .as_deref()
+      .map(Self::new)
+      .unwrap_or_default()
+  }
+
+  /// Whether `headers` carry the token, compared through their hashes so
+  /// the time taken doesn't give it away.
+  fn authorizes(token: &str, headers: &HeaderMap) -> bool {
+    headers
+      .get(header::AUTHORIZATION)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.strip_prefix("Bearer "))
+      .is_some_and(|sent| {
+        Sha256::digest(sent.as_bytes()) == Sha256::digest(token.as_bytes())
+      })
+  }
+}
+
+impl FromRef<State> for MetricsToken {
+  fn from_ref(state: &State) -> Self {
+    state.metrics_token.clone()
+  }
+}
+
+/// Get metrics for Prometheus to scrape
+#[utoipa::path(
+  get,
+  path = "/metrics",
+  responses(
+    (status = 200, description = "Metrics in the Prometheus text format"),
+    (
+      status = 401,
+      description = "The `METRICS_TOKEN` bearer token is missing or wrong",
+    ),
+    (status = 404, description = "`METRICS_TOKEN` isn't set"),
+  ),
+)]
+pub(crate) async fn get_metrics(
+  AppState(db): AppState<Arc<Db>>,
+  AppState(MetricsToken(token)): AppState<MetricsToken>,
+  headers: HeaderMap,
+) -> Response {
+  let Some(token) = token else {
+    return StatusCode::NOT_FOUND.into_response();
+  };
+
+  if !MetricsToken::authorizes(&token, &headers) {
+    return (
+      StatusCode::UNAUTHORIZED,
+      [(header::WWW_AUTHENTICATE, "Bearer")],
+    )
+      .into_response();
+  }
+
+  match db.session_count().await {
+    Ok(count) => SESSIONS.set(count as i64),
+    Err(error) => warn!("Failed to count sessions: {error}"),
+  }
+
+  match render() {
+    Ok(metrics) => (
+      [(
+        header::CONTENT_TYPE,
+        "text/plain; version=0.0.4; charset=utf-8",
+      )],
+      metrics,
+    )
+      .into_response(),
+    Err(error) => error.into_response(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn renders_recorded_metrics() {
+    SCRAPED_PAGES.with_label_values(&["render-test"]).inc_by(2);
+
+    db::SEEDED_ITEMS
+      .with_label_values(&["render-test", "success"])
+      .inc();
+
+    let rendered = render().unwrap();
+
+    for line in [
+      "# TYPE scraped_pages_total counter",
+      "scraped_pages_total{kind=\"render-test\"} 2",
+      "seeded_items_total{kind=\"render-test\",outcome=\"success\"} 1",
+    ] {
+      assert!(rendered.lines().any(|rendered| rendered == line), "{line}");
+    }
+  }
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct User {
   id: String,
   mail: String,
@@ -15,7 +15,6 @@ impl User {
     &self.mail
   }
 
-  #[cfg(test)]
   pub(crate) fn new(id: &str, mail: &str) -> Self {
     User {
       id: String::from(id),
@@ -24,27 +23,148 @@ impl User {
   }
 }
 
-#[derive(Serialize, Deserialize)]
-struct UserResponse {
-  user: Option<User>,
+/// The signed in user, or `None` for a request without credentials. Unlike
+/// `Option<User>`, credentials that are turned away, e.g. a token over its
+/// rate limit, reject the request instead of serving it anonymously.
+#[derive(Debug)]
+pub(crate) struct MaybeUser(pub(crate) Option<User>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for MaybeUser
+where
+  User: FromRequestParts<S, Rejection = AuthRejection>,
+  S: Send + Sync,
+{
+  type Rejection = AuthRejection;
+
+  async fn from_request_parts(
+    parts: &mut Parts,
+    state: &S,
+  ) -> Result<Self, Self::Rejection> {
+    match User::from_request_parts(parts, state).await {
+      Ok(user) => Ok(Self(Some(user))),
+      Err(AuthRejection::Redirect) => Ok(Self(None)),
+      Err(rejection) => Err(rejection),
+    }
+  }
 }
 
-pub(crate) async fn get_user(user: Option<User>) -> impl IntoResponse {
-  Json(UserResponse { user })
+/// Get the signed in user
+#[utoipa::path(
+  get,
+  path = "/api/v1/user",
+  responses(
+    (
+      status = 200,
+      description = "The user, or null when signed out",
+      body = UserResponse,
+    ),
+  ),
+  security(("session" = []), ()),
+)]
+pub(crate) async fn get_user(
+  MaybeUser(user): MaybeUser,
+  AppState(pseudonyms): AppState<Pseudonyms>,
+) -> impl IntoResponse {
+  Json(UserResponse {
+    user: user.map(|user| CurrentUser {
+      pseudonym: pseudonyms.pseudonym(&user.id),
+      id: user.id,
+      mail: user.mail,
+    }),
+  })
+}
+
+/// Export the user's data
+#[utoipa::path(
+  get,
+  path = "/api/v1/user/export",
+  responses(
+    (
+      status = 200,
+      description = "Everything stored about the user",
+      body = UserExport,
+    ),
+  ),
+  security(("session" = [])),
+)]
+pub(crate) async fn export_user(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+  AppState(pseudonyms): AppState<Pseudonyms>,
+) -> Result<impl IntoResponse> {
+  let user_id = user.id();
+
+  info!("Exporting data for user {}", user_id);
+
+  let mut headers = HeaderMap::new();
+
+  headers.insert(
+    header::CONTENT_DISPOSITION,
+    "attachment; filename=\"mcgill-courses-export.json\"".parse()?,
+  );
+
+  let export = db.export_user(&user_id).await?;
+
+  Ok((
+    headers,
+    Json(UserExport {
+      interactions: pseudonyms.interactions(export.interactions),
+      notifications: pseudonyms.notifications(export.notifications),
+      ..export
+    }),
+  ))
+}
+
+/// Delete the user's account and data
+#[utoipa::path(
+  delete,
+  path = "/api/v1/user",
+  responses((status = 200, description = "Success")),
+  security(("session" = [])),
+)]
+pub(crate) async fn delete_user(
+  user: User,
+  AppState(db): AppState<Arc<Db>>,
+) -> Result<impl IntoResponse> {
+  let user_id = user.id();
+
+  info!("Deleting account for user {}", user_id);
+
+  db.delete_user(&user_id).await?;
+
+  let mut headers = HeaderMap::new();
+
+  headers.insert(SET_COOKIE, sessions::clear_session_cookie()?);
+
+  Ok(headers)
 }
 
 #[async_trait]
 impl<S> FromRequestParts<S> for User
 where
+  Arc<Db>: FromRef<S>,
   MongodbSessionStore: FromRef<S>,
+  RateLimiter: FromRef<S>,
+  RateLimits: FromRef<S>,
+  SessionPolicy: FromRef<S>,
   S: Send + Sync,
 {
-  type Rejection = AuthRedirect;
+  type Rejection = AuthRejection;
 
   async fn from_request_parts(
     parts: &mut Parts,
     state: &S,
   ) -> Result<Self, Self::Rejection> {
+    if let Some(token) = parts
+      .headers
+      .get(header::AUTHORIZATION)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.strip_prefix("Bearer "))
+    {
+      return Self::from_token(parts, state, token.trim()).await;
+    }
+
     let session_store = MongodbSessionStore::from_ref(state);
 
     let cookies =
@@ -64,14 +184,95 @@ where
         }
       })?;
 
-    Ok(
-      session_store
-        .load_session(cookies.get(COOKIE_NAME).ok_or(AuthRedirect)?.to_owned())
-        .await
-        .unwrap()
-        .ok_or(AuthRedirect)?
-        .get::<User>("user")
-        .ok_or(AuthRedirect)?,
-    )
+    let session = session_store
+      .load_session(cookies.get(COOKIE_NAME).ok_or(AuthRedirect)?.to_owned())
+      .await
+      .unwrap()
+      .ok_or(AuthRedirect)?;
+
+    let policy = SessionPolicy::from_ref(state);
+
+    if let Err(error) = Arc::<Db>::from_ref(state)
+      .touch_session(session.id(), policy.sliding.then(|| policy.expires_at()))
+      .await
+    {
+      error!("Failed to record session activity: {}", error);
+    }
+
+    let user = session.get::<User>("user").ok_or(AuthRedirect)?;
+
+    request_context::record_user(&user.id);
+
+    Self::check_rate_limits(parts, state, &user.id)?;
+
+    Ok(user)
+  }
+}
+
+impl User {
+  async fn from_token<S>(
+    parts: &Parts,
+    state: &S,
+    token: &str,
+  ) -> Result<Self, AuthRejection>
+  where
+    Arc<Db>: FromRef<S>,
+    RateLimiter: FromRef<S>,
+    RateLimits: FromRef<S>,
+  {
+    if !token.starts_with(api_tokens::TOKEN_PREFIX) {
+      return Err(AuthRejection::InvalidToken);
+    }
+
+    let api_token = Arc::<Db>::from_ref(state)
+      .use_api_token(&api_tokens::hash(token))
+      .await
+      .map_err(|error| {
+        error!("Failed to look up api token: {}", error);
+        AuthRejection::Unavailable
+      })?
+      .ok_or(AuthRejection::InvalidToken)?;
+
+    match api_tokens::required_scope(&parts.method, parts.uri.path()) {
+      Some(scope) if api_token.scopes.contains(&scope) => {}
+      _ => return Err(AuthRejection::InsufficientScope),
+    }
+
+    RateLimiter::from_ref(state)
+      .check(&api_token.id, api_token.rate_limit)
+      .map_err(|retry_after| RateLimited::new("token", retry_after))?;
+
+    request_context::record_user(&api_token.user_id);
+
+    Self::check_rate_limits(parts, state, &api_token.user_id)?;
+
+    Ok(User::new(&api_token.user_id, &api_token.mail))
+  }
+
+  /// Counts the request against the policy of its route, if that policy is
+  /// counted per user.
+  fn check_rate_limits<S>(
+    parts: &Parts,
+    state: &S,
+    user_id: &str,
+  ) -> Result<(), RateLimited>
+  where
+    RateLimiter: FromRef<S>,
+    RateLimits: FromRef<S>,
+  {
+    let policy = parts
+      .extensions
+      .get::<MatchedPath>()
+      .and_then(|path| Policy::for_route(&parts.method, path.as_str()))
+      .filter(|policy| policy.key() == PolicyKey::User);
+
+    match policy {
+      Some(policy) => RateLimits::from_ref(state).check(
+        &RateLimiter::from_ref(state),
+        policy,
+        user_id,
+      ),
+      None => Ok(()),
+    }
   }
 }
@@ -32,7 +32,13 @@ impl<'a> VsbClient<'a> {
           .retry(self.retries)?
           .text()?;
 
-        Ok(extractor::extract_course_schedules(res)?)
+        let schedules = extractor::extract_course_schedules(res)?;
+
+        telemetry::SCRAPED_PAGES
+          .with_label_values(&["schedule"])
+          .inc();
+
+        Ok(schedules)
       })
       .collect::<Result<Vec<_>>>()?
       .into_iter()